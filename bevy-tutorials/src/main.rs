@@ -2,10 +2,14 @@ use bevy::prelude::*;
 use ops::FloatPow;
 use std::time::Duration;
 
+use tutorial_common::args::Args;
+use tutorial_common::locale;
+
 fn main() {
     use bevy::diagnostic::{FrameTimeDiagnosticsPlugin, LogDiagnosticsPlugin};
     use bevy::window::{EnabledButtons, PresentMode};
 
+    let args = Args::from_env(&["--lang"]);
     App::new()
         .add_plugins((
             DefaultPlugins
@@ -32,6 +36,7 @@ fn main() {
             // FrameTimeDiagnosticsPlugin::default(),
         ))
         .add_plugins(MyPlugin)
+        .insert_resource(MyHud::new(locale::Language::from_args(&args)))
         .run();
 }
 
@@ -62,43 +67,74 @@ impl Plugin for MyPlugin {
 #[derive(Component)]
 struct ChainsDisplay;
 
-fn setup_system(mut cmd: Commands, asset_server: Res<AssetServer>) {
+// The HUD strings of the language chosen at startup, and the fonts to draw them with.
+#[derive(Resource)]
+struct MyHud {
+    strings: &'static locale::HudStrings,
+    fonts: locale::FontFallback,
+    handles: Vec<Handle<Font>>,
+}
+
+impl MyHud {
+    fn new(language: locale::Language) -> MyHud {
+        use std::{env, path::PathBuf};
+
+        // the asset server loads the fonts to draw with, and this reads them to find the glyphs
+        let resource_dir = if let Ok(manifest_dir) = env::var("CARGO_MANIFEST_DIR") {
+            let mut path = PathBuf::from(manifest_dir);
+            path.push("../assets");
+            path
+        } else {
+            PathBuf::from("../assets")
+        };
+        let fonts = locale::FontFallback::load(&resource_dir);
+        MyHud {
+            strings: language.strings(),
+            fonts,
+            handles: vec![],
+        }
+    }
+
+    // One span for every run of the text, each with the font that has its glyphs.
+    fn spawn_spans(&self, parent: &mut ChildBuilder, text: &str) {
+        for (font, run) in self.fonts.runs(text) {
+            parent.spawn((
+                TextSpan::new(run),
+                TextFont {
+                    font: self.handles.get(font).cloned().unwrap_or_default(),
+                    font_size: 16.,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+            ));
+        }
+    }
+}
+
+fn setup_system(mut cmd: Commands, asset_server: Res<AssetServer>, mut hud: ResMut<MyHud>) {
     cmd.spawn(Camera2d);
 
-    let font = asset_server.load("LiberationMono-Regular.ttf");
-    let text_font = TextFont {
-        font: font.clone(),
-        font_size: 16.,
-        ..default()
-    };
+    hud.handles = (hud.fonts.fonts().iter())
+        .map(|font| asset_server.load(font.name))
+        .collect();
 
     use bevy::sprite::Anchor;
 
+    let lines = [
+        hud.strings.spawn_explosion,
+        hud.strings.spawn_bomb,
+        hud.strings.pause_resume,
+    ];
+    for (line, y) in lines.into_iter().zip([160., 140., 120.]) {
+        cmd.spawn((
+            Text2d::default(),
+            Transform::from_translation(Vec3::new(0., y, 0.)),
+            Anchor::TopCenter,
+        ))
+        .with_children(|parent| hud.spawn_spans(parent, line));
+    }
     cmd.spawn((
-        Text2d::new("Mouse L: Spawn Explosion"),
-        text_font.clone(),
-        TextColor(Color::WHITE),
-        Transform::from_translation(Vec3::new(0., 160., 0.)),
-        Anchor::TopCenter,
-    ));
-    cmd.spawn((
-        Text2d::new("Mouse M: Spawn Bomb"),
-        text_font.clone(),
-        TextColor(Color::WHITE),
-        Transform::from_translation(Vec3::new(0., 140., 0.)),
-        Anchor::TopCenter,
-    ));
-    cmd.spawn((
-        Text2d::new("Mouse R: Pause/Resume"),
-        text_font.clone(),
-        TextColor(Color::WHITE),
-        Transform::from_translation(Vec3::new(0., 120., 0.)),
-        Anchor::TopCenter,
-    ));
-    cmd.spawn((
-        Text2d::new(""),
-        text_font.clone(),
-        TextColor(Color::WHITE),
+        Text2d::default(),
         Transform::from_translation(Vec3::new(0., -140., 0.)),
         Anchor::TopCenter,
         ChainsDisplay,
@@ -336,13 +372,23 @@ fn get_collision(c1: (&Vec2, f32), c2: (&Vec2, f32)) -> bool {
 }
 
 fn my_chains_display_system(
-    mut text2d: Single<&mut Text2d, With<ChainsDisplay>>,
+    mut cmd: Commands,
+    display: Single<Entity, With<ChainsDisplay>>,
     chains: Res<MyChains>,
+    hud: Res<MyHud>,
+    mut shown: Local<Option<u32>>,
 ) {
+    // the spans are made again only when the number changes
+    if *shown == Some(chains.0) {
+        return;
+    }
+    *shown = Some(chains.0);
     let nbchains = if chains.0 > 0 {
-        format!("{} Chain(s)", chains.0)
+        locale::fill(hud.strings.chains, chains.0)
     } else {
         "".into()
     };
-    text2d.0 = nbchains;
+    cmd.entity(*display)
+        .despawn_descendants()
+        .with_children(|parent| hud.spawn_spans(parent, &nbchains));
 }
@@ -0,0 +1,393 @@
+use lyon::math::{point, Point, Vector};
+use lyon::path::Path;
+
+// A path that keeps its segments as they were written, so that every point can be dragged,
+// and that can be written back out as SVG path data.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EditPath {
+    pub sub_paths: Vec<SubPath>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SubPath {
+    pub start: Point,
+    pub segments: Vec<Segment>,
+    pub closed: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Segment {
+    Line {
+        to: Point,
+    },
+    Quadratic {
+        ctrl: Point,
+        to: Point,
+    },
+    Cubic {
+        ctrl1: Point,
+        ctrl2: Point,
+        to: Point,
+    },
+    // the SVG elliptical arc, with x_rotation in degrees
+    Arc {
+        radii: Vector,
+        x_rotation: f32,
+        large_arc: bool,
+        sweep: bool,
+        to: Point,
+    },
+}
+
+impl Segment {
+    pub fn to(&self) -> Point {
+        match self {
+            Segment::Line { to }
+            | Segment::Quadratic { to, .. }
+            | Segment::Cubic { to, .. }
+            | Segment::Arc { to, .. } => *to,
+        }
+    }
+
+    // The control points first, then the end point.
+    fn points(&self) -> Vec<Point> {
+        match *self {
+            Segment::Line { to } | Segment::Arc { to, .. } => vec![to],
+            Segment::Quadratic { ctrl, to } => vec![ctrl, to],
+            Segment::Cubic { ctrl1, ctrl2, to } => vec![ctrl1, ctrl2, to],
+        }
+    }
+
+    fn points_mut(&mut self) -> Vec<&mut Point> {
+        match self {
+            Segment::Line { to } | Segment::Arc { to, .. } => vec![to],
+            Segment::Quadratic { ctrl, to } => vec![ctrl, to],
+            Segment::Cubic { ctrl1, ctrl2, to } => vec![ctrl1, ctrl2, to],
+        }
+    }
+}
+
+// Where a point of the path is, as returned by EditPath::points.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PathPoint {
+    pub position: Point,
+    // false for Bézier control points
+    pub on_curve: bool,
+    pub sub_path: usize,
+    // None for the start of the sub-path
+    pub segment: Option<usize>,
+}
+
+impl EditPath {
+    // Reads SVG path data with the lyon parser.
+    // The parser turns arcs into Béziers, so imported arcs are no longer Segment::Arc.
+    pub fn parse(source: &str) -> Result<Self, String> {
+        let path = tutorial_common::path_file::parse(source)?;
+        Ok(Self::from_path(&path))
+    }
+
+    pub fn from_path(path: &Path) -> Self {
+        use lyon::path::PathEvent;
+
+        let mut sub_paths = vec![];
+        for event in path.iter() {
+            match event {
+                PathEvent::Begin { at } => sub_paths.push(SubPath {
+                    start: at,
+                    segments: vec![],
+                    closed: false,
+                }),
+                PathEvent::Line { to, .. } => Self::push(&mut sub_paths, Segment::Line { to }),
+                PathEvent::Quadratic { ctrl, to, .. } => {
+                    Self::push(&mut sub_paths, Segment::Quadratic { ctrl, to })
+                }
+                PathEvent::Cubic {
+                    ctrl1, ctrl2, to, ..
+                } => Self::push(&mut sub_paths, Segment::Cubic { ctrl1, ctrl2, to }),
+                PathEvent::End { close, .. } => {
+                    if let Some(sub_path) = sub_paths.last_mut() {
+                        sub_path.closed = close;
+                    }
+                }
+            }
+        }
+        Self { sub_paths }
+    }
+
+    fn push(sub_paths: &mut [SubPath], segment: Segment) {
+        if let Some(sub_path) = sub_paths.last_mut() {
+            sub_path.segments.push(segment);
+        }
+    }
+
+    pub fn to_path(&self) -> Path {
+        use lyon::math::Angle;
+        use lyon::path::builder::SvgPathBuilder;
+        use lyon::path::ArcFlags;
+
+        let mut builder = Path::builder().with_svg();
+        for sub_path in &self.sub_paths {
+            builder.move_to(sub_path.start);
+            for segment in &sub_path.segments {
+                match *segment {
+                    Segment::Line { to } => {
+                        builder.line_to(to);
+                    }
+                    Segment::Quadratic { ctrl, to } => {
+                        builder.quadratic_bezier_to(ctrl, to);
+                    }
+                    Segment::Cubic { ctrl1, ctrl2, to } => {
+                        builder.cubic_bezier_to(ctrl1, ctrl2, to);
+                    }
+                    Segment::Arc {
+                        radii,
+                        x_rotation,
+                        large_arc,
+                        sweep,
+                        to,
+                    } => {
+                        let flags = ArcFlags { large_arc, sweep };
+                        builder.arc_to(radii, Angle::degrees(x_rotation), flags, to);
+                    }
+                }
+            }
+            if sub_path.closed {
+                builder.close();
+            }
+        }
+        builder.build()
+    }
+
+    // Absolute commands only, so the output reads the same as the points on screen.
+    pub fn to_svg(&self) -> String {
+        let mut commands = vec![];
+        for sub_path in &self.sub_paths {
+            let Point { x, y, .. } = sub_path.start;
+            commands.push(format!("M{x} {y}"));
+            for segment in &sub_path.segments {
+                commands.push(match *segment {
+                    Segment::Line { to } => format!("L{} {}", to.x, to.y),
+                    Segment::Quadratic { ctrl, to } => {
+                        format!("Q{} {} {} {}", ctrl.x, ctrl.y, to.x, to.y)
+                    }
+                    Segment::Cubic { ctrl1, ctrl2, to } => format!(
+                        "C{} {} {} {} {} {}",
+                        ctrl1.x, ctrl1.y, ctrl2.x, ctrl2.y, to.x, to.y
+                    ),
+                    Segment::Arc {
+                        radii,
+                        x_rotation,
+                        large_arc,
+                        sweep,
+                        to,
+                    } => format!(
+                        "A{} {} {} {} {} {} {}",
+                        radii.x, radii.y, x_rotation, large_arc as u8, sweep as u8, to.x, to.y
+                    ),
+                });
+            }
+            if sub_path.closed {
+                commands.push("Z".to_owned());
+            }
+        }
+        commands.join(" ")
+    }
+
+    // Every point in the order of points_mut.
+    pub fn points(&self) -> Vec<PathPoint> {
+        let mut points = vec![];
+        for (i, sub_path) in self.sub_paths.iter().enumerate() {
+            points.push(PathPoint {
+                position: sub_path.start,
+                on_curve: true,
+                sub_path: i,
+                segment: None,
+            });
+            for (j, segment) in sub_path.segments.iter().enumerate() {
+                let segment_points = segment.points();
+                let last = segment_points.len() - 1;
+                for (k, position) in segment_points.into_iter().enumerate() {
+                    points.push(PathPoint {
+                        position,
+                        on_curve: k == last,
+                        sub_path: i,
+                        segment: Some(j),
+                    });
+                }
+            }
+        }
+        points
+    }
+
+    pub fn points_mut(&mut self) -> Vec<&mut Point> {
+        let mut points = vec![];
+        for sub_path in &mut self.sub_paths {
+            points.push(&mut sub_path.start);
+            for segment in &mut sub_path.segments {
+                points.extend(segment.points_mut());
+            }
+        }
+        points
+    }
+
+    // The lines from the Bézier control points to the ends they belong to.
+    pub fn handles(&self) -> Vec<[Point; 2]> {
+        let mut handles = vec![];
+        for sub_path in &self.sub_paths {
+            let mut from = sub_path.start;
+            for segment in &sub_path.segments {
+                match *segment {
+                    Segment::Quadratic { ctrl, to } => {
+                        handles.push([from, ctrl]);
+                        handles.push([ctrl, to]);
+                    }
+                    Segment::Cubic { ctrl1, ctrl2, to } => {
+                        handles.push([from, ctrl1]);
+                        handles.push([ctrl2, to]);
+                    }
+                    Segment::Line { .. } | Segment::Arc { .. } => {}
+                }
+                from = segment.to();
+            }
+        }
+        handles
+    }
+
+    // Appends a segment to the last sub-path, starting a new one at the origin if there is none.
+    pub fn add(&mut self, kind: SegmentKind) {
+        if self.sub_paths.is_empty() {
+            self.sub_paths.push(SubPath {
+                start: point(0.0, 0.0),
+                segments: vec![],
+                closed: false,
+            });
+        }
+        let sub_path = self.sub_paths.last_mut().unwrap();
+        let from = sub_path
+            .segments
+            .last()
+            .map(Segment::to)
+            .unwrap_or(sub_path.start);
+        let step = Vector::new(MY_NEW_SEGMENT_LENGTH, 0.0);
+        let to = from + step;
+        let segment = match kind {
+            SegmentKind::Line => Segment::Line { to },
+            SegmentKind::Quadratic => Segment::Quadratic {
+                ctrl: from + step / 2.0 - Vector::new(0.0, MY_NEW_SEGMENT_LENGTH / 2.0),
+                to,
+            },
+            SegmentKind::Cubic => Segment::Cubic {
+                ctrl1: from + step / 3.0 - Vector::new(0.0, MY_NEW_SEGMENT_LENGTH / 2.0),
+                ctrl2: from + step * 2.0 / 3.0 + Vector::new(0.0, MY_NEW_SEGMENT_LENGTH / 2.0),
+                to,
+            },
+            SegmentKind::Arc => Segment::Arc {
+                radii: Vector::splat(MY_NEW_SEGMENT_LENGTH / 2.0),
+                x_rotation: 0.0,
+                large_arc: false,
+                sweep: true,
+                to,
+            },
+        };
+        sub_path.segments.push(segment);
+    }
+
+    pub fn segment_mut(&mut self, sub_path: usize, segment: usize) -> Option<&mut Segment> {
+        self.sub_paths.get_mut(sub_path)?.segments.get_mut(segment)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SegmentKind {
+    Line,
+    Quadratic,
+    Cubic,
+    Arc,
+}
+
+impl Default for EditPath {
+    // One of each kind of segment.
+    fn default() -> Self {
+        EditPath::parse(MY_DEFAULT_PATH)
+            .map(|mut path| {
+                path.sub_paths[0].segments.push(Segment::Arc {
+                    radii: Vector::new(80.0, 50.0),
+                    x_rotation: 0.0,
+                    large_arc: false,
+                    sweep: true,
+                    to: point(40.0, 200.0),
+                });
+                path.sub_paths[0].closed = true;
+                path
+            })
+            .unwrap()
+    }
+}
+
+const MY_DEFAULT_PATH: &str = "M40 120 L120 40 Q200 0 240 80 C280 160 360 40 400 120";
+const MY_NEW_SEGMENT_LENGTH: f32 = 60.0;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip(source: &str) -> EditPath {
+        let path = EditPath::parse(source).unwrap();
+        let svg = path.to_svg();
+        assert_eq!(EditPath::parse(&svg).unwrap(), path, "{}", svg);
+        path
+    }
+
+    #[test]
+    fn segments_survive_a_round_trip() {
+        let path = round_trip("M0 0 L10 0 Q15 5 10 10 C5 15 0 15 -2.5 10 Z M20 20 L30.125 20");
+        assert_eq!(path.sub_paths.len(), 2);
+        assert_eq!(path.sub_paths[0].segments.len(), 3);
+        assert!(path.sub_paths[0].closed);
+        assert!(!path.sub_paths[1].closed);
+        assert_eq!(
+            path.sub_paths[1].segments,
+            [Segment::Line {
+                to: point(30.125, 20.0)
+            }]
+        );
+    }
+
+    #[test]
+    fn relative_and_shorthand_commands_become_absolute() {
+        let path = round_trip("m10 10 h5 v5 l-5 0 z");
+        let svg = path.to_svg();
+        assert_eq!(svg, "M10 10 L15 10 L15 15 L10 15 Z");
+    }
+
+    #[test]
+    fn the_rust_logo_survives_a_round_trip() {
+        let file = concat!(env!("CARGO_MANIFEST_DIR"), "/../assets/rust-logo.path");
+        let source = std::fs::read_to_string(file).unwrap();
+        let path = round_trip(&source);
+        assert!(path.sub_paths.len() > 1);
+        assert_eq!(
+            EditPath::from_path(&path.to_path()),
+            path,
+            "to_path changed the segments"
+        );
+    }
+
+    #[test]
+    fn the_default_path_survives_once_its_arc_is_flattened() {
+        // The arc comes back as Béziers, which then round trip exactly.
+        let path = EditPath::parse(&EditPath::default().to_svg()).unwrap();
+        assert!(path.sub_paths[0].segments.len() > 4);
+        round_trip(&path.to_svg());
+    }
+
+    #[test]
+    fn broken_path_data_is_an_error() {
+        assert!(EditPath::parse("L 1 1")
+            .unwrap_err()
+            .starts_with("byte 0: "));
+        assert!(EditPath::parse("M 0 0 L 1").is_err());
+        assert!(EditPath::parse("M 0 0 X").is_err());
+        assert_eq!(EditPath::parse("").unwrap(), EditPath { sub_paths: vec![] });
+    }
+}
@@ -1,55 +1,250 @@
+mod edit_path;
+mod obj;
+mod path_editor;
+mod preview;
+mod shader_playground;
+
 use eframe::egui;
+use std::path::{Path, PathBuf};
+use tutorial_common::args::Args;
+use tutorial_common::locale;
 
 fn main() -> Result<(), eframe::Error> {
     use egui::ViewportBuilder;
 
+    let args = Args::from_env(&["--lang"]);
+    let language = locale::Language::from_args(&args);
+
+    let asset_dir = if let Ok(manifest_dir) = std::env::var("CARGO_MANIFEST_DIR") {
+        let mut path = PathBuf::from(manifest_dir);
+        path.push("../assets");
+        path
+    } else {
+        PathBuf::from("../assets")
+    };
+
     let options = eframe::NativeOptions {
-        viewport: ViewportBuilder::default().with_inner_size([480.0, 320.0]),
+        viewport: ViewportBuilder::default().with_inner_size([800.0, 600.0]),
         ..Default::default()
     };
     eframe::run_native(
         "egui tutorial",
         options,
-        Box::new(|cc| {
+        Box::new(move |cc| {
             egui_extras::install_image_loaders(&cc.egui_ctx);
-            Box::<MyApp>::default()
+            Box::new(MyApp::new(&cc.egui_ctx, asset_dir, language))
         }),
     )
 }
 
-struct MyApp {
+// A file or a directory under the asset directory.
+struct Entry {
+    path: PathBuf,
     name: String,
-    age: u32,
+    // None for files
+    children: Option<Vec<Entry>>,
+}
+
+impl Entry {
+    // Directories first, then files, each sorted by name.
+    fn read_dir(dir: &Path) -> std::io::Result<Vec<Entry>> {
+        let mut entries = vec![];
+        for dir_entry in std::fs::read_dir(dir)? {
+            let path = dir_entry?.path();
+            let name = path.file_name().unwrap().to_string_lossy().into_owned();
+            let children = if path.is_dir() {
+                Some(Entry::read_dir(&path)?)
+            } else {
+                None
+            };
+            entries.push(Entry {
+                path,
+                name,
+                children,
+            });
+        }
+        entries
+            .sort_by(|a, b| (a.children.is_none(), &a.name).cmp(&(b.children.is_none(), &b.name)));
+        Ok(entries)
+    }
+
+    fn visit<F: FnMut(&Entry)>(entries: &[Entry], f: &mut F) {
+        for entry in entries {
+            f(entry);
+            if let Some(children) = &entry.children {
+                Entry::visit(children, f);
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum MyTab {
+    Assets,
+    PathEditor,
+    ShaderPlayground,
+}
+
+struct MyApp {
+    strings: &'static locale::HudStrings,
+    tab: MyTab,
+    asset_dir: PathBuf,
+    tree: Result<Vec<Entry>, String>,
+    selected: Option<PathBuf>,
+    preview: Option<preview::Preview>,
+    path_editor: path_editor::PathEditor,
+    shader_playground: shader_playground::ShaderPlayground,
 }
 
-impl Default for MyApp {
-    fn default() -> Self {
+impl MyApp {
+    fn new(ctx: &egui::Context, asset_dir: PathBuf, language: locale::Language) -> Self {
+        let tree =
+            Entry::read_dir(&asset_dir).map_err(|err| format!("{}: {}", asset_dir.display(), err));
+        let fallback = locale::FontFallback::load(&asset_dir);
+        if let Ok(tree) = &tree {
+            MyApp::load_fonts(ctx, tree, &fallback);
+        }
         Self {
-            name: "Alice".to_owned(),
-            age: 20,
+            strings: language.strings(),
+            tab: MyTab::Assets,
+            path_editor: path_editor::PathEditor::new(asset_dir.clone()),
+            shader_playground: shader_playground::ShaderPlayground::new(asset_dir.clone()),
+            asset_dir,
+            tree,
+            selected: None,
+            preview: None,
         }
     }
+
+    // Every font file in the tree becomes a family of its own, named after the file.
+    // The fallback fonts after the first are added to the end of every family,
+    // for the characters that the fonts before them do not have.
+    fn load_fonts(ctx: &egui::Context, tree: &[Entry], fallback: &locale::FontFallback) {
+        use egui::{FontData, FontDefinitions, FontFamily};
+
+        let mut fonts = FontDefinitions::default();
+        Entry::visit(tree, &mut |entry| {
+            let is_font = entry.path.extension().is_some_and(|extension| {
+                extension.eq_ignore_ascii_case("ttf") || extension.eq_ignore_ascii_case("otf")
+            });
+            let Some(family) = preview::font_family(&entry.path).filter(|_| is_font) else {
+                return;
+            };
+            match std::fs::read(&entry.path) {
+                Ok(bytes) => {
+                    fonts
+                        .font_data
+                        .insert(family.clone(), FontData::from_owned(bytes));
+                    fonts
+                        .families
+                        .insert(FontFamily::Name(family.clone().into()), vec![family]);
+                }
+                Err(err) => eprintln!("{}: {}", entry.path.display(), err),
+            }
+        });
+        for font in fallback.fonts().iter().skip(1) {
+            let name = font.name.to_owned();
+            fonts
+                .font_data
+                .insert(name.clone(), FontData::from_owned(font.data.clone()));
+            for family in fonts.families.values_mut() {
+                if !family.contains(&name) {
+                    family.push(name.clone());
+                }
+            }
+        }
+        ctx.set_fonts(fonts);
+    }
+
+    fn show_assets(&mut self, ctx: &egui::Context) {
+        use egui::{CentralPanel, ScrollArea, SidePanel};
+
+        SidePanel::left("tree").show(ctx, |ui| {
+            ui.heading(
+                self.asset_dir
+                    .file_name()
+                    .unwrap_or_default()
+                    .to_string_lossy(),
+            );
+            ScrollArea::vertical().show(ui, |ui| {
+                let clicked = match &self.tree {
+                    Ok(entries) => show_tree(ui, entries, self.selected.as_ref()),
+                    Err(err) => {
+                        ui.colored_label(egui::Color32::RED, err);
+                        None
+                    }
+                };
+                if let Some(path) = clicked {
+                    self.preview = Some(preview::Preview::open(ctx, &path));
+                    self.selected = Some(path);
+                }
+            });
+        });
+
+        CentralPanel::default().show(ctx, |ui| match (&self.selected, &mut self.preview) {
+            (Some(path), Some(preview)) => {
+                ui.heading(path.file_name().unwrap_or_default().to_string_lossy());
+                preview.show(ui);
+            }
+            _ => {
+                ui.label(self.strings.select_file);
+            }
+        });
+    }
+}
+
+// Returns the file that was clicked, if any.
+fn show_tree(ui: &mut egui::Ui, entries: &[Entry], selected: Option<&PathBuf>) -> Option<PathBuf> {
+    let mut clicked = None;
+    for entry in entries {
+        match &entry.children {
+            Some(children) => {
+                egui::CollapsingHeader::new(&entry.name)
+                    .id_source(&entry.path)
+                    .default_open(true)
+                    .show(ui, |ui| {
+                        if let Some(path) = show_tree(ui, children, selected) {
+                            clicked = Some(path);
+                        }
+                    });
+            }
+            None => {
+                let checked = selected == Some(&entry.path);
+                if ui.selectable_label(checked, &entry.name).clicked() {
+                    clicked = Some(entry.path.clone());
+                }
+            }
+        }
+    }
+    clicked
 }
 
 use egui::Context;
 
 impl eframe::App for MyApp {
-    fn update(&mut self, ctx: &Context, _frame: &mut eframe::Frame) {
-        use egui::{CentralPanel, Slider};
+    fn update(&mut self, ctx: &Context, frame: &mut eframe::Frame) {
+        use egui::TopBottomPanel;
 
-        CentralPanel::default().show(ctx, |ui| {
-            ui.heading("My egui Application");
+        TopBottomPanel::top("tabs").show(ctx, |ui| {
             ui.horizontal(|ui| {
-                let name_label = ui.label("Your name: ");
-                ui.text_edit_singleline(&mut self.name)
-                    .labelled_by(name_label.id);
+                let strings = self.strings;
+                ui.selectable_value(&mut self.tab, MyTab::Assets, strings.assets);
+                ui.selectable_value(&mut self.tab, MyTab::PathEditor, strings.path_editor);
+                ui.selectable_value(
+                    &mut self.tab,
+                    MyTab::ShaderPlayground,
+                    strings.shader_playground,
+                );
             });
-            ui.add(Slider::new(&mut self.age, 0..=120).text("age"));
-            if ui.button("Click each year").clicked() {
-                self.age += 1;
-            }
-            ui.label(format!("Hello '{}', age {}", self.name, self.age));
-            ui.image(egui::include_image!("../../assets/ferris.png"));
         });
+        match self.tab {
+            MyTab::Assets => self.show_assets(ctx),
+            MyTab::PathEditor => self.path_editor.show(ctx),
+            MyTab::ShaderPlayground => self.shader_playground.show(ctx, frame),
+        }
+    }
+
+    fn on_exit(&mut self, gl: Option<&eframe::glow::Context>) {
+        self.shader_playground.destroy(gl);
     }
 }
@@ -0,0 +1,153 @@
+use std::collections::BTreeSet;
+
+// The edges of a Wavefront OBJ model, which is all a wireframe needs.
+// Texture coordinates, normals and materials are skipped.
+pub struct Wireframe {
+    pub vertices: Vec<[f32; 3]>,
+    pub edges: Vec<[usize; 2]>,
+    pub faces: usize,
+}
+
+impl Wireframe {
+    pub fn parse(source: &str) -> Result<Self, String> {
+        let mut vertices = vec![];
+        let mut edges = BTreeSet::new();
+        let mut faces = 0;
+        for (line_number, line) in source.lines().enumerate() {
+            let error =
+                |message: &str| format!("line {}: {}: {:?}", line_number + 1, message, line);
+            let mut words = line.split_whitespace();
+            match words.next() {
+                Some("v") => {
+                    let mut position = [0.0; 3];
+                    for value in &mut position {
+                        *value = words
+                            .next()
+                            .and_then(|word| word.parse().ok())
+                            .ok_or_else(|| error("bad vertex"))?;
+                    }
+                    vertices.push(position);
+                }
+                Some("f") => {
+                    let indices = words
+                        .map(|word| face_index(word, vertices.len()))
+                        .collect::<Option<Vec<_>>>()
+                        .ok_or_else(|| error("bad face"))?;
+                    if indices.len() < 3 {
+                        return Err(error("a face needs three vertices"));
+                    }
+                    for (i, &a) in indices.iter().enumerate() {
+                        let b = indices[(i + 1) % indices.len()];
+                        // shared edges are drawn once
+                        edges.insert([a.min(b), a.max(b)]);
+                    }
+                    faces += 1;
+                }
+                _ => {}
+            }
+        }
+        Ok(Self {
+            vertices,
+            edges: edges.into_iter().collect(),
+            faces,
+        })
+    }
+
+    // The center and the half size of the bounding box, to fit the model in the view.
+    pub fn bounds(&self) -> ([f32; 3], f32) {
+        let mut min = [f32::INFINITY; 3];
+        let mut max = [f32::NEG_INFINITY; 3];
+        for vertex in &self.vertices {
+            for axis in 0..3 {
+                min[axis] = min[axis].min(vertex[axis]);
+                max[axis] = max[axis].max(vertex[axis]);
+            }
+        }
+        if self.vertices.is_empty() {
+            return ([0.0; 3], 1.0);
+        }
+        let center = [0, 1, 2].map(|axis| (min[axis] + max[axis]) / 2.0);
+        let extent = (0..3)
+            .map(|axis| (max[axis] - min[axis]) / 2.0)
+            .fold(0.0, f32::max);
+        (center, extent.max(f32::EPSILON))
+    }
+}
+
+// "7", "7/1" or "7/1/1" with 1-based or negative indices, to a 0-based index.
+fn face_index(word: &str, vertex_count: usize) -> Option<usize> {
+    let index: i64 = word.split('/').next()?.parse().ok()?;
+    let index = if index < 0 {
+        vertex_count as i64 + index
+    } else {
+        index - 1
+    };
+    (0..vertex_count as i64)
+        .contains(&index)
+        .then_some(index as usize)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MY_SQUARE: &str = "v 0 0 0\nv 1 0 0\nv 1 1 0\nv 0 1 0\n";
+
+    #[test]
+    fn reads_the_cube() {
+        let file = concat!(env!("CARGO_MANIFEST_DIR"), "/../assets/cube.obj");
+        let wireframe = Wireframe::parse(&std::fs::read_to_string(file).unwrap()).unwrap();
+        assert_eq!(wireframe.vertices.len(), 216);
+        assert_eq!(wireframe.faces, 218);
+        // a closed surface without holes, so V - E + F = 2
+        assert_eq!(wireframe.edges.len(), 216 + 218 - 2);
+        assert_eq!(wireframe.bounds(), ([0.0; 3], 1.0));
+    }
+
+    #[test]
+    fn shared_edges_are_kept_once() {
+        let source = format!("{}f 1 2 3\nf 1 3 4\n", MY_SQUARE);
+        let wireframe = Wireframe::parse(&source).unwrap();
+        assert_eq!(wireframe.faces, 2);
+        assert_eq!(wireframe.edges, [[0, 1], [0, 2], [0, 3], [1, 2], [2, 3]]);
+    }
+
+    #[test]
+    fn negative_indices_count_back_from_the_last_vertex() {
+        let relative = format!("{}f -4 -3 -2 -1\n", MY_SQUARE);
+        let absolute = format!("{}f 1 2 3 4\n", MY_SQUARE);
+        let relative = Wireframe::parse(&relative).unwrap();
+        assert_eq!(relative.edges, Wireframe::parse(&absolute).unwrap().edges);
+
+        // only the vertices read so far count
+        let source = "v 0 0 0\nv 1 0 0\nv 1 1 0\nf -3 -2 -1\nv 5 5 5\nf -1 -2 -3\n";
+        let wireframe = Wireframe::parse(source).unwrap();
+        assert_eq!(wireframe.edges, [[0, 1], [0, 2], [1, 2], [1, 3], [2, 3]]);
+    }
+
+    #[test]
+    fn texture_and_normal_indices_are_skipped() {
+        let plain = Wireframe::parse(&format!("{}f 1 2 3 4\n", MY_SQUARE)).unwrap();
+        for face in [
+            "f 1/1 2/2 3/3 4/4",
+            "f 1/1/1 2/2/1 3/3/1 4/4/1",
+            "f 1//1 2//1 3//1 4//1",
+        ] {
+            let wireframe = Wireframe::parse(&format!("{}{}\n", MY_SQUARE, face)).unwrap();
+            assert_eq!(wireframe.edges, plain.edges, "{}", face);
+        }
+    }
+
+    #[test]
+    fn bad_faces_are_errors() {
+        for face in [
+            "f 1 2 5", "f 0 1 2", "f -5 1 2", "f 1 2 x", "f /1 2 3", "f 1 2",
+        ] {
+            let source = format!("{}{}\n", MY_SQUARE, face);
+            let err = Wireframe::parse(&source).err().unwrap();
+            assert!(err.starts_with("line 5: "), "{}: {}", face, err);
+        }
+        let err = Wireframe::parse("v 1 2\n").err().unwrap();
+        assert_eq!(err, "line 1: bad vertex: \"v 1 2\"");
+    }
+}
@@ -0,0 +1,458 @@
+use std::path::PathBuf;
+
+use eframe::egui;
+use egui::{Color32, Context, Pos2, Rect, RichText, Sense, Shape, Stroke, Ui, Vec2};
+use lyon::math::Point;
+use lyon::path::{LineCap, LineJoin};
+use lyon::tessellation::VertexBuffers;
+
+use crate::edit_path::{EditPath, Segment, SegmentKind};
+
+// Edits a path by dragging its points, and shows what lyon makes of it.
+pub struct PathEditor {
+    asset_dir: PathBuf,
+    path: EditPath,
+    style: MyStyle,
+    show_fill: bool,
+    show_stroke: bool,
+    show_points: bool,
+    // screen position = view.min + pan + path position * scale
+    scale: f32,
+    pan: Vec2,
+    fit_pending: bool,
+    dragging: Option<usize>,
+    selected: Option<usize>,
+    svg_text: String,
+    message: Option<String>,
+    tessellation: Option<Tessellation>,
+}
+
+// The options that go to the lyon tessellators, with the same choices as lyon-tutorials.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct MyStyle {
+    tolerance: f32,
+    line_width: f32,
+    start_cap: LineCap,
+    end_cap: LineCap,
+    line_join: LineJoin,
+}
+
+struct Tessellation {
+    style: MyStyle,
+    fill: VertexBuffers<Point, u32>,
+    stroke: VertexBuffers<Point, u32>,
+    error: Option<String>,
+}
+
+impl PathEditor {
+    pub fn new(asset_dir: PathBuf) -> Self {
+        let path = EditPath::default();
+        Self {
+            asset_dir,
+            svg_text: path.to_svg(),
+            path,
+            style: MyStyle {
+                tolerance: 0.2,
+                line_width: 10.0,
+                start_cap: LineCap::Square,
+                end_cap: LineCap::Round,
+                line_join: LineJoin::Round,
+            },
+            show_fill: true,
+            show_stroke: true,
+            show_points: true,
+            scale: 1.0,
+            pan: Vec2::ZERO,
+            fit_pending: true,
+            dragging: None,
+            selected: None,
+            message: None,
+            tessellation: None,
+        }
+    }
+
+    pub fn show(&mut self, ctx: &Context) {
+        use egui::{CentralPanel, ScrollArea, SidePanel};
+
+        SidePanel::left("path_options")
+            .default_width(260.0)
+            .show(ctx, |ui| {
+                ScrollArea::vertical().show(ui, |ui| self.show_options(ui));
+            });
+        CentralPanel::default().show(ctx, |ui| self.show_canvas(ui));
+    }
+
+    // Replaces the path with the one in the text box, or says what is wrong with the text.
+    fn import(&mut self) {
+        match EditPath::parse(&self.svg_text) {
+            Ok(path) => {
+                self.path = path;
+                self.tessellation = None;
+                self.dragging = None;
+                self.selected = None;
+                self.fit_pending = true;
+                self.message = None;
+            }
+            Err(err) => self.message = Some(err),
+        }
+    }
+
+    fn show_options(&mut self, ui: &mut Ui) {
+        use egui::{ComboBox, Slider};
+
+        ui.heading("Tessellation");
+        ui.add(
+            Slider::new(&mut self.style.tolerance, 0.01..=10.0)
+                .logarithmic(true)
+                .text("tolerance"),
+        );
+        ui.add(Slider::new(&mut self.style.line_width, 0.0..=40.0).text("line width"));
+        for (label, cap) in [
+            ("start cap", &mut self.style.start_cap),
+            ("end cap", &mut self.style.end_cap),
+        ] {
+            ComboBox::from_label(label)
+                .selected_text(format!("{:?}", cap))
+                .show_ui(ui, |ui| {
+                    for choice in [LineCap::Butt, LineCap::Square, LineCap::Round] {
+                        ui.selectable_value(cap, choice, format!("{:?}", choice));
+                    }
+                });
+        }
+        ComboBox::from_label("line join")
+            .selected_text(format!("{:?}", self.style.line_join))
+            .show_ui(ui, |ui| {
+                for choice in [
+                    LineJoin::Miter,
+                    LineJoin::MiterClip,
+                    LineJoin::Round,
+                    LineJoin::Bevel,
+                ] {
+                    ui.selectable_value(&mut self.style.line_join, choice, format!("{:?}", choice));
+                }
+            });
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut self.show_fill, "fill");
+            ui.checkbox(&mut self.show_stroke, "stroke");
+            ui.checkbox(&mut self.show_points, "points");
+        });
+        if let Some(tessellation) = &self.tessellation {
+            for (label, buffers) in [
+                ("fill", &tessellation.fill),
+                ("stroke", &tessellation.stroke),
+            ] {
+                ui.monospace(format!(
+                    "{:6}{:6} vertices {:6} indices",
+                    label,
+                    buffers.vertices.len(),
+                    buffers.indices.len()
+                ));
+            }
+            if let Some(err) = &tessellation.error {
+                ui.colored_label(Color32::RED, err);
+            }
+        }
+
+        ui.separator();
+        ui.heading("Segments");
+        ui.horizontal_wrapped(|ui| {
+            for (label, kind) in [
+                ("+ line", SegmentKind::Line),
+                ("+ quadratic", SegmentKind::Quadratic),
+                ("+ cubic", SegmentKind::Cubic),
+                ("+ arc", SegmentKind::Arc),
+            ] {
+                if ui.button(label).clicked() {
+                    self.path.add(kind);
+                    self.tessellation = None;
+                }
+            }
+        });
+        if let Some(sub_path) = self.path.sub_paths.last_mut() {
+            ui.horizontal(|ui| {
+                if ui.checkbox(&mut sub_path.closed, "closed").changed() {
+                    self.tessellation = None;
+                }
+                if ui.button("remove last segment").clicked() {
+                    sub_path.segments.pop();
+                    self.tessellation = None;
+                    self.dragging = None;
+                    self.selected = None;
+                }
+            });
+        }
+        self.show_selected_arc(ui);
+
+        ui.separator();
+        ui.heading("SVG path data");
+        ui.horizontal_wrapped(|ui| {
+            if ui.button("Import").clicked() {
+                self.import();
+            }
+            if ui.button("Export").clicked() {
+                self.svg_text = self.path.to_svg();
+                self.message = None;
+            }
+            if ui.button("Copy").clicked() {
+                ui.output_mut(|output| output.copied_text = self.path.to_svg());
+            }
+            if ui.button("rust-logo.path").clicked() {
+                let file = self.asset_dir.join("rust-logo.path");
+                match std::fs::read_to_string(&file) {
+                    Ok(text) => {
+                        self.svg_text = text;
+                        self.import();
+                    }
+                    Err(err) => self.message = Some(format!("{}: {}", file.display(), err)),
+                }
+            }
+        });
+        if let Some(message) = &self.message {
+            // monospace, so that the caret of a parse error lines up
+            ui.colored_label(Color32::RED, RichText::new(message).monospace());
+        }
+        ui.add(
+            egui::TextEdit::multiline(&mut self.svg_text)
+                .code_editor()
+                .desired_rows(8)
+                .desired_width(f32::INFINITY),
+        );
+    }
+
+    // The arc parameters are not points, so they get widgets when an arc's end point is selected.
+    fn show_selected_arc(&mut self, ui: &mut Ui) {
+        use egui::DragValue;
+
+        let Some(selected) = self.selected else {
+            return;
+        };
+        let Some(point) = self.path.points().get(selected).copied() else {
+            return;
+        };
+        let Some(Segment::Arc {
+            radii,
+            x_rotation,
+            large_arc,
+            sweep,
+            ..
+        }) = point
+            .segment
+            .and_then(|segment| self.path.segment_mut(point.sub_path, segment))
+        else {
+            return;
+        };
+        let mut changed = false;
+        ui.label("selected arc");
+        ui.horizontal(|ui| {
+            changed |= ui
+                .add(DragValue::new(&mut radii.x).clamp_range(0.0..=f32::INFINITY))
+                .changed();
+            changed |= ui
+                .add(DragValue::new(&mut radii.y).clamp_range(0.0..=f32::INFINITY))
+                .changed();
+            ui.label("radii");
+        });
+        ui.horizontal(|ui| {
+            changed |= ui.add(DragValue::new(x_rotation).suffix("°")).changed();
+            ui.label("x rotation");
+        });
+        ui.horizontal(|ui| {
+            changed |= ui.checkbox(large_arc, "large arc").changed();
+            changed |= ui.checkbox(sweep, "sweep").changed();
+        });
+        if changed {
+            self.tessellation = None;
+        }
+    }
+
+    fn show_canvas(&mut self, ui: &mut Ui) {
+        let (response, painter) = ui.allocate_painter(ui.available_size(), Sense::click_and_drag());
+        let view = response.rect;
+        if self.fit_pending {
+            self.fit(view);
+            self.fit_pending = false;
+        }
+
+        // zoom around the pointer
+        if let Some(pointer) = response.hover_pos() {
+            let (scroll, pinch) = ui.input(|i| (i.raw_scroll_delta.y, i.zoom_delta()));
+            let factor = pinch * (scroll * MY_SCROLL_ZOOM_SPEED).exp();
+            if factor != 1.0 {
+                let anchor = pointer - view.min - self.pan;
+                self.pan -= anchor * (factor - 1.0);
+                self.scale *= factor;
+            }
+        }
+
+        let screen_points = (self.path.points().iter())
+            .map(|point| self.to_screen(view, point.position))
+            .collect::<Vec<_>>();
+        let nearest = |pointer: Pos2| {
+            screen_points
+                .iter()
+                .enumerate()
+                .map(|(i, point)| (i, point.distance(pointer)))
+                .filter(|(_, distance)| *distance <= MY_PICK_RADIUS)
+                .min_by(|a, b| a.1.total_cmp(&b.1))
+                .map(|(i, _)| i)
+        };
+        if response.drag_started() {
+            self.dragging = response.interact_pointer_pos().and_then(nearest);
+            if self.dragging.is_some() {
+                self.selected = self.dragging;
+            }
+        }
+        if response.clicked() {
+            self.selected = response.interact_pointer_pos().and_then(nearest);
+        }
+        if response.dragged() {
+            let delta = response.drag_delta();
+            match self.dragging {
+                // a point follows the pointer, anything else pans the view
+                Some(i) => {
+                    if let Some(point) = self.path.points_mut().into_iter().nth(i) {
+                        *point += lyon::math::vector(delta.x, delta.y) / self.scale;
+                        self.tessellation = None;
+                    }
+                }
+                None => self.pan += delta,
+            }
+        }
+        if response.drag_stopped() {
+            self.dragging = None;
+        }
+
+        let tessellation = self.tessellate();
+        painter.rect_filled(view, 0.0, Color32::from_gray(24));
+        if self.show_fill {
+            painter.add(self.mesh(view, &tessellation.fill, MY_FILL_COLOR));
+        }
+        if self.show_stroke {
+            painter.add(self.mesh(view, &tessellation.stroke, MY_STROKE_COLOR));
+        }
+        self.tessellation = Some(tessellation);
+
+        if self.show_points {
+            let handle_stroke = Stroke::new(1.0, Color32::GRAY);
+            for [from, to] in self.path.handles() {
+                painter.line_segment(
+                    [self.to_screen(view, from), self.to_screen(view, to)],
+                    handle_stroke,
+                );
+            }
+            for (i, point) in self.path.points().iter().enumerate() {
+                let center = self.to_screen(view, point.position);
+                let color = if Some(i) == self.selected {
+                    Color32::YELLOW
+                } else {
+                    Color32::WHITE
+                };
+                if point.on_curve {
+                    painter.circle_filled(center, MY_POINT_RADIUS, color);
+                } else {
+                    let rect = Rect::from_center_size(center, Vec2::splat(MY_POINT_RADIUS * 2.0));
+                    painter.rect_stroke(rect, 0.0, Stroke::new(1.5, color));
+                }
+            }
+        }
+    }
+
+    // Re-tessellates only when the path or the style has changed since last time.
+    fn tessellate(&mut self) -> Tessellation {
+        use lyon::tessellation::{
+            BuffersBuilder, FillOptions, FillTessellator, FillVertex, StrokeOptions,
+            StrokeTessellator, StrokeVertex,
+        };
+
+        if let Some(tessellation) = self.tessellation.take() {
+            if tessellation.style == self.style {
+                return tessellation;
+            }
+        }
+
+        let path = self.path.to_path();
+        let mut fill = VertexBuffers::new();
+        let mut stroke = VertexBuffers::new();
+        let mut errors = vec![];
+
+        let options = FillOptions::tolerance(self.style.tolerance);
+        if let Err(err) = FillTessellator::new().tessellate_path(
+            &path,
+            &options,
+            &mut BuffersBuilder::new(&mut fill, |vertex: FillVertex| vertex.position()),
+        ) {
+            errors.push(format!("fill: {:?}", err));
+        }
+
+        let options = StrokeOptions::tolerance(self.style.tolerance)
+            .with_start_cap(self.style.start_cap)
+            .with_end_cap(self.style.end_cap)
+            .with_line_join(self.style.line_join)
+            .with_line_width(self.style.line_width);
+        if let Err(err) = StrokeTessellator::new().tessellate_path(
+            &path,
+            &options,
+            &mut BuffersBuilder::new(&mut stroke, |vertex: StrokeVertex| vertex.position()),
+        ) {
+            errors.push(format!("stroke: {:?}", err));
+        }
+
+        Tessellation {
+            style: self.style,
+            fill,
+            stroke,
+            error: (!errors.is_empty()).then(|| errors.join("\n")),
+        }
+    }
+
+    fn mesh(&self, view: Rect, buffers: &VertexBuffers<Point, u32>, color: Color32) -> Shape {
+        use egui::epaint::{Mesh, Vertex, WHITE_UV};
+
+        let vertices = buffers
+            .vertices
+            .iter()
+            .map(|position| Vertex {
+                pos: self.to_screen(view, *position),
+                uv: WHITE_UV,
+                color,
+            })
+            .collect();
+        Shape::mesh(Mesh {
+            indices: buffers.indices.clone(),
+            vertices,
+            ..Default::default()
+        })
+    }
+
+    // Fits the bounding box of the path in the view, with some margin.
+    fn fit(&mut self, view: Rect) {
+        use lyon::algorithms::aabb::bounding_box;
+
+        let path = self.path.to_path();
+        let bounds = bounding_box(path.iter());
+        let size = bounds.size();
+        if !(size.width > 0.0 || size.height > 0.0) {
+            self.scale = 1.0;
+            self.pan = view.size() / 2.0;
+            return;
+        }
+        self.scale = (view.width() / size.width)
+            .min(view.height() / size.height)
+            .min(MY_MAX_FIT_SCALE)
+            * MY_FIT_MARGIN;
+        let center = bounds.center();
+        self.pan = view.size() / 2.0 - Vec2::new(center.x, center.y) * self.scale;
+    }
+
+    fn to_screen(&self, view: Rect, position: Point) -> Pos2 {
+        view.min + self.pan + Vec2::new(position.x, position.y) * self.scale
+    }
+}
+
+const MY_FILL_COLOR: Color32 = Color32::from_rgb(0xe0, 0x80, 0x40);
+const MY_STROKE_COLOR: Color32 = Color32::from_rgba_premultiplied(0x30, 0x5a, 0x74, 0xa0);
+const MY_POINT_RADIUS: f32 = 4.0;
+const MY_PICK_RADIUS: f32 = 8.0;
+const MY_SCROLL_ZOOM_SPEED: f32 = 0.002;
+const MY_FIT_MARGIN: f32 = 0.9;
+const MY_MAX_FIT_SCALE: f32 = 4.0;
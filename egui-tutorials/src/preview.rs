@@ -0,0 +1,284 @@
+use std::path::Path;
+use std::sync::Arc;
+
+use eframe::egui;
+use egui::{
+    Align2, Color32, Context, FontFamily, FontId, Pos2, Rect, RichText, Sense, Stroke,
+    TextureHandle, Ui, Vec2,
+};
+
+use crate::obj::Wireframe;
+
+// What the central panel shows for the selected file.
+pub enum Preview {
+    Image(ImagePreview),
+    Svg { uri: String, bytes: Arc<[u8]> },
+    Font { family: String },
+    Mesh(MeshPreview),
+    Text { text: String },
+    Unsupported,
+    Error(String),
+}
+
+impl Preview {
+    pub fn open(ctx: &Context, path: &Path) -> Self {
+        let extension = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .map(|extension| extension.to_ascii_lowercase());
+        let result = match extension.as_deref() {
+            Some("png") => ImagePreview::open(ctx, path).map(Preview::Image),
+            Some("svg") => std::fs::read(path)
+                .map(|bytes| Preview::Svg {
+                    // the svg loader picks the file up by the extension of the uri
+                    uri: format!("bytes://{}", path.display()),
+                    bytes: bytes.into(),
+                })
+                .map_err(|err| err.to_string()),
+            // the fonts are registered under their file stem when the app starts
+            Some("ttf") | Some("otf") => Ok(Preview::Font {
+                family: font_family(path).unwrap_or_default(),
+            }),
+            Some("obj") => std::fs::read_to_string(path)
+                .map_err(|err| err.to_string())
+                .and_then(|source| Wireframe::parse(&source))
+                .map(|wireframe| Preview::Mesh(MeshPreview::new(wireframe))),
+            Some("mtl") | Some("path") | Some("txt") => std::fs::read_to_string(path)
+                .map(|text| Preview::Text { text })
+                .map_err(|err| err.to_string()),
+            _ => Ok(Preview::Unsupported),
+        };
+        result.unwrap_or_else(Preview::Error)
+    }
+
+    pub fn show(&mut self, ui: &mut Ui) {
+        match self {
+            Preview::Image(image) => image.show(ui),
+            Preview::Svg { uri, bytes } => {
+                let image = egui::Image::from_bytes(uri.clone(), bytes.clone());
+                ui.add(image.fit_to_exact_size(ui.available_size()));
+            }
+            Preview::Font { family } => show_font(ui, family),
+            Preview::Mesh(mesh) => mesh.show(ui),
+            Preview::Text { text } => {
+                ui.label(format!(
+                    "{} lines, {} bytes",
+                    text.lines().count(),
+                    text.len()
+                ));
+                egui::ScrollArea::both().show(ui, |ui| {
+                    ui.add(
+                        egui::TextEdit::multiline(&mut text.as_str())
+                            .code_editor()
+                            .desired_width(f32::INFINITY),
+                    );
+                });
+            }
+            Preview::Unsupported => {
+                ui.label("No preview for this file.");
+            }
+            Preview::Error(err) => {
+                ui.colored_label(Color32::RED, err.as_str());
+            }
+        }
+    }
+}
+
+// The family name a font file is registered under.
+pub fn font_family(path: &Path) -> Option<String> {
+    Some(path.file_stem()?.to_str()?.to_owned())
+}
+
+fn show_font(ui: &mut Ui, family: &str) {
+    let family = FontFamily::Name(family.into());
+    egui::ScrollArea::vertical().show(ui, |ui| {
+        for size in MY_FONT_SIZES {
+            ui.label(format!("{size} px"));
+            ui.label(RichText::new(MY_FONT_SAMPLE).font(FontId::new(size, family.clone())));
+            ui.separator();
+        }
+    });
+}
+
+// A PNG that can be zoomed with the scroll wheel or a pinch, and panned by dragging.
+// The pixel under the pointer is shown with its RGBA value.
+pub struct ImagePreview {
+    image: image::RgbaImage,
+    texture: TextureHandle,
+    // screen points per image pixel, decided on the first frame to fit the view
+    zoom: Option<f32>,
+    offset: Vec2,
+}
+
+impl ImagePreview {
+    fn open(ctx: &Context, path: &Path) -> Result<Self, String> {
+        let image = image::open(path).map_err(|err| err.to_string())?.to_rgba8();
+        let size = [image.width() as usize, image.height() as usize];
+        let color_image = egui::ColorImage::from_rgba_unmultiplied(size, image.as_raw());
+        // nearest, so that the pixels stay sharp when zoomed in
+        let texture = ctx.load_texture(
+            path.display().to_string(),
+            color_image,
+            egui::TextureOptions::NEAREST,
+        );
+        Ok(Self {
+            image,
+            texture,
+            zoom: None,
+            offset: Vec2::ZERO,
+        })
+    }
+
+    fn show(&mut self, ui: &mut Ui) {
+        let image_size = Vec2::new(self.image.width() as f32, self.image.height() as f32);
+        ui.horizontal(|ui| {
+            ui.label(format!("{} x {}", self.image.width(), self.image.height()));
+            if ui.button("Fit").clicked() {
+                self.zoom = None;
+            }
+            if ui.button("1:1").clicked() {
+                self.zoom = Some(1.0);
+                self.offset = Vec2::ZERO;
+            }
+            if let Some(zoom) = self.zoom {
+                ui.label(format!("{:.0}%", zoom * 100.0));
+            }
+        });
+
+        let inspector_height = ui.spacing().interact_size.y * 1.5;
+        let view_size = ui.available_size() - Vec2::new(0.0, inspector_height);
+        let (response, painter) = ui.allocate_painter(view_size.max(Vec2::ZERO), Sense::drag());
+        let view = response.rect;
+        let zoom = *self.zoom.get_or_insert_with(|| {
+            self.offset = Vec2::ZERO;
+            (view.size() / image_size).min_elem().min(1.0)
+        });
+
+        // zoom around the pointer, so that the pixel under it stays put
+        if let Some(pointer) = response.hover_pos() {
+            let (scroll, pinch) = ui.input(|i| (i.raw_scroll_delta.y, i.zoom_delta()));
+            let factor = pinch * (scroll * MY_SCROLL_ZOOM_SPEED).exp();
+            if factor != 1.0 {
+                let new_zoom = (zoom * factor).clamp(MY_MIN_ZOOM, MY_MAX_ZOOM);
+                let anchor = pointer - view.center() - self.offset;
+                self.offset -= anchor * (new_zoom / zoom - 1.0);
+                self.zoom = Some(new_zoom);
+            }
+        }
+        self.offset += response.drag_delta();
+        let zoom = self.zoom.unwrap_or(zoom);
+
+        let image_rect = Rect::from_center_size(view.center() + self.offset, image_size * zoom);
+        painter.rect_filled(view, 0.0, Color32::from_gray(32));
+        painter.image(
+            self.texture.id(),
+            image_rect,
+            Rect::from_min_max(Pos2::ZERO, Pos2::new(1.0, 1.0)),
+            Color32::WHITE,
+        );
+
+        let hovered_pixel = response.hover_pos().and_then(|pointer| {
+            let position = (pointer - image_rect.min) / zoom;
+            let (x, y) = (position.x.floor(), position.y.floor());
+            (x >= 0.0 && y >= 0.0 && x < image_size.x && y < image_size.y)
+                .then_some((x as u32, y as u32))
+        });
+        let Some((x, y)) = hovered_pixel else {
+            ui.label("Hover over the image to inspect a pixel.");
+            return;
+        };
+        if zoom >= MY_PIXEL_OUTLINE_ZOOM {
+            let pixel_rect = Rect::from_min_size(
+                image_rect.min + Vec2::new(x as f32, y as f32) * zoom,
+                Vec2::splat(zoom),
+            );
+            painter.rect_stroke(pixel_rect, 0.0, Stroke::new(1.0, Color32::YELLOW));
+        }
+        let [r, g, b, a] = self.image.get_pixel(x, y).0;
+        ui.horizontal(|ui| {
+            let (swatch, _) = ui.allocate_exact_size(Vec2::splat(16.0), Sense::hover());
+            ui.painter()
+                .rect_filled(swatch, 2.0, Color32::from_rgba_unmultiplied(r, g, b, a));
+            ui.monospace(format!(
+                "({x}, {y})  rgba({r}, {g}, {b}, {a})  #{r:02x}{g:02x}{b:02x}{a:02x}"
+            ));
+        });
+    }
+}
+
+// A wireframe that turns when dragged.
+pub struct MeshPreview {
+    wireframe: Wireframe,
+    center: [f32; 3],
+    extent: f32,
+    yaw: f32,
+    pitch: f32,
+}
+
+impl MeshPreview {
+    fn new(wireframe: Wireframe) -> Self {
+        let (center, extent) = wireframe.bounds();
+        Self {
+            wireframe,
+            center,
+            extent,
+            yaw: 0.6,
+            pitch: 0.4,
+        }
+    }
+
+    fn show(&mut self, ui: &mut Ui) {
+        ui.label(format!(
+            "{} vertices, {} edges, {} faces - drag to rotate",
+            self.wireframe.vertices.len(),
+            self.wireframe.edges.len(),
+            self.wireframe.faces
+        ));
+        let (response, painter) = ui.allocate_painter(ui.available_size(), Sense::drag());
+        let view = response.rect;
+        let drag = response.drag_delta();
+        self.yaw += drag.x * MY_ROTATE_SPEED;
+        self.pitch = (self.pitch + drag.y * MY_ROTATE_SPEED).clamp(-1.5, 1.5);
+
+        let (sin_yaw, cos_yaw) = self.yaw.sin_cos();
+        let (sin_pitch, cos_pitch) = self.pitch.sin_cos();
+        let scale = view.width().min(view.height()) * 0.4;
+        // rotate around y, then x, and look at it from MY_CAMERA_DISTANCE on +z
+        let project = |vertex: &[f32; 3]| {
+            let [x, y, z] = [0, 1, 2].map(|axis| (vertex[axis] - self.center[axis]) / self.extent);
+            let (x, z) = (x * cos_yaw + z * sin_yaw, z * cos_yaw - x * sin_yaw);
+            let (y, z) = (y * cos_pitch - z * sin_pitch, y * sin_pitch + z * cos_pitch);
+            let perspective = MY_CAMERA_DISTANCE / (MY_CAMERA_DISTANCE - z);
+            view.center() + Vec2::new(x, -y) * perspective * scale
+        };
+        let points = self
+            .wireframe
+            .vertices
+            .iter()
+            .map(project)
+            .collect::<Vec<_>>();
+
+        painter.rect_filled(view, 0.0, Color32::from_gray(24));
+        let stroke = Stroke::new(1.0, Color32::from_rgb(0x48, 0xb2, 0xe8));
+        for [a, b] in &self.wireframe.edges {
+            painter.line_segment([points[*a], points[*b]], stroke);
+        }
+        painter.text(
+            view.left_bottom() + Vec2::new(4.0, -4.0),
+            Align2::LEFT_BOTTOM,
+            format!("yaw {:.2} pitch {:.2}", self.yaw, self.pitch),
+            FontId::monospace(12.0),
+            Color32::GRAY,
+        );
+    }
+}
+
+const MY_FONT_SIZES: [f32; 5] = [12.0, 16.0, 24.0, 36.0, 48.0];
+const MY_FONT_SAMPLE: &str =
+    "The quick brown fox jumps over the lazy dog\n0123456789 {}[]() <=> != ->";
+const MY_SCROLL_ZOOM_SPEED: f32 = 0.002;
+const MY_MIN_ZOOM: f32 = 0.05;
+const MY_MAX_ZOOM: f32 = 64.0;
+const MY_PIXEL_OUTLINE_ZOOM: f32 = 8.0;
+const MY_ROTATE_SPEED: f32 = 0.01;
+const MY_CAMERA_DISTANCE: f32 = 4.0;
@@ -0,0 +1,424 @@
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use eframe::egui;
+use eframe::glow::{self, HasContext};
+use egui::{Color32, Context, Sense, Ui, Vec2};
+
+// Edits a fragment shader and draws it with glow, under egui, through a PaintCallback.
+// A shader that fails to build leaves the last good one running.
+pub struct ShaderPlayground {
+    asset_dir: PathBuf,
+    preset: usize,
+    fragment_source: String,
+    // what the renderer was last asked to build, so it is only rebuilt after an edit
+    built: Option<(usize, String)>,
+    error: Option<String>,
+    renderer: Arc<Mutex<Option<Renderer>>>,
+    started: Instant,
+    // in pixels from the bottom left of the viewport
+    mouse: Vec2,
+}
+
+struct Preset {
+    name: &'static str,
+    vertex: &'static str,
+    fragment: &'static str,
+}
+
+// The vertices give the shaders of both tutorials what they read, so their files run as they are.
+const MY_PRESETS: [Preset; 3] = [
+    Preset {
+        name: "playground",
+        vertex: include_str!("shaders/playground.vert"),
+        fragment: include_str!("shaders/playground.frag"),
+    },
+    Preset {
+        name: "gl-tutorials",
+        vertex: include_str!("../../gl-tutorials/src/shader.vert"),
+        fragment: include_str!("../../gl-tutorials/src/shader.frag"),
+    },
+    Preset {
+        name: "macroquad shading",
+        vertex: include_str!("../../macroquad-tutorials/examples/shading.vert"),
+        fragment: include_str!("../../macroquad-tutorials/examples/shading.frag"),
+    },
+];
+
+impl ShaderPlayground {
+    pub fn new(asset_dir: PathBuf) -> Self {
+        Self {
+            asset_dir,
+            preset: 0,
+            fragment_source: MY_PRESETS[0].fragment.to_owned(),
+            built: None,
+            error: None,
+            renderer: Arc::new(Mutex::new(None)),
+            started: Instant::now(),
+            mouse: Vec2::ZERO,
+        }
+    }
+
+    pub fn show(&mut self, ctx: &Context, frame: &eframe::Frame) {
+        use egui::{CentralPanel, ComboBox, ScrollArea, SidePanel};
+
+        let Some(gl) = frame.gl() else {
+            CentralPanel::default().show(ctx, |ui| {
+                ui.label("The shader playground needs the glow renderer.");
+            });
+            return;
+        };
+        self.build(gl);
+
+        SidePanel::left("shader_source")
+            .default_width(420.0)
+            .show(ctx, |ui| {
+                ComboBox::from_label("preset")
+                    .selected_text(MY_PRESETS[self.preset].name)
+                    .show_ui(ui, |ui| {
+                        for (i, preset) in MY_PRESETS.iter().enumerate() {
+                            if ui.selectable_label(i == self.preset, preset.name).clicked() {
+                                self.preset = i;
+                                self.fragment_source = preset.fragment.to_owned();
+                            }
+                        }
+                    });
+                ui.label("u_time, u_resolution and u_mouse are set every frame.");
+                match &self.error {
+                    Some(err) => {
+                        ui.colored_label(Color32::RED, "The last good shader is still running.");
+                        ui.colored_label(Color32::RED, err);
+                    }
+                    None => {
+                        ui.colored_label(Color32::GREEN, "OK");
+                    }
+                }
+                ScrollArea::vertical().show(ui, |ui| {
+                    ui.add(
+                        egui::TextEdit::multiline(&mut self.fragment_source)
+                            .code_editor()
+                            .desired_rows(24)
+                            .desired_width(f32::INFINITY),
+                    );
+                    ui.collapsing("vertex shader", |ui| {
+                        let mut vertex = MY_PRESETS[self.preset].vertex;
+                        ui.add(
+                            egui::TextEdit::multiline(&mut vertex)
+                                .code_editor()
+                                .desired_width(f32::INFINITY),
+                        );
+                    });
+                });
+            });
+
+        CentralPanel::default().show(ctx, |ui| {
+            egui::Resize::default()
+                .default_size([480.0, 320.0])
+                .min_size([64.0, 64.0])
+                .show(ui, |ui| self.show_viewport(ui));
+        });
+        ctx.request_repaint();
+    }
+
+    // Called with the GL context when the app exits.
+    pub fn destroy(&self, gl: Option<&glow::Context>) {
+        if let (Some(gl), Some(renderer)) = (gl, self.renderer.lock().unwrap().as_ref()) {
+            unsafe { renderer.destroy(gl) };
+        }
+    }
+
+    // Rebuilds the program after the source or the preset has changed.
+    fn build(&mut self, gl: &Arc<glow::Context>) {
+        let request = (self.preset, self.fragment_source.clone());
+        if self.built.as_ref() == Some(&request) {
+            return;
+        }
+        self.built = Some(request);
+
+        let mut renderer = self.renderer.lock().unwrap();
+        if renderer.is_none() {
+            match unsafe { Renderer::new(gl, &self.asset_dir) } {
+                Ok(new_renderer) => *renderer = Some(new_renderer),
+                Err(err) => {
+                    self.error = Some(err);
+                    return;
+                }
+            }
+        }
+        let renderer = renderer.as_mut().unwrap();
+        let vertex = MY_PRESETS[self.preset].vertex;
+        self.error = unsafe { renderer.set_program(gl, vertex, &self.fragment_source) }.err();
+    }
+
+    fn show_viewport(&mut self, ui: &mut Ui) {
+        let (rect, response) = ui.allocate_exact_size(ui.available_size(), Sense::drag());
+        let pixels_per_point = ui.ctx().pixels_per_point();
+        if let Some(pointer) = response.hover_pos() {
+            self.mouse =
+                Vec2::new(pointer.x - rect.left(), rect.bottom() - pointer.y) * pixels_per_point;
+        }
+        let uniforms = MyUniforms {
+            time: self.started.elapsed().as_secs_f32(),
+            resolution: rect.size() * pixels_per_point,
+            mouse: self.mouse,
+        };
+
+        let renderer = self.renderer.clone();
+        let callback = egui::PaintCallback {
+            rect,
+            callback: Arc::new(eframe::egui_glow::CallbackFn::new(move |_info, painter| {
+                if let Some(renderer) = renderer.lock().unwrap().as_ref() {
+                    unsafe { renderer.paint(painter.gl(), &uniforms) };
+                }
+            })),
+        };
+        ui.painter().add(callback);
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct MyUniforms {
+    time: f32,
+    resolution: Vec2,
+    mouse: Vec2,
+}
+
+// The GL objects, shared with the paint callback.
+struct Renderer {
+    program: Option<glow::Program>,
+    vao: glow::VertexArray,
+    vbo: glow::Buffer,
+    texture: glow::Texture,
+}
+
+impl Renderer {
+    unsafe fn new(gl: &glow::Context, asset_dir: &std::path::Path) -> Result<Self, String> {
+        let vao = gl.create_vertex_array()?;
+        let vbo = gl.create_buffer()?;
+        gl.bind_vertex_array(Some(vao));
+        gl.bind_buffer(glow::ARRAY_BUFFER, Some(vbo));
+        let bytes = MY_QUAD
+            .iter()
+            .flatten()
+            .flat_map(|value| value.to_ne_bytes())
+            .collect::<Vec<_>>();
+        gl.buffer_data_u8_slice(glow::ARRAY_BUFFER, &bytes, glow::STATIC_DRAW);
+        // position, texcoord, color0 and normal, as in the macroquad shading example
+        let stride = std::mem::size_of::<[f32; 12]>() as i32;
+        let mut offset = 0;
+        for (location, size) in [(0, 3), (1, 2), (2, 4), (3, 3)] {
+            gl.vertex_attrib_pointer_f32(location, size, glow::FLOAT, false, stride, offset);
+            gl.enable_vertex_attrib_array(location);
+            offset += size * std::mem::size_of::<f32>() as i32;
+        }
+        gl.bind_vertex_array(None);
+
+        // ferris for the Texture sampler, flipped so that texcoord (0, 0) is the bottom left
+        let image = image::open(asset_dir.join("ferris.png"))
+            .map_err(|err| err.to_string())?
+            .flipv()
+            .to_rgba8();
+        let texture = gl.create_texture()?;
+        gl.bind_texture(glow::TEXTURE_2D, Some(texture));
+        gl.tex_image_2d(
+            glow::TEXTURE_2D,
+            0,
+            glow::RGBA8 as i32,
+            image.width() as i32,
+            image.height() as i32,
+            0,
+            glow::RGBA,
+            glow::UNSIGNED_BYTE,
+            Some(image.as_raw()),
+        );
+        gl.tex_parameter_i32(
+            glow::TEXTURE_2D,
+            glow::TEXTURE_MIN_FILTER,
+            glow::LINEAR as i32,
+        );
+        gl.tex_parameter_i32(
+            glow::TEXTURE_2D,
+            glow::TEXTURE_MAG_FILTER,
+            glow::LINEAR as i32,
+        );
+        gl.bind_texture(glow::TEXTURE_2D, None);
+
+        Ok(Self {
+            program: None,
+            vao,
+            vbo,
+            texture,
+        })
+    }
+
+    // Replaces the program if the new one builds, and keeps the old one if it does not.
+    unsafe fn set_program(
+        &mut self,
+        gl: &glow::Context,
+        vertex: &str,
+        fragment: &str,
+    ) -> Result<(), String> {
+        let program = create_program(
+            gl,
+            &[
+                (glow::VERTEX_SHADER, vertex),
+                (glow::FRAGMENT_SHADER, fragment),
+            ],
+        )?;
+        if let Some(old_program) = self.program.replace(program) {
+            gl.delete_program(old_program);
+        }
+        Ok(())
+    }
+
+    unsafe fn paint(&self, gl: &glow::Context, uniforms: &MyUniforms) {
+        let Some(program) = self.program else {
+            return;
+        };
+        gl.use_program(Some(program));
+        let location = |name: &str| gl.get_uniform_location(program, name);
+
+        gl.uniform_1_f32(location("u_time").as_ref(), uniforms.time);
+        gl.uniform_2_f32(
+            location("u_resolution").as_ref(),
+            uniforms.resolution.x,
+            uniforms.resolution.y,
+        );
+        gl.uniform_2_f32(
+            location("u_mouse").as_ref(),
+            uniforms.mouse.x,
+            uniforms.mouse.y,
+        );
+
+        // what the presets read under their own names
+        gl.uniform_1_f32(location("u_angle_y").as_ref(), uniforms.time);
+        let (model, projection, view, light) = macroquad_scene(uniforms);
+        gl.uniform_matrix_4_f32_slice(location("Model").as_ref(), false, &model.to_cols_array());
+        gl.uniform_matrix_4_f32_slice(
+            location("Projection").as_ref(),
+            false,
+            &projection.to_cols_array(),
+        );
+        gl.uniform_3_f32(location("view").as_ref(), view.x, view.y, view.z);
+        gl.uniform_3_f32(location("light").as_ref(), light.x, light.y, light.z);
+        gl.uniform_1_i32(location("Texture").as_ref(), 0);
+        gl.active_texture(glow::TEXTURE0);
+        gl.bind_texture(glow::TEXTURE_2D, Some(self.texture));
+
+        gl.bind_vertex_array(Some(self.vao));
+        gl.draw_arrays(glow::TRIANGLE_STRIP, 0, MY_QUAD.len() as i32);
+        gl.bind_vertex_array(None);
+        gl.bind_texture(glow::TEXTURE_2D, None);
+        gl.use_program(None);
+    }
+
+    unsafe fn destroy(&self, gl: &glow::Context) {
+        if let Some(program) = self.program {
+            gl.delete_program(program);
+        }
+        gl.delete_vertex_array(self.vao);
+        gl.delete_buffer(self.vbo);
+        gl.delete_texture(self.texture);
+    }
+}
+
+// The quad turns slowly, and the mouse moves the light, like the camera in the example.
+fn macroquad_scene(uniforms: &MyUniforms) -> (glam::Mat4, glam::Mat4, glam::Vec3, glam::Vec3) {
+    use glam::{Mat4, Vec3};
+
+    let aspect = uniforms.resolution.x / uniforms.resolution.y.max(1.0);
+    let view = Vec3::new(0.0, 0.0, 3.0);
+    let projection = Mat4::perspective_rh_gl(45f32.to_radians(), aspect, 0.1, 100.0)
+        * Mat4::look_at_rh(view, Vec3::ZERO, Vec3::Y);
+    let model = Mat4::from_rotation_y((uniforms.time * 0.5).sin() * 0.8);
+    let mouse = uniforms.mouse / uniforms.resolution.max(Vec2::splat(1.0)) * 2.0 - Vec2::splat(1.0);
+    let light = Vec3::new(mouse.x * 3.0, mouse.y * 3.0, 2.0);
+    (model, projection, view, light)
+}
+
+// The shaders from the tutorials have no #version, or one for desktop GL only, so the line is
+// replaced with the one for this context. #line keeps the line numbers in the errors right.
+unsafe fn create_program(
+    gl: &glow::Context,
+    shader_sources: &[(u32, &str)],
+) -> Result<glow::Program, String> {
+    let shader_version = if gl.version().is_embedded {
+        "#version 300 es"
+    } else {
+        "#version 330"
+    };
+
+    let program = gl.create_program()?;
+    let mut shaders = vec![];
+    let mut result = Ok(());
+    for (shader_type, shader_source) in shader_sources {
+        let shader = gl.create_shader(*shader_type)?;
+        let shader_source = shader_source
+            .lines()
+            .map(|line| {
+                if line.trim_start().starts_with("#version") {
+                    ""
+                } else {
+                    line
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        let shader_source = format!("{shader_version}\n#line 1\n{shader_source}");
+        gl.shader_source(shader, shader_source.as_str());
+        gl.compile_shader(shader);
+        gl.attach_shader(program, shader);
+        shaders.push(shader);
+        if !gl.get_shader_compile_status(shader) {
+            let stage = if *shader_type == glow::VERTEX_SHADER {
+                "vertex shader"
+            } else {
+                "fragment shader"
+            };
+            result = Err(format!(
+                "Failed to compile the {stage}:\n{}",
+                gl.get_shader_info_log(shader)
+            ));
+            break;
+        }
+    }
+    if result.is_ok() {
+        gl.link_program(program);
+        if !gl.get_program_link_status(program) {
+            result = Err(format!(
+                "Failed to link:\n{}",
+                gl.get_program_info_log(program)
+            ));
+        }
+    }
+    shaders.iter().for_each(|shader| {
+        gl.detach_shader(program, *shader);
+        gl.delete_shader(*shader);
+    });
+
+    match result {
+        Ok(()) => Ok(program),
+        Err(err) => {
+            gl.delete_program(program);
+            Err(err)
+        }
+    }
+}
+
+// A quad over the whole viewport, facing the camera.
+// position, texcoord, color0 (0 to 255, as macroquad passes it) and normal
+const MY_QUAD: [[f32; 12]; 4] = [
+    [
+        -1.0, -1.0, 0.0, 0.0, 0.0, 255.0, 255.0, 255.0, 255.0, 0.0, 0.0, 1.0,
+    ],
+    [
+        1.0, -1.0, 0.0, 1.0, 0.0, 255.0, 255.0, 255.0, 255.0, 0.0, 0.0, 1.0,
+    ],
+    [
+        -1.0, 1.0, 0.0, 0.0, 1.0, 255.0, 255.0, 255.0, 255.0, 0.0, 0.0, 1.0,
+    ],
+    [
+        1.0, 1.0, 0.0, 1.0, 1.0, 255.0, 255.0, 255.0, 255.0, 0.0, 0.0, 1.0,
+    ],
+];
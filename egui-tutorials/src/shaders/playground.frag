@@ -0,0 +1,17 @@
+precision mediump float;
+uniform float u_time;
+uniform vec2 u_resolution;
+uniform vec2 u_mouse;
+in vec2 v_uv;
+out vec4 f_color;
+
+void main() {
+    // v_uv goes from (0, 0) at the bottom left to (1, 1) at the top right, and u_mouse is in
+    // pixels from the same corner. gl_FragCoord would count from the corner of the window.
+    vec2 p = (v_uv - 0.5) * u_resolution / u_resolution.y;
+    vec2 m = (u_mouse - 0.5 * u_resolution) / u_resolution.y;
+    float d = length(p - m);
+    vec3 color = 0.5 + 0.5 * cos(u_time + v_uv.xyx + vec3(0.0, 2.0, 4.0));
+    color *= smoothstep(0.0, 0.02, abs(fract(d * 8.0 - u_time) - 0.5) - 0.1) * 0.3 + 0.7;
+    f_color = vec4(color, 1.0);
+}
@@ -0,0 +1,9 @@
+precision mediump float;
+layout(location = 0) in vec3 position;
+layout(location = 1) in vec2 texcoord;
+out vec2 v_uv;
+
+void main() {
+    gl_Position = vec4(position, 1.0);
+    v_uv = texcoord;
+}
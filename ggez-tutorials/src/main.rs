@@ -2,7 +2,13 @@ use ggez::{Context, GameResult};
 use glam::*;
 use std::{env, path};
 
+use tutorial_common::args::Args;
+use tutorial_common::locale;
+
 pub fn main() -> GameResult {
+    let args = Args::from_env(&["--lang"]);
+    let language = locale::Language::from_args(&args);
+
     let resource_dir = if let Ok(manifest_dir) = env::var("CARGO_MANIFEST_DIR") {
         let mut path = path::PathBuf::from(manifest_dir);
         path.push("../assets");
@@ -23,26 +29,53 @@ pub fn main() -> GameResult {
             height: 320.0,
             ..Default::default()
         })
-        .add_resource_path(resource_dir);
+        .add_resource_path(&resource_dir);
     let (mut ggez_context, event_queue) = context_builder.build()?;
 
-    let my_app = MyApp::new(&mut ggez_context)?;
+    let my_app = MyApp::new(&mut ggez_context, &resource_dir, language)?;
     ggez::event::run(ggez_context, event_queue, my_app)
 }
 
-struct MyApp {}
+struct MyApp {
+    strings: &'static locale::HudStrings,
+    fonts: locale::FontFallback,
+}
 
 impl MyApp {
-    fn new(ctx: &mut Context) -> GameResult<MyApp> {
+    fn new(
+        ctx: &mut Context,
+        resource_dir: &path::Path,
+        language: locale::Language,
+    ) -> GameResult<MyApp> {
         use ggez::graphics::FontData;
 
-        ctx.gfx.add_font(
-            "LiberationMono",
-            FontData::from_path(ctx, "/LiberationMono-Regular.ttf")?,
-        );
-        let my_app = MyApp {};
+        // every font is added under its file name, which is what the runs refer to
+        let fonts = locale::FontFallback::load(resource_dir);
+        for font in fonts.fonts() {
+            ctx.gfx
+                .add_font(font.name, FontData::from_vec(font.data.clone())?);
+        }
+        let my_app = MyApp {
+            strings: language.strings(),
+            fonts,
+        };
         Ok(my_app)
     }
+
+    // A text whose characters are drawn with the first font that has them.
+    fn text(&self, text: &str, scale: f32) -> ggez::graphics::Text {
+        use ggez::graphics::{Text, TextFragment};
+
+        let mut result = Text::default();
+        for (font, run) in self.fonts.runs(text) {
+            let mut fragment = TextFragment::new(run).scale(scale);
+            if let Some(font) = self.fonts.fonts().get(font) {
+                fragment = fragment.font(font.name);
+            }
+            result.add(fragment);
+        }
+        result
+    }
 }
 
 use ggez::{event::EventHandler, GameError};
@@ -53,7 +86,7 @@ impl EventHandler<GameError> for MyApp {
     }
 
     fn draw(&mut self, ctx: &mut Context) -> GameResult {
-        use ggez::graphics::{Canvas, Color, DrawMode, DrawParam, Drawable, Mesh, Text};
+        use ggez::graphics::{Canvas, Color, DrawMode, DrawParam, Drawable, Mesh};
 
         let mut canvas = Canvas::from_frame(ctx, Color::from([0.2, 0.2, 0.2, 1.0]));
 
@@ -84,16 +117,13 @@ impl EventHandler<GameError> for MyApp {
                 // circle.draw(&mut canvas, DrawParam::new().transform(mat));
             });
 
-        let fps = format!("FPS: {:.2}", ctx.time.fps());
+        let fps = locale::fill(self.strings.fps, format!("{:.2}", ctx.time.fps()));
         let scales = [48.0, 32.0, 24.0, 16.0];
         [0.0, 48.0, 80.0, 104.0]
             .iter()
             .zip(scales.iter())
             .for_each(|(y, s)| {
-                let mut text = Text::new(&fps);
-                text.set_font("LiberationMono")
-                    .set_scale(*s)
-                    .draw(&mut canvas, Vec2::new(0.0, *y));
+                self.text(&fps, *s).draw(&mut canvas, Vec2::new(0.0, *y));
             });
 
         // let fps = format!("FPS: {:.2}", ctx.time.fps());
@@ -0,0 +1,54 @@
+// Compiles the GLSL sources to SPIR-V for drivers that support GL_ARB_gl_spirv.
+fn main() {
+    use naga::back::spv::{Options, WriterFlags};
+    use naga::front::glsl::Frontend;
+    use naga::valid::{Capabilities, ValidationFlags, Validator};
+    use naga::ShaderStage;
+
+    let out_dir = std::env::var("OUT_DIR").unwrap();
+    for (path, stage) in [
+        ("src/shader.vert", ShaderStage::Vertex),
+        ("src/shader.frag", ShaderStage::Fragment),
+    ] {
+        println!("cargo:rerun-if-changed={path}");
+
+        let shader_source = std::fs::read_to_string(path).unwrap();
+        let shader_source = format!("#version 450\n{shader_source}");
+        let mut options = naga::front::glsl::Options::from(stage);
+        options
+            .defines
+            .insert("GL_SPIRV".to_owned(), "100".to_owned());
+        let module = Frontend::default()
+            .parse(&options, &shader_source)
+            .unwrap_or_else(|errors| {
+                let errors = errors
+                    .iter()
+                    .map(|error| {
+                        let location = error.meta.location(&shader_source);
+                        format!(
+                            "{path}:{}:{}: {error}",
+                            location.line_number - 1,
+                            location.line_position
+                        )
+                    })
+                    .collect::<Vec<_>>();
+                panic!("Failed to parse:\n{}", errors.join("\n"));
+            });
+        let info = Validator::new(ValidationFlags::all(), Capabilities::all())
+            .validate(&module)
+            .unwrap_or_else(|error| panic!("{path}: {error}"));
+
+        // OpenGL keeps the clip space as it is, unlike Vulkan
+        let options = Options {
+            flags: WriterFlags::LABEL_VARYINGS,
+            ..Default::default()
+        };
+        let words = naga::back::spv::write_vec(&module, &info, &options, None).unwrap();
+        let bytes = words
+            .iter()
+            .flat_map(|word| word.to_le_bytes())
+            .collect::<Vec<_>>();
+        let file_name = path.trim_start_matches("src/");
+        std::fs::write(format!("{out_dir}/{file_name}.spv"), bytes).unwrap();
+    }
+}
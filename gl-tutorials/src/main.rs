@@ -1,8 +1,13 @@
 fn main() {
     use glfw::{Context, SwapInterval, WindowHint, WindowMode};
+    use tutorial_common::args::Args;
+
+    let args = Args::from_env(&["--mesh", "--watch"]);
+    let mesh = MeshFile::from_args(&args);
 
     let mut glfw = glfw::init(glfw::fail_on_errors).unwrap();
     glfw.window_hint(WindowHint::ContextVersion(3, 3));
+    glfw.window_hint(WindowHint::OpenGlDebugContext(true));
     let (mut window, event_queue) = glfw
         .create_window(480, 320, "gl tutorial", WindowMode::Windowed)
         .unwrap();
@@ -10,13 +15,30 @@ fn main() {
     window.make_current();
     gl::load_with(|symbol| window.get_proc_address(symbol).cast());
     glfw.set_swap_interval(SwapInterval::Sync(1));
-    if glfw.extension_supported("GL_ARB_gl_spirv") {
+    let spirv = if glfw.extension_supported("GL_ARB_gl_spirv") {
         println!("Extension 'GL_ARB_gl_spirv' is supported.");
+        tutorial_common::shader_program::load_spirv_with(|symbol| {
+            window.get_proc_address(symbol).cast()
+        })
     } else {
         println!("Extension 'GL_ARB_gl_spirv' is not supported.");
+        false
+    };
+    if glfw.extension_supported("GL_KHR_debug") {
+        unsafe {
+            gl::Enable(gl::DEBUG_OUTPUT);
+            gl::Enable(gl::DEBUG_OUTPUT_SYNCHRONOUS);
+            gl::DebugMessageCallback(Some(debug_message_callback), std::ptr::null());
+        }
+    } else {
+        println!("Extension 'GL_KHR_debug' is not supported.");
     }
 
-    let mut my_app = MyApp::new();
+    // the watched files are GLSL sources, so watching always takes the source path
+    let mut my_app = MyApp::new(spirv && !args.watch, mesh);
+    if args.watch {
+        my_app.watch_shaders(&[MY_VERTEX_SHADER_PATH, MY_FRAGMENT_SHADER_PATH]);
+    }
     let mut i = 0;
     window.set_all_polling(true);
     while !window.should_close() {
@@ -43,69 +65,78 @@ fn main() {
     }
 }
 
+use tutorial_common::mesh_file::MeshFile;
+use tutorial_common::shader_program::ShaderProgram;
+use tutorial_common::shader_watcher::ShaderWatcher;
+
+extern "system" fn debug_message_callback(
+    source: gl::types::GLenum,
+    gltype: gl::types::GLenum,
+    id: gl::types::GLuint,
+    severity: gl::types::GLenum,
+    length: gl::types::GLsizei,
+    message: *const gl::types::GLchar,
+    _user_param: *mut std::ffi::c_void,
+) {
+    let message = unsafe { std::slice::from_raw_parts(message as *const u8, length as usize) };
+    let message = String::from_utf8_lossy(message);
+    match severity {
+        gl::DEBUG_SEVERITY_NOTIFICATION => {}
+        gl::DEBUG_SEVERITY_HIGH if gltype == gl::DEBUG_TYPE_ERROR => {
+            eprintln!("GL error {id:#x} (source {source:#x}): {message}");
+        }
+        _ => {
+            eprintln!("GL debug {id:#x} (source {source:#x}, type {gltype:#x}): {message}");
+        }
+    }
+}
+
 struct MyApp {
-    program: u32,
+    program: ShaderProgram,
     vao: u32,
     vbo: u32,
+    ubo: Option<u32>,
+    vertices: Vec<MyVertex>,
     angle_y: f32,
+    shader_watcher: Option<ShaderWatcher>,
 }
 
 impl MyApp {
-    fn new() -> Self {
-        let shader_version = if cfg!(target_arch = "wasm32") {
-            "#version 300 es"
+    fn new(spirv: bool, mesh: Option<MeshFile>) -> Self {
+        let program = if spirv {
+            ShaderProgram::from_spirv(&[
+                (gl::VERTEX_SHADER, MY_VERTEX_SHADER_BINARY),
+                (gl::FRAGMENT_SHADER, MY_FRAGMENT_SHADER_BINARY),
+            ])
+            .inspect_err(|err| eprintln!("{err}"))
+            .ok()
         } else {
-            "#version 330"
+            None
         };
-        let shader_sources = [
-            (gl::VERTEX_SHADER, MY_VERTEX_SHADER_SOURCE),
-            (gl::FRAGMENT_SHADER, MY_FRAGMENT_SHADER_SOURCE),
-        ];
-
-        let program = unsafe {
-            let program = gl::CreateProgram();
-            let shaders = shader_sources
-                .iter()
-                .map(|(shader_type, shader_source)| {
-                    let shader = gl::CreateShader(*shader_type);
-                    let shader_source = format!("{shader_version}\n{shader_source}");
-                    let _length = shader_source.len() as _;
-                    gl::ShaderSource(shader, 1, [shader_source.as_ptr() as _].as_ptr(), &_length);
-                    gl::CompileShader(shader);
-                    {
-                        let mut message = String::with_capacity(200);
-                        gl::GetShaderInfoLog(
-                            shader,
-                            200,
-                            std::ptr::null_mut(),
-                            message.as_mut_ptr() as _,
-                        );
-                        assert!(
-                            message.len() == 0,
-                            "Failed to compile {shader_type}: {}",
-                            message
-                        );
-                    }
-                    gl::AttachShader(program, shader);
-                    shader
-                })
-                .collect::<Vec<_>>();
-            gl::LinkProgram(program);
-            {
-                let mut message = String::with_capacity(200);
-                gl::GetProgramInfoLog(
-                    program,
-                    200,
-                    std::ptr::null_mut(),
-                    message.as_mut_ptr() as _,
-                );
-                assert!(message.len() == 0, "Failed to link: {}", message);
+        let (program, ubo) = match program {
+            Some(program) => {
+                println!("Shaders are loaded from SPIR-V binaries.");
+
+                // SPIR-V has no loose uniforms, u_angle_y lives in a uniform block instead
+                let ubo = unsafe {
+                    let mut ubo = 0;
+                    gl::CreateBuffers(1, &mut ubo);
+                    gl::BindBuffer(gl::UNIFORM_BUFFER, ubo);
+                    gl::BufferData(gl::UNIFORM_BUFFER, 16, std::ptr::null(), gl::DYNAMIC_DRAW);
+                    gl::BindBufferBase(gl::UNIFORM_BUFFER, MY_UNIFORM_BLOCK_BINDING, ubo);
+                    ubo
+                };
+                (program, Some(ubo))
+            }
+            None => {
+                println!("Shaders are compiled from GLSL sources.");
+                let program = ShaderProgram::new(&[
+                    (gl::VERTEX_SHADER, MY_VERTEX_SHADER_SOURCE),
+                    (gl::FRAGMENT_SHADER, MY_FRAGMENT_SHADER_SOURCE),
+                ])
+                .unwrap();
+                (program, None)
             }
-            shaders.iter().for_each(|shader| {
-                gl::DetachShader(program, *shader);
-                gl::DeleteShader(*shader);
-            });
-            program
         };
 
         let (vbo, vao) = unsafe {
@@ -138,15 +169,64 @@ impl MyApp {
             (vbo, vao)
         };
 
+        // drawn with DrawArrays, so a mesh is unrolled into its triangles
+        let vertices = match mesh {
+            Some(mesh) => MyVertex::from_mesh(mesh),
+            None => MY_VERTEX_DATA.to_vec(),
+        };
+
         Self {
             program,
             vao,
             vbo,
+            ubo,
+            vertices,
             angle_y: 0.0,
+            shader_watcher: None,
+        }
+    }
+
+    fn watch_shaders(&mut self, paths: &[&str]) {
+        println!("watching {}", paths.join(", "));
+        self.shader_watcher = Some(ShaderWatcher::new(paths));
+    }
+
+    fn reload_shaders(&mut self) {
+        let Some(shader_watcher) = &self.shader_watcher else {
+            return;
+        };
+        let sources = (0..2)
+            .map(|i| shader_watcher.read(i))
+            .collect::<Result<Vec<_>, _>>();
+        let sources = match sources {
+            Ok(sources) => sources,
+            Err(err) => {
+                eprintln!("{err}");
+                return;
+            }
+        };
+
+        // keep the last good program on errors
+        let program = ShaderProgram::new(&[
+            (gl::VERTEX_SHADER, sources[0].as_str()),
+            (gl::FRAGMENT_SHADER, sources[1].as_str()),
+        ]);
+        match program {
+            Ok(program) => {
+                println!("shaders reloaded");
+                self.program = program;
+            }
+            Err(err) => eprintln!("{err}"),
         }
     }
 
     fn update(&mut self) {
+        if let Some(shader_watcher) = &mut self.shader_watcher {
+            if shader_watcher.changed() {
+                self.reload_shaders();
+            }
+        }
+
         self.angle_y += std::f32::consts::PI / 60.0;
     }
 
@@ -155,20 +235,28 @@ impl MyApp {
             gl::ClearColor(0.2, 0.2, 0.2, 1.0);
             gl::Clear(gl::COLOR_BUFFER_BIT);
         }
+        self.program.use_program();
+        match self.ubo {
+            Some(ubo) => unsafe {
+                gl::BindBuffer(gl::UNIFORM_BUFFER, ubo);
+                gl::BufferSubData(
+                    gl::UNIFORM_BUFFER,
+                    0,
+                    std::mem::size_of::<f32>() as isize,
+                    [self.angle_y].as_ptr() as _,
+                );
+            },
+            None => self.program.set_uniform("u_angle_y", self.angle_y),
+        }
         unsafe {
-            gl::UseProgram(self.program);
-            gl::Uniform1f(
-                gl::GetUniformLocation(self.program, "u_angle_y".as_ptr() as _),
-                self.angle_y,
-            );
             gl::BufferData(
                 gl::ARRAY_BUFFER,
-                (MY_VERTEX_DATA.len() * core::mem::size_of::<MyVertex>()) as isize,
-                MY_VERTEX_DATA.as_ptr() as _,
+                (self.vertices.len() * core::mem::size_of::<MyVertex>()) as isize,
+                self.vertices.as_ptr() as _,
                 gl::STATIC_DRAW,
             );
             gl::BindVertexArray(self.vao);
-            gl::DrawArrays(gl::TRIANGLES, 0, 3);
+            gl::DrawArrays(gl::TRIANGLES, 0, self.vertices.len() as i32);
         }
     }
 }
@@ -176,18 +264,37 @@ impl MyApp {
 impl Drop for MyApp {
     fn drop(&mut self) {
         unsafe {
-            gl::DeleteProgram(self.program);
             gl::DeleteVertexArrays(1, [self.vao].as_ptr());
             gl::DeleteBuffers(1, [self.vbo].as_ptr());
+            if let Some(ubo) = self.ubo {
+                gl::DeleteBuffers(1, [ubo].as_ptr());
+            }
         }
     }
 }
 
+#[derive(Clone, Copy)]
 struct MyVertex {
     pos: [f32; 2],
     col: [f32; 3],
 }
 
+impl MyVertex {
+    // The triangles of a mesh file, colored by where they are.
+    fn from_mesh(mut mesh: MeshFile) -> Vec<MyVertex> {
+        mesh.fit_to_clip_space();
+        (mesh.indices.iter())
+            .map(|&index| {
+                let pos = mesh.positions[index as usize];
+                MyVertex {
+                    pos,
+                    col: MeshFile::color(pos),
+                }
+            })
+            .collect()
+    }
+}
+
 static MY_VERTEX_DATA: [MyVertex; 3] = [
     MyVertex {
         pos: [0.8, 0.0],
@@ -203,34 +310,13 @@ static MY_VERTEX_DATA: [MyVertex; 3] = [
     },
 ];
 
-use const_format::formatcp;
 const MY_VERTEX_DATA_POS: u32 = 0;
 const MY_VERTEX_DATA_COL: u32 = 1;
-const MY_VERTEX_SHADER_SOURCE: &str = formatcp!(
-    r"
-    precision mediump float;
-    uniform float u_angle_y;
-    layout(location = {}) in vec2 in_pos;
-    layout(location = {}) in vec3 in_col;
-    out vec3 v_color;
-
-    void main() {{
-        gl_Position = vec4(in_pos, 0.0, 1.0);
-        gl_Position.x *= cos(u_angle_y);
-        v_color = in_col;
-    }}
-    ",
-    MY_VERTEX_DATA_POS,
-    MY_VERTEX_DATA_COL,
-);
-const MY_FRAGMENT_SHADER_SOURCE: &str = formatcp!(
-    r"
-    precision mediump float;
-    in vec3 v_color;
-    out vec4 f_color;
-
-    void main() {{
-        f_color = vec4(v_color, 1.0);
-    }}
-    ",
-);
+const MY_VERTEX_SHADER_SOURCE: &str = include_str!("shader.vert");
+const MY_FRAGMENT_SHADER_SOURCE: &str = include_str!("shader.frag");
+const MY_VERTEX_SHADER_BINARY: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/shader.vert.spv"));
+const MY_FRAGMENT_SHADER_BINARY: &[u8] =
+    include_bytes!(concat!(env!("OUT_DIR"), "/shader.frag.spv"));
+const MY_UNIFORM_BLOCK_BINDING: u32 = 0;
+const MY_VERTEX_SHADER_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/src/shader.vert");
+const MY_FRAGMENT_SHADER_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/src/shader.frag");
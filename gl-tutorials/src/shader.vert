@@ -0,0 +1,17 @@
+precision mediump float;
+#ifdef GL_SPIRV
+layout(std140, binding = 0) uniform MyUniforms {
+    float u_angle_y;
+};
+#else
+uniform float u_angle_y;
+#endif
+layout(location = 0) in vec2 in_pos;
+layout(location = 1) in vec3 in_col;
+out vec3 v_color;
+
+void main() {
+    gl_Position = vec4(in_pos, 0.0, 1.0);
+    gl_Position.x *= cos(u_angle_y);
+    v_color = in_col;
+}
@@ -1,5 +1,8 @@
 fn main() {
     use glfw::{Context, SwapInterval, WindowHint, WindowMode};
+    use tutorial_common::args::Args;
+
+    let args = Args::from_env(&["--watch"]);
 
     let mut glfw = glfw::init(glfw::fail_on_errors).unwrap();
     glfw.window_hint(WindowHint::ContextVersion(3, 3));
@@ -21,6 +24,9 @@ fn main() {
     }
 
     let mut my_app = MyApp::new(gl);
+    if args.watch {
+        my_app.watch_shaders(&[MY_VERTEX_SHADER_PATH, MY_FRAGMENT_SHADER_PATH]);
+    }
     let mut i = 0;
     window.set_all_polling(true);
     while !window.should_close() {
@@ -49,6 +55,7 @@ fn main() {
 
 use glow as gl;
 use glow::{Buffer, Context, HasContext, Program, VertexArray};
+use tutorial_common::shader_watcher::ShaderWatcher;
 
 struct MyApp {
     gl: Context,
@@ -56,49 +63,20 @@ struct MyApp {
     vao: VertexArray,
     vbo: Buffer,
     angle_y: f32,
+    shader_watcher: Option<ShaderWatcher>,
 }
 
 impl MyApp {
     fn new(gl: Context) -> Self {
-        let shader_version = if cfg!(target_arch = "wasm32") {
-            "#version 300 es"
-        } else {
-            "#version 330"
-        };
-        let shader_sources = [
-            (gl::VERTEX_SHADER, MY_VERTEX_SHADER_SOURCE),
-            (gl::FRAGMENT_SHADER, MY_FRAGMENT_SHADER_SOURCE),
-        ];
-
         let program = unsafe {
-            let program = gl.create_program().unwrap();
-            let shaders = shader_sources
-                .iter()
-                .map(|(shader_type, shader_source)| {
-                    let shader = gl.create_shader(*shader_type).unwrap();
-                    let shader_source = format!("{shader_version}\n{shader_source}");
-                    gl.shader_source(shader, shader_source.as_str());
-                    gl.compile_shader(shader);
-                    assert!(
-                        gl.get_shader_compile_status(shader),
-                        "Failed to compile {shader_type}: {}",
-                        gl.get_shader_info_log(shader)
-                    );
-                    gl.attach_shader(program, shader);
-                    shader
-                })
-                .collect::<Vec<_>>();
-            gl.link_program(program);
-            assert!(
-                gl.get_program_link_status(program),
-                "Failed to link: {}",
-                gl.get_program_info_log(program)
-            );
-            shaders.iter().for_each(|shader| {
-                gl.detach_shader(program, *shader);
-                gl.delete_shader(*shader);
-            });
-            program
+            create_program(
+                &gl,
+                &[
+                    (gl::VERTEX_SHADER, MY_VERTEX_SHADER_SOURCE),
+                    (gl::FRAGMENT_SHADER, MY_FRAGMENT_SHADER_SOURCE),
+                ],
+            )
+            .unwrap()
         };
 
         let (vbo, vao) = unsafe {
@@ -135,10 +113,60 @@ impl MyApp {
             vao,
             vbo,
             angle_y: 0.0,
+            shader_watcher: None,
+        }
+    }
+
+    fn watch_shaders(&mut self, paths: &[&str]) {
+        println!("watching {}", paths.join(", "));
+        self.shader_watcher = Some(ShaderWatcher::new(paths));
+    }
+
+    fn reload_shaders(&mut self) {
+        let Some(shader_watcher) = &self.shader_watcher else {
+            return;
+        };
+        let sources = (0..2)
+            .map(|i| shader_watcher.read(i))
+            .collect::<Result<Vec<_>, _>>();
+        let sources = match sources {
+            Ok(sources) => sources,
+            Err(err) => {
+                eprintln!("{err}");
+                return;
+            }
+        };
+
+        // keep the last good program on errors
+        let gl = &self.gl;
+        let program = unsafe {
+            create_program(
+                gl,
+                &[
+                    (gl::VERTEX_SHADER, sources[0].as_str()),
+                    (gl::FRAGMENT_SHADER, sources[1].as_str()),
+                ],
+            )
+        };
+        match program {
+            Ok(program) => {
+                println!("shaders reloaded");
+                unsafe {
+                    gl.delete_program(self.program);
+                }
+                self.program = program;
+            }
+            Err(err) => eprintln!("{err}"),
         }
     }
 
     fn update(&mut self) {
+        if let Some(shader_watcher) = &mut self.shader_watcher {
+            if shader_watcher.changed() {
+                self.reload_shaders();
+            }
+        }
+
         self.angle_y += std::f32::consts::PI / 60.0;
     }
 
@@ -165,6 +193,54 @@ impl MyApp {
     }
 }
 
+unsafe fn create_program(gl: &Context, shader_sources: &[(u32, &str)]) -> Result<Program, String> {
+    let shader_version = if cfg!(target_arch = "wasm32") {
+        "#version 300 es"
+    } else {
+        "#version 330"
+    };
+
+    let program = gl.create_program()?;
+    let mut shaders = vec![];
+    let mut result = Ok(());
+    for (shader_type, shader_source) in shader_sources {
+        let shader = gl.create_shader(*shader_type)?;
+        let shader_source = format!("{shader_version}\n{shader_source}");
+        gl.shader_source(shader, shader_source.as_str());
+        gl.compile_shader(shader);
+        gl.attach_shader(program, shader);
+        shaders.push(shader);
+        if !gl.get_shader_compile_status(shader) {
+            result = Err(format!(
+                "Failed to compile {shader_type}: {}",
+                gl.get_shader_info_log(shader)
+            ));
+            break;
+        }
+    }
+    if result.is_ok() {
+        gl.link_program(program);
+        if !gl.get_program_link_status(program) {
+            result = Err(format!(
+                "Failed to link: {}",
+                gl.get_program_info_log(program)
+            ));
+        }
+    }
+    shaders.iter().for_each(|shader| {
+        gl.detach_shader(program, *shader);
+        gl.delete_shader(*shader);
+    });
+
+    match result {
+        Ok(()) => Ok(program),
+        Err(err) => {
+            gl.delete_program(program);
+            Err(err)
+        }
+    }
+}
+
 impl Drop for MyApp {
     fn drop(&mut self) {
         let gl = &self.gl;
@@ -198,34 +274,9 @@ static MY_VERTEX_DATA: &[MyVertex] = &[
     },
 ];
 
-use const_format::formatcp;
 const MY_VERTEX_DATA_POS: u32 = 0;
 const MY_VERTEX_DATA_COL: u32 = 1;
-const MY_VERTEX_SHADER_SOURCE: &str = formatcp!(
-    r"
-    precision mediump float;
-    uniform float u_angle_y;
-    layout(location = {}) in vec2 in_pos;
-    layout(location = {}) in vec3 in_col;
-    out vec3 v_color;
-
-    void main() {{
-        gl_Position = vec4(in_pos, 0.0, 1.0);
-        gl_Position.x *= cos(u_angle_y);
-        v_color = in_col;
-    }}
-    ",
-    MY_VERTEX_DATA_POS,
-    MY_VERTEX_DATA_COL,
-);
-const MY_FRAGMENT_SHADER_SOURCE: &str = formatcp!(
-    r"
-    precision mediump float;
-    in vec3 v_color;
-    out vec4 f_color;
-
-    void main() {{
-        f_color = vec4(v_color, 1.0);
-    }}
-    ",
-);
+const MY_VERTEX_SHADER_SOURCE: &str = include_str!("shader.vert");
+const MY_FRAGMENT_SHADER_SOURCE: &str = include_str!("shader.frag");
+const MY_VERTEX_SHADER_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/src/shader.vert");
+const MY_FRAGMENT_SHADER_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/src/shader.frag");
@@ -0,0 +1,186 @@
+// Renders a few frames without a window, e.g. on Mesa's llvmpipe in CI.
+// The EGL device platform needs no display server, and the frames are drawn into a framebuffer
+// object so that it works both with a pbuffer surface and with a surfaceless context.
+use std::ffi::CString;
+
+use glutin::api::egl::device::Device;
+use glutin::api::egl::display::Display;
+use glutin::prelude::*;
+
+use crate::{print_context_report, shader_version, MyApp};
+
+// Returns how many pixels of the last frame are not the background.
+pub fn run(width: u32, height: u32) -> usize {
+    use glutin::config::{ConfigSurfaceTypes, ConfigTemplateBuilder};
+    use glutin::context::{ContextApi, ContextAttributesBuilder, Version};
+    use glutin::surface::{PbufferSurface, SurfaceAttributesBuilder};
+    use std::num::NonZeroU32;
+    use winit::dpi::PhysicalSize;
+
+    let devices = Device::query_devices()
+        .expect("EGL devices are not available")
+        .collect::<Vec<_>>();
+    for device in &devices {
+        println!("device: {:?} {:?}", device.name(), device.vendor());
+    }
+    let device = devices.first().expect("no EGL device");
+    let gl_display = unsafe { Display::with_device(device, None) }.unwrap();
+    println!(
+        "gl_display.version_string(): {:?}",
+        gl_display.version_string()
+    );
+
+    // any surface type, pbuffers are optional
+    let template = ConfigTemplateBuilder::new()
+        .with_surface_type(ConfigSurfaceTypes::empty())
+        .build();
+    let gl_config = unsafe { gl_display.find_configs(template) }
+        .unwrap()
+        .next()
+        .expect("no EGL config");
+    println!("gl_config: {:?}", gl_config);
+
+    let context_attributes = ContextAttributesBuilder::new().build(None);
+    let fallback_context_attributes = ContextAttributesBuilder::new()
+        .with_context_api(ContextApi::Gles(None))
+        .build(None);
+    let legacy_context_attributes = ContextAttributesBuilder::new()
+        .with_context_api(ContextApi::OpenGl(Some(Version::new(3, 3))))
+        .build(None);
+    let gl_context_not_current = unsafe {
+        gl_display
+            .create_context(&gl_config, &context_attributes)
+            .unwrap_or_else(|_| {
+                gl_display
+                    .create_context(&gl_config, &fallback_context_attributes)
+                    .unwrap_or_else(|_| {
+                        gl_display
+                            .create_context(&gl_config, &legacy_context_attributes)
+                            .expect("failed to create context")
+                    })
+            })
+    };
+
+    // the surface has to outlive the context being current
+    let (_gl_surface, gl_context) = if gl_config
+        .config_surface_types()
+        .contains(ConfigSurfaceTypes::PBUFFER)
+    {
+        println!("Rendering with a pbuffer surface.");
+        let attrs = SurfaceAttributesBuilder::<PbufferSurface>::new().build(
+            NonZeroU32::new(width).unwrap(),
+            NonZeroU32::new(height).unwrap(),
+        );
+        let gl_surface = unsafe { gl_display.create_pbuffer_surface(&gl_config, &attrs) }.unwrap();
+        let gl_context = gl_context_not_current.make_current(&gl_surface).unwrap();
+        (Some(gl_surface), gl_context)
+    } else {
+        println!("Rendering with a surfaceless context.");
+        let gl_context = gl_context_not_current.make_current_surfaceless().unwrap();
+        (None, gl_context)
+    };
+    gl::load_with(|symbol| {
+        let symbol = CString::new(symbol).unwrap();
+        gl_display.get_proc_address(symbol.as_c_str()).cast()
+    });
+    print_context_report(gl_context.context_api());
+
+    let framebuffer = Framebuffer::new(width as i32, height as i32);
+    let mut my_app = MyApp::new(shader_version(gl_context.context_api()));
+    my_app.resize(PhysicalSize::new(width, height), 1.0);
+    for _ in 0..MY_HEADLESS_FRAMES {
+        my_app.update();
+        my_app.render();
+    }
+
+    let pixels = framebuffer.read_pixels();
+    let background = [51, 51, 51, 255];
+    let covered = pixels
+        .chunks_exact(4)
+        .filter(|pixel| *pixel != background)
+        .count();
+    println!(
+        "{MY_HEADLESS_FRAMES} frames rendered, {covered} of {} pixels covered",
+        width * height
+    );
+    drop(my_app);
+    drop(framebuffer);
+    covered
+}
+
+// An RGBA8 color attachment, bound as the draw and read framebuffer while alive.
+struct Framebuffer {
+    fbo: u32,
+    rbo: u32,
+    width: i32,
+    height: i32,
+}
+
+impl Framebuffer {
+    fn new(width: i32, height: i32) -> Self {
+        unsafe {
+            let mut rbo = 0;
+            gl::GenRenderbuffers(1, &mut rbo);
+            gl::BindRenderbuffer(gl::RENDERBUFFER, rbo);
+            gl::RenderbufferStorage(gl::RENDERBUFFER, gl::RGBA8, width, height);
+
+            let mut fbo = 0;
+            gl::GenFramebuffers(1, &mut fbo);
+            gl::BindFramebuffer(gl::FRAMEBUFFER, fbo);
+            gl::FramebufferRenderbuffer(
+                gl::FRAMEBUFFER,
+                gl::COLOR_ATTACHMENT0,
+                gl::RENDERBUFFER,
+                rbo,
+            );
+            let status = gl::CheckFramebufferStatus(gl::FRAMEBUFFER);
+            assert_eq!(status, gl::FRAMEBUFFER_COMPLETE, "incomplete framebuffer");
+
+            Self {
+                fbo,
+                rbo,
+                width,
+                height,
+            }
+        }
+    }
+
+    fn read_pixels(&self) -> Vec<u8> {
+        let mut pixels = vec![0u8; (self.width * self.height * 4) as usize];
+        unsafe {
+            gl::Finish();
+            gl::ReadPixels(
+                0,
+                0,
+                self.width,
+                self.height,
+                gl::RGBA,
+                gl::UNSIGNED_BYTE,
+                pixels.as_mut_ptr() as _,
+            );
+        }
+        pixels
+    }
+}
+
+impl Drop for Framebuffer {
+    fn drop(&mut self) {
+        unsafe {
+            gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+            gl::DeleteFramebuffers(1, [self.fbo].as_ptr());
+            gl::DeleteRenderbuffers(1, [self.rbo].as_ptr());
+        }
+    }
+}
+
+const MY_HEADLESS_FRAMES: usize = 60;
+
+#[cfg(test)]
+mod tests {
+    // cargo test -p glutin-tutorials -- --ignored
+    #[test]
+    #[ignore = "needs an EGL device, such as Mesa's llvmpipe"]
+    fn renders_without_a_window() {
+        assert!(super::run(480, 320) > 0, "Nothing was drawn.");
+    }
+}
@@ -1,19 +1,48 @@
+#[cfg(not(any(target_os = "macos", target_os = "ios")))]
+mod headless;
+
 use std::ffi::CString;
 use std::num::NonZeroU32;
 
 use raw_window_handle::HasRawWindowHandle;
+use tutorial_common::args::Args;
+use tutorial_common::event_log::winit_0_29 as event_log;
+
+use event_log::Command;
 
 fn main() {
+    let args = Args::from_env(&["--play", "--record", "--headless"]);
+    if let Some(path) = &args.play {
+        let events = event_log::load(path).unwrap();
+        let played = event_log::play(&events, |event| {
+            let command = event.command();
+            println!("{:?} -> {:?}", event, command);
+            command != Some(Command::Exit)
+        });
+        println!("played {} of {} events", played, events.len());
+        return;
+    }
+    if args.headless {
+        #[cfg(not(any(target_os = "macos", target_os = "ios")))]
+        if headless::run(480, 320) == 0 {
+            eprintln!("Nothing was drawn.");
+            std::process::exit(1);
+        }
+        #[cfg(any(target_os = "macos", target_os = "ios"))]
+        eprintln!("The headless mode needs EGL.");
+        return;
+    }
+
     use glutin::config::ConfigTemplateBuilder;
     use glutin::prelude::*;
     use glutin_winit::DisplayBuilder;
-    use winit::dpi::PhysicalSize;
+    use winit::dpi::LogicalSize;
     use winit::event_loop::EventLoop;
     use winit::window::WindowBuilder;
 
     let event_queue = EventLoop::new().unwrap();
     let window_builder = WindowBuilder::new()
-        .with_inner_size(PhysicalSize::new(480.0, 320.0))
+        .with_inner_size(LogicalSize::new(480.0, 320.0))
         .with_title("glutin tutorial");
     let template = ConfigTemplateBuilder::new().prefer_hardware_accelerated(Some(true));
     let (window, gl_config) = DisplayBuilder::new()
@@ -86,53 +115,57 @@ fn main() {
     });
     println!("gl_surface: {:?}", gl_surface);
     println!("gl_context: {:?}", gl_context);
-    println!("gl_context.context_api(): {:?}", gl_context.context_api());
+    print_context_report(gl_context.context_api());
 
-    use winit::event::{Event, KeyEvent, WindowEvent};
-    use winit::keyboard::{Key, NamedKey};
+    let mut my_app = MyApp::new(shader_version(gl_context.context_api()));
+    my_app.resize(window.inner_size(), window.scale_factor());
+
+    use event_log::{EventRecorder, InputEvent};
+    use winit::event::{Event, WindowEvent};
+    use winit::event_loop::EventLoopWindowTarget;
 
     let mut i = 0;
-    _ = event_queue.run(move |event, window_target| match event {
-        Event::WindowEvent { event, .. } => match event {
-            WindowEvent::CloseRequested
-            | WindowEvent::KeyboardInput {
-                event:
-                    KeyEvent {
-                        logical_key: Key::Named(NamedKey::Escape),
-                        ..
-                    },
-                ..
-            } => {
-                window_target.exit();
+    let handler = move |event: Event<()>, window_target: &EventLoopWindowTarget<()>| match event {
+        Event::WindowEvent {
+            event: WindowEvent::RedrawRequested,
+            ..
+        } => {
+            my_app.update();
+            my_app.render();
+            gl_surface.swap_buffers(&gl_context).unwrap();
+
+            if i % 60 == 0 {
+                println!("loop {}", i as f32 / 60.0);
             }
-            WindowEvent::Resized(size) => {
-                if size.width != 0 && size.height != 0 {
-                    gl_surface.resize(
-                        &gl_context,
-                        NonZeroU32::new(size.width).unwrap(),
-                        NonZeroU32::new(size.height).unwrap(),
-                    );
-                    // my_app.resize(size.width as i32, size.height as i32);
+            i += 1;
+        }
+        Event::WindowEvent { event, .. } => {
+            match InputEvent::from_window_event(&event).and_then(|event| event.command()) {
+                Some(Command::Exit) => {
+                    window_target.exit();
                 }
-            }
-            WindowEvent::RedrawRequested => {
-                // my_app.update();
-                // my_app.render();
-                // unsafe {
-                //     gl::ClearColor(0.2, 0.2, 0.2, 1.0);
-                //     gl::Clear(gl::COLOR_BUFFER_BIT);
-                // }
-                gl_surface.swap_buffers(&gl_context).unwrap();
-
-                if i % 60 == 0 {
-                    println!("loop {}", i as f32 / 60.0);
+                Some(Command::Resize(size)) => {
+                    if let (Some(width), Some(height)) =
+                        (NonZeroU32::new(size.width), NonZeroU32::new(size.height))
+                    {
+                        gl_surface.resize(&gl_context, width, height);
+                        my_app.resize(size, window.scale_factor());
+                    }
+                }
+                Some(Command::Rescale(scale_factor)) => {
+                    let size = window.inner_size();
+                    if let (Some(width), Some(height)) =
+                        (NonZeroU32::new(size.width), NonZeroU32::new(size.height))
+                    {
+                        gl_surface.resize(&gl_context, width, height);
+                        my_app.resize(size, scale_factor);
+                    }
+                }
+                None => {
+                    println!("{:?}", event);
                 }
-                i += 1;
-            }
-            _ => {
-                println!("{:?}", event);
             }
-        },
+        }
         Event::Resumed | Event::Suspended => {
             println!("{:?}", event);
         }
@@ -144,5 +177,175 @@ fn main() {
         _ => {
             println!("{:?}", event);
         }
-    });
+    };
+    _ = if let Some(path) = &args.record {
+        println!("recording to {}", path);
+        let recorder = EventRecorder::create(path).unwrap();
+        event_queue.run(recorder.wrap(handler))
+    } else {
+        event_queue.run(handler)
+    };
+}
+
+use glutin::context::ContextApi;
+use tutorial_common::shader_program::ShaderProgram;
+use winit::dpi::PhysicalSize;
+
+// The context may be GLES even when GL was asked for, so the version line follows the actual API.
+fn shader_version(context_api: ContextApi) -> &'static str {
+    match context_api {
+        ContextApi::OpenGl(_) => "#version 330",
+        ContextApi::Gles(_) => "#version 300 es",
+    }
 }
+
+// The context must be current.
+fn print_context_report(context_api: ContextApi) {
+    let get_string = |name| unsafe {
+        let string = gl::GetString(name);
+        if string.is_null() {
+            return String::from("(null)");
+        }
+        std::ffi::CStr::from_ptr(string.cast())
+            .to_string_lossy()
+            .into_owned()
+    };
+    println!("context api: {:?}", context_api);
+    println!("GL_VERSION: {}", get_string(gl::VERSION));
+    println!("GL_RENDERER: {}", get_string(gl::RENDERER));
+    println!("GL_VENDOR: {}", get_string(gl::VENDOR));
+    println!(
+        "GL_SHADING_LANGUAGE_VERSION: {}",
+        get_string(gl::SHADING_LANGUAGE_VERSION)
+    );
+    println!("shader version: {}", shader_version(context_api));
+}
+
+struct MyApp {
+    program: ShaderProgram,
+    vao: u32,
+    vbo: u32,
+    angle_y: f32,
+}
+
+impl MyApp {
+    fn new(shader_version: &str) -> Self {
+        let program = ShaderProgram::with_version(
+            shader_version,
+            &[
+                (gl::VERTEX_SHADER, MY_VERTEX_SHADER_SOURCE),
+                (gl::FRAGMENT_SHADER, MY_FRAGMENT_SHADER_SOURCE),
+            ],
+        )
+        .unwrap();
+
+        // GenBuffers instead of CreateBuffers, GLES 3.0 and GL 3.3 have no DSA
+        let (vbo, vao) = unsafe {
+            let mut vbo = 0;
+            gl::GenBuffers(1, &mut vbo);
+            gl::BindBuffer(gl::ARRAY_BUFFER, vbo);
+            gl::BufferData(
+                gl::ARRAY_BUFFER,
+                (MY_VERTEX_DATA.len() * core::mem::size_of::<MyVertex>()) as isize,
+                MY_VERTEX_DATA.as_ptr() as _,
+                gl::STATIC_DRAW,
+            );
+
+            let mut vao = 0;
+            gl::GenVertexArrays(1, &mut vao);
+            gl::BindVertexArray(vao);
+
+            gl::EnableVertexAttribArray(MY_VERTEX_DATA_POS);
+            gl::VertexAttribPointer(
+                MY_VERTEX_DATA_POS,
+                2,
+                gl::FLOAT,
+                gl::FALSE,
+                std::mem::size_of::<MyVertex>() as i32,
+                std::mem::offset_of!(MyVertex, pos) as *const std::ffi::c_void,
+            );
+            gl::EnableVertexAttribArray(MY_VERTEX_DATA_COL);
+            gl::VertexAttribPointer(
+                MY_VERTEX_DATA_COL,
+                3,
+                gl::FLOAT,
+                gl::FALSE,
+                std::mem::size_of::<MyVertex>() as i32,
+                std::mem::offset_of!(MyVertex, col) as *const std::ffi::c_void,
+            );
+            (vbo, vao)
+        };
+
+        Self {
+            program,
+            vao,
+            vbo,
+            angle_y: 0.0,
+        }
+    }
+
+    // The viewport is in physical pixels. The triangle is in clip space, so the scale factor
+    // does not change how it looks.
+    fn resize(&mut self, size: PhysicalSize<u32>, scale_factor: f64) {
+        let logical_size = size.to_logical::<f64>(scale_factor);
+        println!(
+            "viewport: {}x{} physical, {}x{} logical, scale factor {}",
+            size.width, size.height, logical_size.width, logical_size.height, scale_factor
+        );
+        unsafe {
+            gl::Viewport(0, 0, size.width as i32, size.height as i32);
+        }
+    }
+
+    fn update(&mut self) {
+        self.angle_y += std::f32::consts::PI / 60.0;
+    }
+
+    fn render(&mut self) {
+        unsafe {
+            gl::ClearColor(0.2, 0.2, 0.2, 1.0);
+            gl::Clear(gl::COLOR_BUFFER_BIT);
+        }
+        self.program.use_program();
+        self.program.set_uniform("u_angle_y", self.angle_y);
+        unsafe {
+            gl::BindVertexArray(self.vao);
+            gl::DrawArrays(gl::TRIANGLES, 0, 3);
+        }
+    }
+}
+
+impl Drop for MyApp {
+    fn drop(&mut self) {
+        unsafe {
+            gl::DeleteVertexArrays(1, [self.vao].as_ptr());
+            gl::DeleteBuffers(1, [self.vbo].as_ptr());
+        }
+    }
+}
+
+#[repr(C)]
+struct MyVertex {
+    pos: [f32; 2],
+    col: [f32; 3],
+}
+
+static MY_VERTEX_DATA: [MyVertex; 3] = [
+    MyVertex {
+        pos: [0.8, 0.0],
+        col: [1.0, 0.0, 0.0],
+    },
+    MyVertex {
+        pos: [0.0, 0.8],
+        col: [0.0, 1.0, 0.0],
+    },
+    MyVertex {
+        pos: [-0.8, -0.8],
+        col: [0.0, 0.0, 1.0],
+    },
+];
+
+const MY_VERTEX_DATA_POS: u32 = 0;
+const MY_VERTEX_DATA_COL: u32 = 1;
+const MY_VERTEX_SHADER_SOURCE: &str = include_str!("shader.vert");
+const MY_FRAGMENT_SHADER_SOURCE: &str = include_str!("shader.frag");
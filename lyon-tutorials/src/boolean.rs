@@ -0,0 +1,543 @@
+use lyon::math::{Point, Vector};
+use lyon::path::iterator::PathIterator;
+use lyon::path::{FillRule, Path, PathEvent};
+use std::collections::HashMap;
+
+// How close, along a segment, an intersection must be to an end to be taken as that end.
+const MY_SNAP: f32 = 1e-5;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MyOperation {
+    Union,
+    Intersection,
+    Difference,
+    Xor,
+}
+
+impl MyOperation {
+    pub const ALL: [MyOperation; 4] = [
+        MyOperation::Union,
+        MyOperation::Intersection,
+        MyOperation::Difference,
+        MyOperation::Xor,
+    ];
+
+    fn keeps(self, in_a: bool, in_b: bool) -> bool {
+        match self {
+            MyOperation::Union => in_a || in_b,
+            MyOperation::Intersection => in_a && in_b,
+            MyOperation::Difference => in_a && !in_b,
+            MyOperation::Xor => in_a != in_b,
+        }
+    }
+}
+
+// A segment of the flattened paths, and how many times each path goes along it. Going from
+// its left side to its right side, the winding number of A drops by `windings[0]` and the one
+// of B by `windings[1]`.
+struct MyEdge {
+    from: usize,
+    to: usize,
+    windings: [i16; 2],
+}
+
+// Combines the areas that the paths fill with the fill rule. Both are flattened with the
+// tolerance, cut where they cross, and the pieces that separate the result from the rest are
+// linked into closed polygons, the inside on their left. The result fills the same with
+// either fill rule.
+pub fn boolean(
+    a: &Path,
+    b: &Path,
+    operation: MyOperation,
+    fill_rule: FillRule,
+    tolerance: f32,
+) -> Path {
+    let mut segments = vec![];
+    flatten(a, 0, tolerance, &mut segments);
+    flatten(b, 1, tolerance, &mut segments);
+    let (points, edges) = split(&segments);
+
+    // the pieces between the result and the rest, turned so that the result is on the left
+    let mut outgoing: HashMap<usize, Vec<usize>> = HashMap::new();
+    for (index, edge) in edges.iter().enumerate() {
+        let (left, right) = windings(&points, &edges, index);
+        let inside = |winding: [i16; 2]| {
+            operation.keeps(fill_rule.is_in(winding[0]), fill_rule.is_in(winding[1]))
+        };
+        match (inside(left), inside(right)) {
+            (true, false) => outgoing.entry(edge.from).or_default().push(edge.to),
+            (false, true) => outgoing.entry(edge.to).or_default().push(edge.from),
+            _ => {}
+        }
+    }
+
+    // every point has as many pieces in as out, so following them always comes back
+    let mut builder = Path::builder();
+    let mut starts = outgoing.keys().copied().collect::<Vec<_>>();
+    starts.sort_unstable();
+    for start in starts {
+        while let Some(next) = outgoing.get_mut(&start).and_then(Vec::pop) {
+            builder.begin(points[start]);
+            let mut at = next;
+            while at != start {
+                builder.line_to(points[at]);
+                let Some(next) = outgoing.get_mut(&at).and_then(Vec::pop) else {
+                    break;
+                };
+                at = next;
+            }
+            builder.end(true);
+        }
+    }
+    builder.build()
+}
+
+// The area that the path fills with the fill rule.
+#[cfg(test)]
+pub fn area(path: &Path, fill_rule: FillRule, tolerance: f32) -> f32 {
+    let empty = Path::new();
+    let filled = boolean(path, &empty, MyOperation::Union, fill_rule, tolerance);
+    signed_area(&filled, tolerance)
+}
+
+// The sum of the areas of the sub-paths, positive when counter-clockwise with y up, each one
+// closed as a fill would.
+#[cfg(test)]
+pub fn signed_area(path: &Path, tolerance: f32) -> f32 {
+    let mut segments = vec![];
+    flatten(path, 0, tolerance, &mut segments);
+    let sum: f32 = segments.iter().map(|(a, b, _)| a.x * b.y - b.x * a.y).sum();
+    sum / 2.0
+}
+
+// The segments of the flattened path with the index of the path, every sub-path closed.
+fn flatten(path: &Path, source: usize, tolerance: f32, segments: &mut Vec<(Point, Point, usize)>) {
+    for event in path.iter().flattened(tolerance) {
+        match event {
+            PathEvent::Line { from, to } if from != to => segments.push((from, to, source)),
+            PathEvent::End { last, first, .. } if last != first => {
+                segments.push((last, first, source))
+            }
+            _ => {}
+        }
+    }
+}
+
+// Cuts the segments where they cross or touch, and merges the pieces that lie on each other.
+// Pieces share the points where they meet, so that they can be linked.
+fn split(segments: &[(Point, Point, usize)]) -> (Vec<Point>, Vec<MyEdge>) {
+    let mut cuts: Vec<Vec<(f32, Point)>> = vec![vec![]; segments.len()];
+
+    // only the segments whose x ranges overlap can cross
+    let mut order = (0..segments.len()).collect::<Vec<_>>();
+    let min_x = |i: usize| segments[i].0.x.min(segments[i].1.x);
+    let max_x = |i: usize| segments[i].0.x.max(segments[i].1.x);
+    order.sort_by(|&i, &j| min_x(i).total_cmp(&min_x(j)));
+    for (n, &i) in order.iter().enumerate() {
+        for &j in &order[n + 1..] {
+            if min_x(j) > max_x(i) {
+                break;
+            }
+            let (a1, a2, _) = segments[i];
+            let (b1, b2, _) = segments[j];
+            for (t, u, point) in intersections(a1, a2, b1, b2) {
+                cuts[i].push((t, point));
+                cuts[j].push((u, point));
+            }
+        }
+    }
+
+    let mut points = vec![];
+    let mut ids = HashMap::new();
+    let mut id = |point: Point| {
+        *ids.entry((point.x.to_bits(), point.y.to_bits()))
+            .or_insert_with(|| {
+                points.push(point);
+                points.len() - 1
+            })
+    };
+    let mut merged: HashMap<(usize, usize), [i16; 2]> = HashMap::new();
+    for (segment, mut cuts) in segments.iter().zip(cuts) {
+        let (from, to, source) = *segment;
+        cuts.push((0.0, from));
+        cuts.push((1.0, to));
+        cuts.sort_by(|a, b| a.0.total_cmp(&b.0));
+        for pair in cuts.windows(2) {
+            let (from, to) = (id(pair[0].1), id(pair[1].1));
+            if from == to {
+                continue;
+            }
+            // pieces are kept from the lower point to the higher one
+            let (key, sign) = if from < to {
+                ((from, to), 1)
+            } else {
+                ((to, from), -1)
+            };
+            merged.entry(key).or_default()[source] += sign;
+        }
+    }
+    let mut edges = merged
+        .into_iter()
+        .filter(|(_, windings)| *windings != [0, 0])
+        .map(|((from, to), windings)| MyEdge { from, to, windings })
+        .collect::<Vec<_>>();
+    // the same result whatever the order of the hash map
+    edges.sort_unstable_by_key(|edge| (edge.from, edge.to));
+    (points, edges)
+}
+
+// Where two segments meet, as the position along each and the point. Points within MY_SNAP of
+// an end are that end, so that touching segments are cut at the same point.
+fn intersections(a1: Point, a2: Point, b1: Point, b2: Point) -> Vec<(f32, f32, Point)> {
+    let da = a2 - a1;
+    let db = b2 - b1;
+    let denominator = da.cross(db);
+    let along = |p: Point, from: Point, d: Vector| (p - from).dot(d) / d.square_length();
+    let close = |t: f32| (-MY_SNAP..=1.0 + MY_SNAP).contains(&t);
+
+    if denominator.abs() <= f32::EPSILON * da.length() * db.length() {
+        // parallel: if they lie on each other, each is cut at the ends of the other
+        let distance = (b1 - a1).cross(da).abs() / da.length();
+        if distance > MY_SNAP * da.length().max(db.length()) {
+            return vec![];
+        }
+        let mut found = vec![];
+        for end in [b1, b2] {
+            let t = along(end, a1, da);
+            if close(t) {
+                found.push((t.clamp(0.0, 1.0), along(end, b1, db), end));
+            }
+        }
+        for end in [a1, a2] {
+            let u = along(end, b1, db);
+            if close(u) {
+                found.push((along(end, a1, da), u.clamp(0.0, 1.0), end));
+            }
+        }
+        return found;
+    }
+
+    let t = (b1 - a1).cross(db) / denominator;
+    let u = (b1 - a1).cross(da) / denominator;
+    if !close(t) || !close(u) {
+        return vec![];
+    }
+    let snap = |t: f32, from: Point, to: Point| {
+        if t <= MY_SNAP {
+            Some(from)
+        } else if t >= 1.0 - MY_SNAP {
+            Some(to)
+        } else {
+            None
+        }
+    };
+    let point = snap(t, a1, a2).or(snap(u, b1, b2)).unwrap_or(a1 + da * t);
+    vec![(t.clamp(0.0, 1.0), u.clamp(0.0, 1.0), point)]
+}
+
+// The winding numbers of A and B on the left and on the right of an edge. A ray from its middle
+// crosses the other edges; it goes along x for the steep edges and along y for the others.
+fn windings(points: &[Point], edges: &[MyEdge], index: usize) -> ([i16; 2], [i16; 2]) {
+    let edge = &edges[index];
+    let (from, to) = (points[edge.from], points[edge.to]);
+    let middle = from.lerp(to, 0.5);
+    let direction = to - from;
+    let along_x = direction.y.abs() >= direction.x.abs();
+
+    // the winding numbers on the side the ray goes to
+    let mut winding = [0i16; 2];
+    for (other_index, other) in edges.iter().enumerate() {
+        if other_index == index {
+            continue;
+        }
+        let (a, b) = (points[other.from], points[other.to]);
+        // with the axes swapped, a ray along y is a ray along x
+        let (p, a, b) = if along_x {
+            (middle, a, b)
+        } else {
+            (middle.yx(), a.yx(), b.yx())
+        };
+        if (a.y <= p.y) == (b.y <= p.y) {
+            continue;
+        }
+        let x = a.x + (p.y - a.y) / (b.y - a.y) * (b.x - a.x);
+        if x > p.x {
+            // an edge going up, y up, has its inside on the left, the side the ray comes from;
+            // swapping the axes mirrors the edges, which turns that around
+            let sign = if (b.y > a.y) == along_x { 1 } else { -1 };
+            winding[0] += sign * other.windings[0];
+            winding[1] += sign * other.windings[1];
+        }
+    }
+
+    let ray = if along_x {
+        Vector::new(1.0, 0.0)
+    } else {
+        Vector::new(0.0, 1.0)
+    };
+    let ray_goes_left = direction.cross(ray) > 0.0;
+    let other = |winding: [i16; 2], sign: i16| {
+        [
+            winding[0] + sign * edge.windings[0],
+            winding[1] + sign * edge.windings[1],
+        ]
+    };
+    if ray_goes_left {
+        (winding, other(winding, -1))
+    } else {
+        (other(winding, 1), winding)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lyon::math::{point, vector, Box2D};
+    use lyon::path::Winding;
+
+    const MY_TOLERANCE: f32 = 0.01;
+    // relative to the areas of A and B
+    const MY_MAX_ERROR: f32 = 1e-3;
+    const MY_FILL_RULES: [FillRule; 2] = [FillRule::EvenOdd, FillRule::NonZero];
+
+    fn polygon(points: &[Point]) -> Path {
+        let mut builder = Path::builder();
+        builder.begin(points[0]);
+        for &p in &points[1..] {
+            builder.line_to(p);
+        }
+        builder.end(true);
+        builder.build()
+    }
+
+    fn square(min: Point, size: f32, winding: Winding) -> Path {
+        let mut builder = Path::builder();
+        builder.add_rectangle(&Box2D::new(min, min + vector(size, size)), winding);
+        builder.build()
+    }
+
+    fn paths(paths: &[&Path]) -> Path {
+        let mut builder = Path::builder();
+        for path in paths {
+            for event in path.iter() {
+                builder.path_event(event);
+            }
+        }
+        builder.build()
+    }
+
+    // The areas of A∪B, A∩B, A∖B and A xor B.
+    fn areas(a: &Path, b: &Path, fill_rule: FillRule) -> [f32; 4] {
+        MyOperation::ALL.map(|operation| {
+            let result = boolean(a, b, operation, fill_rule, MY_TOLERANCE);
+            signed_area(&result, MY_TOLERANCE)
+        })
+    }
+
+    // A∪B + A∩B = A + B, A∖B = A - A∩B and A xor B = A∪B - A∩B. Returns the areas of A∪B and
+    // A∩B.
+    fn assert_areas_add_up(a: &Path, b: &Path, fill_rule: FillRule) -> (f32, f32) {
+        let area_a = area(a, fill_rule, MY_TOLERANCE);
+        let area_b = area(b, fill_rule, MY_TOLERANCE);
+        let [union, intersection, difference, xor] = areas(a, b, fill_rule);
+        let max_error = MY_MAX_ERROR * (area_a + area_b).max(1.0);
+        let context =
+            format!("{fill_rule:?}: A {area_a}, B {area_b}, A∪B {union}, A∩B {intersection}");
+        assert!(
+            (union + intersection - area_a - area_b).abs() <= max_error,
+            "A∪B + A∩B = A + B, {context}"
+        );
+        assert!(
+            (difference - (area_a - intersection)).abs() <= max_error,
+            "A∖B = A - A∩B, {context}, A∖B {difference}"
+        );
+        assert!(
+            (xor - (union - intersection)).abs() <= max_error,
+            "A xor B = A∪B - A∩B, {context}, A xor B {xor}"
+        );
+        (union, intersection)
+    }
+
+    fn assert_near(actual: f32, expected: f32, what: &str) {
+        let max_error = MY_MAX_ERROR * expected.abs().max(1.0);
+        assert!(
+            (actual - expected).abs() <= max_error,
+            "{what}: {actual}, expected {expected}"
+        );
+    }
+
+    #[test]
+    fn squares_at_offsets() {
+        // 10 by 10 squares, B moved along x and then along the diagonal; they overlap below an
+        // offset of 10, touch at 10 and are apart beyond it
+        let a = square(point(0.0, 0.0), 10.0, Winding::Positive);
+        for offset in [0.0, 2.5, 5.0, 7.5, 10.0, 12.5, 30.0] {
+            for (direction, winding) in [
+                (vector(1.0, 0.0), Winding::Positive),
+                (vector(1.0, 1.0), Winding::Negative),
+            ] {
+                let b = square(point(0.0, 0.0) + direction * offset, 10.0, winding);
+                let overlap = (10.0 - offset).max(0.0) * (10.0 - offset * direction.y).max(0.0);
+                for fill_rule in MY_FILL_RULES {
+                    let (union, intersection) = assert_areas_add_up(&a, &b, fill_rule);
+                    let what = format!("{offset} along {direction:?}, {fill_rule:?}");
+                    assert_near(intersection, overlap, &what);
+                    assert_near(union, 200.0 - overlap, &what);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn nested() {
+        let outer = square(point(0.0, 0.0), 10.0, Winding::Positive);
+        for winding in [Winding::Positive, Winding::Negative] {
+            let inner = square(point(2.0, 3.0), 5.0, winding);
+            for fill_rule in MY_FILL_RULES {
+                let [union, intersection, difference, xor] = areas(&outer, &inner, fill_rule);
+                assert_areas_add_up(&outer, &inner, fill_rule);
+                assert_near(union, 100.0, "union");
+                assert_near(intersection, 25.0, "intersection");
+                assert_near(difference, 75.0, "difference");
+                assert_near(xor, 75.0, "xor");
+                // the other way around, nothing of the inner square is left
+                let [_, _, difference, _] = areas(&inner, &outer, fill_rule);
+                assert_near(difference, 0.0, "inner minus outer");
+            }
+        }
+    }
+
+    #[test]
+    fn fill_rules_differ_on_overlapping_sub_paths() {
+        let outer = square(point(0.0, 0.0), 10.0, Winding::Positive);
+        // turning the same way, the inside of the inner square winds twice: non-zero fills it,
+        // even-odd makes it a hole
+        let same = paths(&[&outer, &square(point(2.0, 3.0), 5.0, Winding::Positive)]);
+        assert_near(
+            area(&same, FillRule::NonZero, MY_TOLERANCE),
+            100.0,
+            "non-zero",
+        );
+        assert_near(
+            area(&same, FillRule::EvenOdd, MY_TOLERANCE),
+            75.0,
+            "even-odd",
+        );
+        // turning the other way, it is a hole with both
+        let opposite = paths(&[&outer, &square(point(2.0, 3.0), 5.0, Winding::Negative)]);
+        for fill_rule in MY_FILL_RULES {
+            assert_near(area(&opposite, fill_rule, MY_TOLERANCE), 75.0, "opposite");
+        }
+
+        // a star that crosses itself has a center that winds twice
+        let star = polygon(&[
+            point(0.0, 0.0),
+            point(30.0, 0.0),
+            point(30.0, 20.0),
+            point(10.0, 20.0),
+            point(10.0, 10.0),
+            point(40.0, 10.0),
+            point(40.0, 30.0),
+            point(0.0, 30.0),
+        ]);
+        let b = square(point(15.0, 5.0), 10.0, Winding::Positive);
+        for fill_rule in MY_FILL_RULES {
+            assert_areas_add_up(&star, &b, fill_rule);
+            assert_areas_add_up(&same, &b, fill_rule);
+        }
+        assert!(
+            area(&star, FillRule::NonZero, MY_TOLERANCE)
+                > area(&star, FillRule::EvenOdd, MY_TOLERANCE)
+        );
+    }
+
+    #[test]
+    fn circles() {
+        use std::f32::consts::PI;
+
+        let circle = |x: f32, radius: f32| {
+            let mut builder = Path::builder();
+            builder.add_circle(point(x, 0.0), radius, Winding::Positive);
+            builder.build()
+        };
+        let a = circle(0.0, 20.0);
+        for fill_rule in MY_FILL_RULES {
+            // flattened, the circles are a little smaller
+            let area_a = area(&a, fill_rule, MY_TOLERANCE);
+            assert!((area_a - PI * 400.0).abs() < 0.01 * PI * 400.0, "{area_a}");
+            for (b, intersection) in [
+                (circle(0.0, 10.0), Some(PI * 100.0)),
+                (circle(50.0, 10.0), Some(0.0)),
+                (circle(25.0, 10.0), None),
+                (circle(5.0, 20.0), None),
+            ] {
+                let (_, actual) = assert_areas_add_up(&a, &b, fill_rule);
+                if let Some(intersection) = intersection {
+                    assert!((actual - intersection).abs() < 0.01 * area_a, "{actual}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn with_nothing() {
+        let a = square(point(0.0, 0.0), 10.0, Winding::Positive);
+        let empty = Path::new();
+        for fill_rule in MY_FILL_RULES {
+            let [union, intersection, difference, xor] = areas(&a, &empty, fill_rule);
+            assert_eq!(
+                [union, intersection, difference, xor],
+                [100.0, 0.0, 100.0, 100.0]
+            );
+            let [union, intersection, difference, xor] = areas(&empty, &a, fill_rule);
+            assert_eq!(
+                [union, intersection, difference, xor],
+                [100.0, 0.0, 0.0, 100.0]
+            );
+        }
+    }
+
+    #[test]
+    fn random_polygons() {
+        // the same polygons on every run
+        let mut seed = 0x2545_f491_u32;
+        let mut random = move || {
+            seed = seed.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+            (seed >> 8) as f32 / (1 << 24) as f32
+        };
+
+        for trial in 0..50 {
+            // around a center at growing angles, or anywhere in the square, crossing itself
+            let crossing = trial % 2 == 1;
+            let mut shape = || {
+                let center = point(random() * 50.0, random() * 50.0);
+                let count = 3 + (random() * 8.0) as usize;
+                let mut points = (0..count)
+                    .map(|i| {
+                        if crossing {
+                            point(random() * 100.0, random() * 100.0)
+                        } else {
+                            let angle =
+                                (i as f32 + random() * 0.9) / count as f32 * std::f32::consts::TAU;
+                            let radius = 10.0 + random() * 40.0;
+                            center + vector(angle.cos(), angle.sin()) * radius
+                        }
+                    })
+                    .collect::<Vec<_>>();
+                if random() < 0.5 {
+                    points.reverse();
+                }
+                polygon(&points)
+            };
+            let (a, b) = (shape(), shape());
+            for fill_rule in MY_FILL_RULES {
+                assert_areas_add_up(&a, &b, fill_rule);
+                if !crossing {
+                    // a simple polygon fills its inside with both rules
+                    let area_a = area(&a, fill_rule, MY_TOLERANCE);
+                    assert_near(area_a, signed_area(&a, MY_TOLERANCE).abs(), "shoelace");
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,391 @@
+use lyon::algorithms::measure::{PathMeasurements, SampleType};
+use lyon::path::{Path, PathEvent};
+use std::ops::Range;
+
+// Splits each sub-path into dashes, the lengths of the pattern alternating between dash and
+// gap, starting offset into the pattern. As in SVG, a pattern of odd length is repeated to make
+// it even and each sub-path starts the pattern again. The dashes are open sub-paths that keep
+// the curves of the path, so a stroke puts its caps on each of them. A dash over the seam of a
+// closed sub-path stays in one piece, with a join at the seam rather than two caps.
+// Dashes of zero length are left out, as lyon has no sub-path to put caps on. A pattern that
+// would repeat more than MY_MAX_PERIODS times along a sub-path is an error.
+pub fn dash(path: &Path, pattern: &[f32], offset: f32, tolerance: f32) -> Result<Path, String> {
+    if !offset.is_finite()
+        || pattern
+            .iter()
+            .any(|length| !(length.is_finite() && *length >= 0.0))
+    {
+        return Err(format!(
+            "a dash pattern needs lengths of 0 or more and a finite offset, not {:?} and {}",
+            pattern, offset
+        ));
+    }
+    let mut pattern = pattern.to_vec();
+    if !pattern.len().is_multiple_of(2) {
+        pattern.extend_from_within(..);
+    }
+    let period = pattern.iter().sum::<f32>();
+    if period <= 0.0 {
+        // nothing to dash, the stroke stays solid
+        return Ok(path.clone());
+    }
+
+    let mut builder = Path::builder();
+    for (subpath, closed) in subpaths(path) {
+        let measurements = PathMeasurements::from_path(&subpath, tolerance);
+        let length = measurements.length();
+        if length / period > MY_MAX_PERIODS {
+            return Err(format!(
+                "the dash pattern {:?} would repeat more than {} times along a length of {}",
+                pattern, MY_MAX_PERIODS, length
+            ));
+        }
+        let mut dashes = intervals(&pattern, offset, length);
+        if closed && dashes == [0.0..length] {
+            for event in subpath.iter() {
+                builder.path_event(event);
+            }
+            continue;
+        }
+
+        let mut sampler = measurements.create_sampler(&subpath, SampleType::Distance);
+        let mut piece = |range: Range<f32>| {
+            let mut piece = Path::builder();
+            sampler.split_range(range, piece.inner_mut());
+            piece.build()
+        };
+        // the dash at the end of a closed sub-path goes on with the one at its start
+        let seam = match (dashes.first(), dashes.last()) {
+            (Some(first), Some(last))
+                if closed && dashes.len() > 1 && first.start <= 0.0 && last.end >= length =>
+            {
+                let first = dashes.remove(0);
+                let last = dashes.pop().unwrap();
+                Some((piece(last), piece(first)))
+            }
+            _ => None,
+        };
+        for range in dashes {
+            for event in piece(range).iter() {
+                builder.path_event(event);
+            }
+        }
+        if let Some((end, start)) = seam {
+            let events = end
+                .iter()
+                .filter(|event| !matches!(event, PathEvent::End { .. }));
+            let start = start
+                .iter()
+                .filter(|event| !matches!(event, PathEvent::Begin { .. }));
+            for event in events.chain(start) {
+                builder.path_event(event);
+            }
+        }
+    }
+    Ok(builder.build())
+}
+
+// Where along a sub-path of the length the dashes of the pattern are. Dashes only apart by a gap
+// of zero are one. The entries are placed from the start of their period rather than added up
+// one by one, as an entry below the precision of the distance would not move it on. The periods
+// themselves are long enough to, as dash keeps them to MY_MAX_PERIODS along the length.
+fn intervals(pattern: &[f32], offset: f32, length: f32) -> Vec<Range<f32>> {
+    // where each entry starts in the period, and then the period, where the last one ends
+    let mut bounds = vec![0.0];
+    for entry in pattern {
+        bounds.push(bounds[bounds.len() - 1] + entry);
+    }
+    let period = bounds[pattern.len()];
+
+    let mut dashes: Vec<Range<f32>> = vec![];
+    let mut period_start = -offset.rem_euclid(period);
+    while period_start < length {
+        // the same sums for the end of an entry and the start of the next, so that a gap of
+        // zero is exactly zero
+        for bound in bounds.windows(2).step_by(2) {
+            let (start, end) = (period_start + bound[0], period_start + bound[1]);
+            if start < length && end > start.max(0.0) {
+                let range = start.max(0.0)..end.min(length);
+                match dashes.last_mut() {
+                    Some(last) if last.end >= range.start => last.end = range.end,
+                    _ => dashes.push(range),
+                }
+            }
+        }
+        period_start += period;
+    }
+    dashes
+}
+
+// Each sub-path on its own, and whether it is closed. Segments of no length, such as the line
+// that the SVG builder puts before an arc, are left out, as the sampler can't split them.
+fn subpaths(path: &Path) -> Vec<(Path, bool)> {
+    let mut subpaths = vec![];
+    let mut builder = Path::builder();
+    for event in path.iter() {
+        let degenerate = match event {
+            PathEvent::Line { from, to } => from == to,
+            PathEvent::Quadratic { from, ctrl, to } => from == ctrl && ctrl == to,
+            PathEvent::Cubic {
+                from,
+                ctrl1,
+                ctrl2,
+                to,
+            } => from == ctrl1 && ctrl1 == ctrl2 && ctrl2 == to,
+            _ => false,
+        };
+        if degenerate {
+            continue;
+        }
+        builder.path_event(event);
+        if let PathEvent::End { close, .. } = event {
+            let subpath = std::mem::replace(&mut builder, Path::builder()).build();
+            subpaths.push((subpath, close));
+        }
+    }
+    subpaths
+}
+
+// A pattern that repeats more often along a sub-path has dashes too small to see, and too many.
+const MY_MAX_PERIODS: f32 = 10_000.0;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lyon::math::{point, vector, Angle, Point};
+    use lyon::path::Winding;
+
+    const MY_PATTERN: [f32; 2] = [10.0, 5.0];
+    const MY_TOLERANCE: f32 = 0.01;
+    // the measured lengths of curves are flattened with the tolerance
+    const MY_MAX_ERROR: f32 = 0.05;
+
+    fn length(path: &Path) -> f32 {
+        PathMeasurements::from_path(path, MY_TOLERANCE).length()
+    }
+
+    // The length of each dash, and its first and last point.
+    fn dashes(path: &Path, pattern: &[f32], offset: f32) -> Vec<(f32, Point, Point)> {
+        let dashed = dash(path, pattern, offset, MY_TOLERANCE).unwrap();
+        (subpaths(&dashed).iter())
+            .map(|(dash, closed)| {
+                assert!(!closed, "a dash is an open sub-path");
+                let first = dash.first_endpoint().unwrap().0;
+                let last = dash.last_endpoint().unwrap().0;
+                (length(dash), first, last)
+            })
+            .collect()
+    }
+
+    // What MY_PATTERN asks for along an open path of the length, from the offset.
+    fn expected_lengths(length: f32, offset: f32) -> Vec<f32> {
+        let mut expected = vec![];
+        let mut start = -offset;
+        while start < length {
+            let end = (start + MY_PATTERN[0]).min(length);
+            if end > start.max(0.0) {
+                expected.push(end - start.max(0.0));
+            }
+            start += MY_PATTERN[0] + MY_PATTERN[1];
+        }
+        expected
+    }
+
+    fn assert_lengths(actual: &[(f32, Point, Point)], expected: &[f32]) {
+        let lengths = actual
+            .iter()
+            .map(|(length, ..)| *length)
+            .collect::<Vec<_>>();
+        let ok = lengths.len() == expected.len()
+            && (lengths.iter().zip(expected)).all(|(a, b)| (a - b).abs() <= MY_MAX_ERROR);
+        assert!(ok, "dashes of {lengths:?}, expected {expected:?}");
+    }
+
+    #[test]
+    fn line() {
+        let mut builder = Path::builder();
+        builder.begin(point(0.0, 0.0));
+        builder.line_to(point(100.0, 0.0));
+        builder.end(false);
+        let path = builder.build();
+
+        for offset in [0.0f32, 3.0, 12.0, -4.0] {
+            let expected = expected_lengths(100.0, offset.rem_euclid(15.0));
+            assert_lengths(&dashes(&path, &MY_PATTERN, offset), &expected);
+        }
+        // the first dash starts 3 into the pattern, so 3 short
+        let actual = dashes(&path, &MY_PATTERN, 3.0);
+        assert_eq!(
+            (actual[0].1, actual[0].2),
+            (point(0.0, 0.0), point(7.0, 0.0))
+        );
+        assert_eq!(
+            (actual[1].1, actual[1].2),
+            (point(12.0, 0.0), point(22.0, 0.0))
+        );
+    }
+
+    #[test]
+    fn arc() {
+        let mut builder = Path::svg_builder();
+        builder.move_to(point(50.0, 0.0));
+        builder.arc(
+            point(0.0, 0.0),
+            vector(50.0, 50.0),
+            Angle::degrees(90.0),
+            Angle::zero(),
+        );
+        let path = builder.build();
+
+        let length = length(&path);
+        assert!((length - 25.0 * std::f32::consts::PI).abs() < MY_MAX_ERROR);
+        assert_lengths(
+            &dashes(&path, &MY_PATTERN, 3.0),
+            &expected_lengths(length, 3.0),
+        );
+        // the dashes stay on the circle
+        for (_, first, last) in dashes(&path, &MY_PATTERN, 3.0) {
+            for p in [first, last] {
+                assert!(
+                    (p.to_vector().length() - 50.0).abs() < MY_MAX_ERROR,
+                    "{p:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn cubic() {
+        let mut builder = Path::builder();
+        builder.begin(point(0.0, 0.0));
+        builder.cubic_bezier_to(point(30.0, 60.0), point(70.0, -60.0), point(100.0, 0.0));
+        builder.end(false);
+        let path = builder.build();
+
+        let length = length(&path);
+        for offset in [0.0, 3.0, 7.5] {
+            assert_lengths(
+                &dashes(&path, &MY_PATTERN, offset),
+                &expected_lengths(length, offset),
+            );
+        }
+    }
+
+    // A square of 25, 100 around, from its top left corner.
+    fn square() -> Path {
+        let mut builder = Path::builder();
+        builder.begin(point(0.0, 0.0));
+        builder.line_to(point(25.0, 0.0));
+        builder.line_to(point(25.0, 25.0));
+        builder.line_to(point(0.0, 25.0));
+        builder.end(true);
+        builder.build()
+    }
+
+    #[test]
+    fn across_the_seam() {
+        // 8 into the pattern, the dash from 97 to 107 goes over the seam at 100 as one dash
+        let actual = dashes(&square(), &MY_PATTERN, 8.0);
+        let mut expected = expected_lengths(100.0, 8.0);
+        let first = expected.remove(0);
+        *expected.last_mut().unwrap() += first;
+        assert_eq!(expected.len(), 7);
+        assert_lengths(&actual, &expected);
+        let (_, first, last) = *actual.last().unwrap();
+        assert_eq!((first, last), (point(0.0, 3.0), point(2.0, 0.0)));
+
+        // 3 into the pattern, the last dash ends at 97, so nothing goes over the seam
+        let actual = dashes(&square(), &MY_PATTERN, 3.0);
+        assert_lengths(&actual, &expected_lengths(100.0, 3.0));
+        assert_eq!(actual[0].1, point(0.0, 0.0));
+    }
+
+    #[test]
+    fn around_a_circle() {
+        let mut builder = Path::builder();
+        builder.add_circle(point(0.0, 0.0), 20.0, Winding::Positive);
+        let path = builder.build();
+
+        let length = length(&path);
+        let mut expected = expected_lengths(length, 3.0);
+        // the circle is not a whole number of periods, so the last dash reaches the seam
+        let first = expected.remove(0);
+        *expected.last_mut().unwrap() += first;
+        assert_lengths(&dashes(&path, &MY_PATTERN, 3.0), &expected);
+    }
+
+    #[test]
+    fn each_subpath_starts_the_pattern_again() {
+        let mut builder = Path::builder();
+        for y in [0.0, 10.0] {
+            builder.begin(point(0.0, y));
+            builder.line_to(point(30.0, y));
+            builder.end(false);
+        }
+        let actual = dashes(&builder.build(), &MY_PATTERN, 0.0);
+        assert_lengths(&actual, &[10.0, 10.0, 10.0, 10.0]);
+        assert_eq!(actual[2].1, point(0.0, 10.0));
+    }
+
+    #[test]
+    fn patterns() {
+        assert_eq!(intervals(&[10.0, 10.0], 0.0, 35.0), [0.0..10.0, 20.0..30.0]);
+        assert_eq!(
+            intervals(&[10.0, 10.0], 15.0, 35.0),
+            [5.0..15.0, 25.0..35.0]
+        );
+        // dashes apart by a gap of zero are one
+        assert_eq!(
+            intervals(&[5.0, 0.0, 5.0, 5.0], 0.0, 20.0),
+            [0.0..10.0, 15.0..20.0]
+        );
+        let mut line = Path::builder();
+        line.begin(point(0.0, 0.0));
+        line.line_to(point(35.0, 0.0));
+        line.end(false);
+        let line = line.build();
+        // an odd pattern is repeated, so 10 is a dash and then a gap
+        assert_lengths(&dashes(&line, &[10.0], 0.0), &[10.0, 10.0]);
+        // a pattern of only zeros leaves the path solid
+        assert_lengths(&dashes(&line, &[0.0, 0.0], 0.0), &[35.0]);
+        assert!(dash(&line, &[10.0, -5.0], 0.0, MY_TOLERANCE).is_err());
+        assert!(dash(&line, &MY_PATTERN, f32::NAN, MY_TOLERANCE).is_err());
+    }
+
+    #[test]
+    fn patterns_too_fine_for_the_length() {
+        let mut line = Path::builder();
+        line.begin(point(0.0, 0.0));
+        line.line_to(point(1000.0, 0.0));
+        line.end(false);
+        let line = line.build();
+        // half an ulp of 1000 is about 3e-5, so adding these up stalled at 256
+        assert!(dash(&line, &[1e-5, 1e-5], 0.0, MY_TOLERANCE).is_err());
+        assert!(dash(&line, &[0.03125, 0.0625], 0.0, MY_TOLERANCE).is_err());
+        // 8000 periods, which add up exactly in floats, so there is a dash for each of them
+        let dashed = dash(&line, &[0.0625, 0.0625], 0.0, MY_TOLERANCE).unwrap();
+        let count = dashed
+            .iter()
+            .filter(|event| matches!(event, PathEvent::Begin { .. }));
+        assert_eq!(count.count(), 8000);
+
+        // dashes below the precision of where they are come out empty and are left out,
+        // but the pattern still moves on
+        let dashes = intervals(&[1e-5, 10.0], 0.0, 1000.0);
+        assert!(dashes.len() <= 100);
+        assert!(dashes.iter().all(|dash| dash.end > dash.start));
+    }
+
+    #[test]
+    fn gaps_of_zero_join_dashes_anywhere_along_the_path() {
+        // two dashes a period, as the second and the third meet; placing the third at the end
+        // of the second plus its own length misses by an ulp and splits them in one period
+        let dashes = intervals(&[0.1, 0.1, 0.1, 0.0, 0.2, 0.1], 0.0, 3.0);
+        assert_eq!(dashes.len(), 10, "{:?}", dashes);
+        // and across the end of the period
+        let dashes = intervals(&[0.1, 0.2, 0.3, 0.0], 0.0, 1.2);
+        assert_eq!(dashes.len(), 3, "{:?}", dashes);
+        assert!((dashes[1].start - 0.3).abs() < 1e-6);
+        assert!((dashes[1].end - 0.7).abs() < 1e-6);
+    }
+}
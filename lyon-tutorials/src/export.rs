@@ -0,0 +1,426 @@
+#[cfg(test)]
+use lyon::math::point;
+use lyon::math::Point;
+use lyon::tessellation::VertexBuffers;
+use std::path::Path;
+
+pub type MyGeometry = VertexBuffers<Point, u32>;
+
+// The format is chosen by the extension of the file.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MyFormat {
+    // .obj, with z = 0
+    Obj,
+    // .gltf, with the buffer in a .bin of the same name
+    Gltf,
+    // .json, {"vertices": [[x, y], ...], "indices": [...]}
+    Json,
+    // .mesh, which the renderer tutorials load
+    Binary,
+}
+
+impl MyFormat {
+    pub fn from_path(file: &Path) -> Result<Self, String> {
+        let extension = file.extension().unwrap_or_default().to_string_lossy();
+        match extension.to_ascii_lowercase().as_str() {
+            "obj" => Ok(MyFormat::Obj),
+            "gltf" => Ok(MyFormat::Gltf),
+            "json" => Ok(MyFormat::Json),
+            "mesh" => Ok(MyFormat::Binary),
+            _ => Err(format!(
+                "{}: the extension must be .obj, .gltf, .json or .mesh",
+                file.display()
+            )),
+        }
+    }
+}
+
+// The binary format, all little-endian:
+//   b"MESH", version: u32, vertex count: u32, index count: u32,
+//   [x: f32, y: f32] for each vertex, then u32 for each index.
+// mesh_file.rs of tutorial-common reads the same layout for the renderer tutorials.
+const MY_BINARY_MAGIC: &[u8; 4] = b"MESH";
+const MY_BINARY_VERSION: u32 = 1;
+const MY_BINARY_HEADER_SIZE: usize = 16;
+
+pub fn write(file: &Path, geometry: &MyGeometry) -> Result<(), String> {
+    let error = |err: std::io::Error| format!("{}: {}", file.display(), err);
+    match MyFormat::from_path(file)? {
+        MyFormat::Obj => std::fs::write(file, to_obj(geometry)).map_err(error),
+        MyFormat::Gltf => {
+            let bin_file = file.with_extension("bin");
+            let bin_name = bin_file.file_name().unwrap().to_string_lossy();
+            let (gltf, bin) = to_gltf(geometry, &bin_name);
+            std::fs::write(&bin_file, bin).map_err(error)?;
+            std::fs::write(file, gltf).map_err(error)
+        }
+        MyFormat::Json => std::fs::write(file, to_json(geometry)).map_err(error),
+        MyFormat::Binary => std::fs::write(file, to_binary(geometry)).map_err(error),
+    }
+}
+
+// The tests read every format back, to check that it holds the buffers exactly.
+#[cfg(test)]
+pub fn read(file: &Path) -> Result<MyGeometry, String> {
+    let error = |err: String| format!("{}: {}", file.display(), err);
+    match MyFormat::from_path(file)? {
+        MyFormat::Obj => {
+            let source = std::fs::read_to_string(file).map_err(|err| error(err.to_string()))?;
+            from_obj(&source).map_err(error)
+        }
+        MyFormat::Gltf => {
+            let source = std::fs::read_to_string(file).map_err(|err| error(err.to_string()))?;
+            from_gltf(&source, file.parent().unwrap_or(Path::new("."))).map_err(error)
+        }
+        MyFormat::Json => {
+            let source = std::fs::read_to_string(file).map_err(|err| error(err.to_string()))?;
+            serde_json::from_str::<MyJson>(&source)
+                .map(MyJson::into_geometry)
+                .map_err(|err| error(err.to_string()))
+        }
+        MyFormat::Binary => {
+            let bytes = std::fs::read(file).map_err(|err| error(err.to_string()))?;
+            from_binary(&bytes).map_err(error)
+        }
+    }
+}
+
+fn to_obj(geometry: &MyGeometry) -> String {
+    let mut obj = String::from("# tessellated by lyon-tutorials\n");
+    for vertex in &geometry.vertices {
+        obj.push_str(&format!("v {} {} 0\n", vertex.x, vertex.y));
+    }
+    for triangle in geometry.indices.chunks(3) {
+        let [a, b, c] = [triangle[0] + 1, triangle[1] + 1, triangle[2] + 1];
+        obj.push_str(&format!("f {} {} {}\n", a, b, c));
+    }
+    obj
+}
+
+// Only what to_obj writes: vertices and triangles with 1-based indices.
+#[cfg(test)]
+fn from_obj(source: &str) -> Result<MyGeometry, String> {
+    let mut geometry = MyGeometry::new();
+    for (line_number, line) in source.lines().enumerate() {
+        let error = |message: &str| format!("line {}: {}: {:?}", line_number + 1, message, line);
+        let mut words = line.split_whitespace();
+        match words.next() {
+            Some("v") => {
+                let values = words
+                    .map(|word| word.parse::<f32>().ok())
+                    .collect::<Option<Vec<_>>>()
+                    .filter(|values| values.len() >= 2)
+                    .ok_or_else(|| error("bad vertex"))?;
+                geometry.vertices.push(point(values[0], values[1]));
+            }
+            Some("f") => {
+                let indices = words
+                    .map(|word| word.split('/').next()?.parse::<u32>().ok()?.checked_sub(1))
+                    .collect::<Option<Vec<_>>>()
+                    .filter(|indices| indices.len() == 3)
+                    .ok_or_else(|| error("bad triangle"))?;
+                geometry.indices.extend(indices);
+            }
+            _ => {}
+        }
+    }
+    check(geometry)
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+struct MyJson {
+    vertices: Vec<[f32; 2]>,
+    indices: Vec<u32>,
+}
+
+#[cfg(test)]
+impl MyJson {
+    fn into_geometry(self) -> MyGeometry {
+        let mut geometry = MyGeometry::new();
+        geometry.vertices = (self.vertices.iter()).map(|&[x, y]| point(x, y)).collect();
+        geometry.indices = self.indices;
+        geometry
+    }
+}
+
+fn to_json(geometry: &MyGeometry) -> String {
+    let json = MyJson {
+        vertices: (geometry.vertices.iter()).map(|v| [v.x, v.y]).collect(),
+        indices: geometry.indices.clone(),
+    };
+    serde_json::to_string(&json).unwrap()
+}
+
+// A glTF 2.0 scene with a single mesh, its positions and indices in one buffer.
+fn to_gltf(geometry: &MyGeometry, bin_name: &str) -> (String, Vec<u8>) {
+    use serde_json::json;
+
+    let mut bin = vec![];
+    for vertex in &geometry.vertices {
+        for value in [vertex.x, vertex.y, 0.0] {
+            bin.extend(value.to_le_bytes());
+        }
+    }
+    let positions_length = bin.len();
+    for index in &geometry.indices {
+        bin.extend(index.to_le_bytes());
+    }
+    let indices_length = bin.len() - positions_length;
+
+    let (min, max) = (geometry.vertices.iter()).fold(
+        ([f32::INFINITY; 3], [f32::NEG_INFINITY; 3]),
+        |(min, max), v| {
+            (
+                [min[0].min(v.x), min[1].min(v.y), 0.0],
+                [max[0].max(v.x), max[1].max(v.y), 0.0],
+            )
+        },
+    );
+    let gltf = json!({
+        "asset": { "version": "2.0", "generator": "lyon-tutorials" },
+        "scene": 0,
+        "scenes": [{ "nodes": [0] }],
+        "nodes": [{ "mesh": 0 }],
+        "meshes": [{
+            "primitives": [{ "attributes": { "POSITION": 0 }, "indices": 1, "mode": 4 }]
+        }],
+        "buffers": [{ "uri": bin_name, "byteLength": bin.len() }],
+        "bufferViews": [
+            { "buffer": 0, "byteOffset": 0, "byteLength": positions_length, "target": 34962 },
+            {
+                "buffer": 0,
+                "byteOffset": positions_length,
+                "byteLength": indices_length,
+                "target": 34963
+            }
+        ],
+        "accessors": [
+            {
+                "bufferView": 0,
+                "componentType": 5126,
+                "count": geometry.vertices.len(),
+                "type": "VEC3",
+                "min": min,
+                "max": max
+            },
+            {
+                "bufferView": 1,
+                "componentType": 5125,
+                "count": geometry.indices.len(),
+                "type": "SCALAR"
+            }
+        ]
+    });
+    (serde_json::to_string_pretty(&gltf).unwrap(), bin)
+}
+
+// The first primitive of the first mesh, with float positions and 16 or 32-bit indices.
+#[cfg(test)]
+fn from_gltf(source: &str, dir: &Path) -> Result<MyGeometry, String> {
+    use serde_json::Value;
+
+    let gltf: Value = serde_json::from_str(source).map_err(|err| err.to_string())?;
+    let primitive = &gltf["meshes"][0]["primitives"][0];
+    let accessor = |index: &Value| -> Result<(Vec<u8>, &Value), String> {
+        let accessor = &gltf["accessors"][index.as_u64().ok_or("no accessor")? as usize];
+        let view =
+            &gltf["bufferViews"][accessor["bufferView"].as_u64().ok_or("no buffer view")? as usize];
+        let buffer = &gltf["buffers"][view["buffer"].as_u64().ok_or("no buffer")? as usize];
+        let uri = buffer["uri"].as_str().ok_or("no buffer uri")?;
+        let bin = std::fs::read(dir.join(uri)).map_err(|err| format!("{}: {}", uri, err))?;
+        let start = (view["byteOffset"].as_u64().unwrap_or(0)
+            + accessor["byteOffset"].as_u64().unwrap_or(0)) as usize;
+        let end = view["byteOffset"].as_u64().unwrap_or(0) as usize
+            + view["byteLength"].as_u64().ok_or("no byte length")? as usize;
+        let bytes = bin.get(start..end).ok_or("the buffer is too short")?;
+        Ok((bytes.to_vec(), accessor))
+    };
+
+    let mut geometry = MyGeometry::new();
+    let (bytes, positions) = accessor(&primitive["attributes"]["POSITION"])?;
+    if positions["componentType"] != 5126 || positions["type"] != "VEC3" {
+        return Err("positions must be float VEC3".to_owned());
+    }
+    let count = positions["count"].as_u64().unwrap_or(0) as usize;
+    let floats = (bytes.chunks_exact(4))
+        .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        .collect::<Vec<_>>();
+    if floats.len() < count * 3 {
+        return Err("the positions are too short".to_owned());
+    }
+    geometry.vertices = (floats.chunks_exact(3).take(count))
+        .map(|v| point(v[0], v[1]))
+        .collect();
+
+    let (bytes, indices) = accessor(&primitive["indices"])?;
+    let count = indices["count"].as_u64().unwrap_or(0) as usize;
+    geometry.indices = match indices["componentType"].as_u64() {
+        Some(5125) => (bytes.chunks_exact(4))
+            .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+            .collect(),
+        Some(5123) => (bytes.chunks_exact(2))
+            .map(|b| u16::from_le_bytes([b[0], b[1]]) as u32)
+            .collect(),
+        _ => return Err("indices must be 16 or 32-bit".to_owned()),
+    };
+    if geometry.indices.len() < count {
+        return Err("the indices are too short".to_owned());
+    }
+    geometry.indices.truncate(count);
+    check(geometry)
+}
+
+fn to_binary(geometry: &MyGeometry) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(
+        MY_BINARY_HEADER_SIZE + geometry.vertices.len() * 8 + geometry.indices.len() * 4,
+    );
+    bytes.extend(MY_BINARY_MAGIC);
+    bytes.extend(MY_BINARY_VERSION.to_le_bytes());
+    bytes.extend((geometry.vertices.len() as u32).to_le_bytes());
+    bytes.extend((geometry.indices.len() as u32).to_le_bytes());
+    for vertex in &geometry.vertices {
+        bytes.extend(vertex.x.to_le_bytes());
+        bytes.extend(vertex.y.to_le_bytes());
+    }
+    for index in &geometry.indices {
+        bytes.extend(index.to_le_bytes());
+    }
+    bytes
+}
+
+#[cfg(test)]
+fn from_binary(bytes: &[u8]) -> Result<MyGeometry, String> {
+    let word = |offset: usize| {
+        let b = bytes
+            .get(offset..offset + 4)
+            .ok_or("unexpected end of file")?;
+        Ok::<_, String>([b[0], b[1], b[2], b[3]])
+    };
+    if bytes.get(..4) != Some(MY_BINARY_MAGIC) {
+        return Err("not a mesh file".to_owned());
+    }
+    let version = u32::from_le_bytes(word(4)?);
+    if version != MY_BINARY_VERSION {
+        return Err(format!("version {} is not supported", version));
+    }
+    let vertex_count = u32::from_le_bytes(word(8)?) as usize;
+    let index_count = u32::from_le_bytes(word(12)?) as usize;
+    let mut geometry = MyGeometry::new();
+    let mut offset = MY_BINARY_HEADER_SIZE;
+    for _ in 0..vertex_count {
+        let x = f32::from_le_bytes(word(offset)?);
+        let y = f32::from_le_bytes(word(offset + 4)?);
+        geometry.vertices.push(point(x, y));
+        offset += 8;
+    }
+    for _ in 0..index_count {
+        geometry.indices.push(u32::from_le_bytes(word(offset)?));
+        offset += 4;
+    }
+    check(geometry)
+}
+
+// Whole triangles, and no index out of range.
+#[cfg(test)]
+fn check(geometry: MyGeometry) -> Result<MyGeometry, String> {
+    if !geometry.indices.len().is_multiple_of(3) {
+        return Err(format!(
+            "{} indices do not make triangles",
+            geometry.indices.len()
+        ));
+    }
+    let vertex_count = geometry.vertices.len();
+    match geometry
+        .indices
+        .iter()
+        .find(|&&index| index as usize >= vertex_count)
+    {
+        Some(index) => Err(format!(
+            "index {} is out of {} vertices",
+            index, vertex_count
+        )),
+        None => Ok(geometry),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A square with a triangle beside it, with coordinates that decimal text does not hold
+    // exactly.
+    fn geometry() -> MyGeometry {
+        let mut geometry = MyGeometry::new();
+        geometry.vertices = vec![
+            point(0.0, 0.0),
+            point(10.1, 0.0),
+            point(10.1, 10.1),
+            point(0.0, 10.1),
+            point(-1.0 / 3.0, 1e-7),
+        ];
+        geometry.indices = vec![0, 1, 2, 0, 2, 3, 0, 3, 4];
+        geometry
+    }
+
+    fn round_trip(extension: &str) -> MyGeometry {
+        let dir = std::env::temp_dir().join(format!("lyon-tutorials-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("geometry").with_extension(extension);
+        write(&file, &geometry()).unwrap();
+        let read = read(&file);
+        std::fs::remove_file(&file).unwrap();
+        if extension == "gltf" {
+            std::fs::remove_file(file.with_extension("bin")).unwrap();
+        }
+        read.unwrap()
+    }
+
+    fn assert_same(read: MyGeometry) {
+        let expected = geometry();
+        assert_eq!(read.vertices, expected.vertices);
+        assert_eq!(read.indices, expected.indices);
+    }
+
+    #[test]
+    fn obj_round_trip() {
+        assert_same(round_trip("obj"));
+    }
+
+    #[test]
+    fn gltf_round_trip() {
+        assert_same(round_trip("gltf"));
+    }
+
+    #[test]
+    fn json_round_trip() {
+        assert_same(round_trip("json"));
+    }
+
+    #[test]
+    fn mesh_round_trip() {
+        assert_same(round_trip("mesh"));
+    }
+
+    #[test]
+    fn the_renderers_read_the_mesh() {
+        use tutorial_common::mesh_file::MeshFile;
+
+        let mesh = MeshFile::parse(&to_binary(&geometry())).unwrap();
+        let positions = (geometry().vertices.iter())
+            .map(|v| [v.x, v.y])
+            .collect::<Vec<_>>();
+        assert_eq!(mesh.positions, positions);
+        assert_eq!(mesh.indices, geometry().indices);
+    }
+
+    #[test]
+    fn rejects_broken_files() {
+        let mut bytes = to_binary(&geometry());
+        bytes.pop();
+        assert!(from_binary(&bytes).is_err());
+        assert!(from_obj("v 0 0 0\nf 1 2 3\n").is_err());
+        assert!(from_obj("v 0 0 0\nv 1 0 0\nf 1 2\n").is_err());
+        assert!(serde_json::from_str::<MyJson>("{\"vertices\": []}").is_err());
+        assert!(MyFormat::from_path(Path::new("geometry.stl")).is_err());
+    }
+}
@@ -1,106 +1,152 @@
-fn main() {
-    use lyon::math::{point, Box2D, Point};
-    use lyon::path::Winding;
-    use lyon::tessellation::geometry_builder::simple_builder;
-    use lyon::tessellation::{FillOptions, FillTessellator, VertexBuffers};
+mod boolean;
+mod dash;
+mod export;
+// the tests render shapes with it, to compare them with golden images and exact coverage
+#[cfg(test)]
+mod raster;
+mod shapes;
+mod svg;
+mod text;
 
-    let mut geometry: VertexBuffers<Point, u16> = VertexBuffers::new();
-    let mut geometry_builder = simple_builder(&mut geometry);
-    let options = FillOptions::tolerance(0.2);
-    let mut tessellator = FillTessellator::new();
-    let mut builder = tessellator.builder(&options, &mut geometry_builder);
+use tutorial_common::args::Args;
+use tutorial_common::path_file;
 
-    builder.add_rectangle(
-        &Box2D {
-            min: point(0.0, 0.0),
-            max: point(100.0, 50.0),
-        },
-        Winding::Positive,
-    );
-    _ = builder.build();
+fn main() {
+    // with a path file or a text, only that is tessellated
+    let args = Args::from_env(&[
+        "--input",
+        "--text",
+        "--size",
+        "--stroke",
+        "--tolerance",
+        "--output",
+    ]);
+    if args.input.is_some() || args.text.is_some() {
+        if let Err(err) = tessellate_file(&args) {
+            eprintln!("{}", err);
+            std::process::exit(1);
+        }
+        return;
+    }
 
-    println!("----- rectangle -----");
-    println!("{} vertices", geometry.vertices.len());
-    println!("{:?}", geometry.vertices);
-    println!("{} indices", geometry.indices.len());
-    println!("{:?}", geometry.indices);
+    let resource_dir = resource_dir();
 
-    let mut geometry: VertexBuffers<Point, u16> = VertexBuffers::new();
-    let mut geometry_builder = simple_builder(&mut geometry);
-    let options = FillOptions::tolerance(0.2);
-    let mut tessellator = FillTessellator::new();
-    let mut builder = tessellator.builder(&options, &mut geometry_builder);
+    for (name, geometry) in shapes::all(&resource_dir, 0.2) {
+        println!("----- {} -----", name);
+        println!("{} vertices", geometry.vertices.len());
+        println!("{:?}", geometry.vertices);
+        println!("{} indices", geometry.indices.len());
+        println!("{:?}", geometry.indices);
+    }
 
-    use lyon::path::builder::BorderRadii;
+    for name in ["rust-logo-blk.svg", "rustacean-flat-happy.svg"] {
+        let data = std::fs::read(resource_dir.join(name)).unwrap();
+        let shapes = svg::import(&data, 0.2).unwrap();
 
-    builder.add_rounded_rectangle(
-        &Box2D {
-            min: point(0.0, 0.0),
-            max: point(100.0, 50.0),
-        },
-        &BorderRadii {
-            top_left: 5.0,
-            top_right: 5.0,
-            bottom_left: 5.0,
-            bottom_right: 5.0,
-        },
-        Winding::Positive,
-    );
-    _ = builder.build();
+        println!("----- {} -----", name);
+        for shape in &shapes {
+            println!(
+                "{:?} {:?}: {} vertices, {} indices, {:?}",
+                shape.id,
+                shape.color,
+                shape.geometry.vertices.len(),
+                shape.geometry.indices.len(),
+                shape.bounds()
+            );
+        }
+    }
 
-    println!("----- rounded rectangle -----");
-    println!("{} vertices", geometry.vertices.len());
-    println!("{:?}", geometry.vertices);
-    println!("{} indices", geometry.indices.len());
-    println!("{:?}", geometry.indices);
+    for (name, geometry) in shapes::booleans(&resource_dir, 0.2) {
+        println!("----- {} rounded rectangle -----", name);
+        println!("{} vertices", geometry.vertices.len());
+        println!("{} indices", geometry.indices.len());
+    }
 
-    use lyon::path::{LineCap, LineJoin};
-    use lyon::tessellation::{StrokeOptions, StrokeTessellator};
+    for (name, geometry) in shapes::titles(&resource_dir, 0.2) {
+        println!("----- {} -----", name);
+        println!("{} vertices", geometry.vertices.len());
+        println!("{} indices", geometry.indices.len());
+    }
+}
+
+// Fills the path of the --input file or of the --text, or strokes it with --stroke, and
+// prints the size of the result. Writes it to every --output, whose extension is one of
+// obj, gltf, json or mesh.
+fn tessellate_file(args: &Args) -> Result<(), String> {
+    use lyon::math::{Box2D, Point};
+    use lyon::path::FillRule;
+    use lyon::tessellation::geometry_builder::{BuffersBuilder, Positions};
+    use lyon::tessellation::{
+        FillOptions, FillTessellator, StrokeOptions, StrokeTessellator, VertexBuffers,
+    };
 
-    let mut geometry: VertexBuffers<Point, u16> = VertexBuffers::new();
-    let mut geometry_builder = simple_builder(&mut geometry);
-    let options = StrokeOptions::tolerance(0.2)
-        .with_start_cap(LineCap::Square)
-        .with_end_cap(LineCap::Round)
-        .with_line_join(LineJoin::Round)
-        .with_line_width(10.0);
-    let mut tessellator = StrokeTessellator::new();
-    let mut builder = tessellator.builder(&options, &mut geometry_builder);
+    let tolerance = args.tolerance.unwrap_or(0.2);
+    let size = args.size.unwrap_or(shapes::MY_TITLE_SIZE);
+    // glyph outlines are meant for the non-zero rule, path files for the default even-odd one
+    let mut laid_out = None;
+    let (name, path, fill_rule) = match (&args.input, &args.text) {
+        (Some(file), None) => (
+            file.clone(),
+            path_file::load(std::path::Path::new(file))?,
+            FillRule::EvenOdd,
+        ),
+        (None, Some(text)) => {
+            let path = laid_out
+                .insert(text::load(&resource_dir(), text, size)?)
+                .path();
+            (format!("{:?}", text), path, FillRule::NonZero)
+        }
+        _ => return Err("either --input or --text is needed".to_owned()),
+    };
 
-    const RADIUS: f32 = 150.0;
-    builder.begin(point(240.0 + RADIUS, 160.0));
-    for i in 1..24 {
-        let d = i as f32 * std::f32::consts::PI / 12.0;
-        let r = if i % 2 == 0 { RADIUS } else { RADIUS / 1.5 };
-        builder.line_to(point(240.0 + r * d.cos(), 160.0 + r * d.sin()));
+    let mut geometry: VertexBuffers<Point, u32> = VertexBuffers::new();
+    let mut geometry_builder = BuffersBuilder::new(&mut geometry, Positions);
+    match args.stroke {
+        Some(width) => {
+            let options = StrokeOptions::tolerance(tolerance).with_line_width(width);
+            StrokeTessellator::new().tessellate_path(&path, &options, &mut geometry_builder)
+        }
+        None => {
+            let options = FillOptions::tolerance(tolerance).with_fill_rule(fill_rule);
+            FillTessellator::new().tessellate_path(&path, &options, &mut geometry_builder)
+        }
     }
-    builder.end(true);
-    _ = builder.build();
+    .map_err(|err| format!("{}: {:?}", name, err))?;
 
-    println!("----- star -----");
+    println!("----- {} -----", name);
     println!("{} vertices", geometry.vertices.len());
-    println!("{:?}", geometry.vertices);
     println!("{} indices", geometry.indices.len());
-    println!("{:?}", geometry.indices);
+    println!("{:?}", Box2D::from_points(&geometry.vertices));
+    if let Some(laid_out) = laid_out {
+        // where the layout put each glyph, kerned and on its line
+        for glyph in &laid_out.glyphs {
+            println!(
+                "{:?}: glyph {} at {:?}",
+                glyph.character, glyph.id.0, glyph.origin
+            );
+        }
+        println!(
+            "{} units to a font unit, {} to a line",
+            laid_out.scale, laid_out.line_height
+        );
+    }
 
-    use lyon::extra::parser::{ParserOptions, PathParser, Source};
-    let mut geometry: VertexBuffers<Point, u16> = VertexBuffers::new();
-    let mut geometry_builder = simple_builder(&mut geometry);
-    let options = FillOptions::tolerance(0.2);
-    let mut tessellator = FillTessellator::new();
-    let mut builder = tessellator.builder(&options, &mut geometry_builder);
-    let mut parser = PathParser::new();
-    let options = ParserOptions::DEFAULT;
-    let mut source = Source::with_position(0, 0, RUST_LOGO.chars());
-    parser.parse(&options, &mut source, &mut builder).unwrap();
+    for output in &args.output {
+        let output = std::path::Path::new(output);
+        export::write(output, &geometry)?;
+        println!("wrote {}", output.display());
+    }
+    Ok(())
+}
 
-    _ = builder.build();
+fn resource_dir() -> std::path::PathBuf {
+    use std::{env, path::PathBuf};
 
-    println!("----- rust logo -----");
-    println!("{} vertices", geometry.vertices.len());
-    println!("{:?}", geometry.vertices);
-    println!("{} indices", geometry.indices.len());
-    println!("{:?}", geometry.indices);
+    if let Ok(manifest_dir) = env::var("CARGO_MANIFEST_DIR") {
+        let mut path = PathBuf::from(manifest_dir);
+        path.push("../assets");
+        path
+    } else {
+        PathBuf::from("../assets")
+    }
 }
-
-const RUST_LOGO: &str = "m47.781 31.608-1.343-.832a18.57 18.57 0 0 0-.038-.391l1.154-1.077a.46.46 0 0 0-.153-.771l-1.476-.552a16.798 16.798 0 0 0-.115-.381l.92-1.279a.462.462 0 0 0-.3-.727l-1.557-.253c-.06-.118-.123-.234-.187-.35l.654-1.435a.46.46 0 0 0-.437-.654l-1.579.055a12.482 12.482 0 0 0-.25-.302l.363-1.539a.461.461 0 0 0-.556-.556l-1.538.362c-.1-.084-.2-.167-.303-.25l.055-1.578a.46.46 0 0 0-.654-.437l-1.435.654a16.712 16.712 0 0 0-.35-.188l-.253-1.556a.462.462 0 0 0-.726-.301l-1.28.92a14.31 14.31 0 0 0-.38-.115l-.552-1.476a.461.461 0 0 0-.771-.154l-1.077 1.156c-.13-.014-.26-.028-.391-.038l-.832-1.344a.462.462 0 0 0-.786 0l-.832 1.344c-.13.01-.261.024-.391.038l-1.077-1.155a.464.464 0 0 0-.771.153l-.552 1.476c-.128.037-.255.076-.38.116l-1.28-.921a.46.46 0 0 0-.727.3l-.254 1.557c-.117.061-.233.124-.35.188l-1.434-.654a.46.46 0 0 0-.654.436l.055 1.58c-.102.082-.203.165-.303.25l-1.538-.363a.464.464 0 0 0-.557.556l.363 1.539c-.085.1-.168.2-.25.302l-1.579-.055a.462.462 0 0 0-.437.654l.654 1.436c-.063.115-.126.231-.187.35l-1.556.252a.462.462 0 0 0-.301.727l.92 1.279c-.04.126-.078.253-.115.38l-1.476.553a.462.462 0 0 0-.153.771l1.155 1.077c-.015.13-.028.26-.039.391l-1.343.832a.462.462 0 0 0 0 .786l1.343.831c.011.131.024.262.039.392l-1.155 1.077a.462.462 0 0 0 .153.771l1.476.552c.037.128.076.255.116.38l-.921 1.28a.462.462 0 0 0 .301.726l1.556.253c.061.118.123.235.188.35l-.655 1.435a.462.462 0 0 0 .437.654l1.579-.055c.082.103.165.203.25.303l-.363 1.539a.46.46 0 0 0 .557.555l1.538-.362c.1.085.201.167.303.249l-.055 1.58a.461.461 0 0 0 .654.436l1.435-.654c.115.064.232.127.35.188l.253 1.555a.461.461 0 0 0 .727.302l1.279-.922c.126.04.253.08.38.116l.552 1.476a.46.46 0 0 0 .771.153l1.078-1.155c.13.015.26.028.391.04l.832 1.343a.463.463 0 0 0 .786 0l.831-1.344c.131-.011.262-.024.392-.039l1.077 1.155a.46.46 0 0 0 .77-.153l.553-1.476c.127-.036.254-.076.38-.116l1.28.922a.463.463 0 0 0 .726-.302l.254-1.556c.117-.06.233-.124.349-.187l1.435.654a.461.461 0 0 0 .654-.437l-.055-1.58c.102-.08.203-.163.303-.248l1.538.362a.46.46 0 0 0 .556-.555l-.362-1.539c.084-.1.167-.2.249-.303l1.58.055a.46.46 0 0 0 .436-.654l-.654-1.435c.064-.115.126-.232.187-.35l1.556-.253a.46.46 0 0 0 .301-.726l-.92-1.28a17.5 17.5 0 0 0 .115-.38l1.476-.552a.46.46 0 0 0 .153-.771l-1.155-1.077c.014-.13.027-.261.039-.392l1.343-.831a.462.462 0 0 0 0-.786zM38.79 42.752a.952.952 0 0 1 .399-1.861.952.952 0 0 1-.4 1.861zm-.457-3.087a.866.866 0 0 0-1.028.666l-.477 2.226A11.649 11.649 0 0 1 32 43.597c-1.76 0-3.43-.39-4.929-1.087l-.477-2.225a.866.866 0 0 0-1.028-.667l-1.965.422a11.68 11.68 0 0 1-1.016-1.197h9.561c.108 0 .18-.02.18-.118v-3.382c0-.099-.072-.118-.18-.118H29.35V33.08h3.024c.276 0 1.476.079 1.86 1.613.12.471.384 2.006.564 2.497.18.551.912 1.652 1.692 1.652h4.764a.977.977 0 0 0 .173-.017c-.33.449-.693.874-1.083 1.27l-2.01-.431zm-13.223 3.04a.952.952 0 0 1-.399-1.861.95.95 0 0 1 .398 1.862zm-3.627-14.707a.95.95 0 1 1-1.737.771.95.95 0 1 1 1.737-.771zm-1.115 2.643 2.047-.91a.868.868 0 0 0 .44-1.145l-.421-.953h1.658v7.474h-3.345a11.714 11.714 0 0 1-.38-4.466zm8.983-.726v-2.203h3.948c.204 0 1.44.236 1.44 1.16 0 .767-.948 1.043-1.728 1.043h-3.66zM43.7 31.898c0 .292-.011.581-.033.868h-1.2c-.12 0-.168.08-.168.197v.551c0 1.298-.732 1.58-1.373 1.652-.61.068-1.288-.256-1.371-.63-.36-2.025-.96-2.458-1.908-3.206 1.176-.746 2.4-1.848 2.4-3.323 0-1.593-1.092-2.596-1.836-3.088-1.044-.688-2.2-.826-2.512-.826H23.285a11.684 11.684 0 0 1 6.545-3.694l1.463 1.535c.331.346.88.36 1.225.028l1.638-1.566a11.71 11.71 0 0 1 8.009 5.704l-1.121 2.532a.869.869 0 0 0 .44 1.145l2.159.958c.037.383.056.77.056 1.163zM31.294 19.093a.95.95 0 0 1 1.344.031.952.952 0 0 1-.032 1.346.949.949 0 0 1-1.343-.032.953.953 0 0 1 .031-1.345zm11.123 8.951a.95.95 0 1 1 1.737.772.95.95 0 1 1-1.737-.772z";
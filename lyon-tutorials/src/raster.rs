@@ -0,0 +1,302 @@
+use lyon::math::{point, vector, Box2D, Point};
+use lyon::tessellation::VertexBuffers;
+
+// How many samples per pixel side the reference takes on the edge of its shape.
+const MY_REFERENCE_SAMPLES: u32 = 16;
+
+// How much of each pixel is covered, from 0 to 1, row by row.
+pub struct MyCoverage {
+    pub width: u32,
+    pub height: u32,
+    pub values: Vec<f32>,
+}
+
+// Each triangle adds the exact area it covers of every pixel it touches, so the shared edges
+// of a fill add up to full coverage without seams. Strokes may overlap themselves, so the
+// sum is clamped, which makes overlapping edges a little too dark.
+pub fn rasterize<I: Copy + Into<u32>>(
+    geometry: &VertexBuffers<Point, I>,
+    view: &Box2D,
+    width: u32,
+    height: u32,
+) -> MyCoverage {
+    let mut coverage = MyCoverage {
+        width,
+        height,
+        values: vec![0.0; (width * height) as usize],
+    };
+    let to_pixels = |p: Point| {
+        point(
+            (p.x - view.min.x) / view.width() * width as f32,
+            (p.y - view.min.y) / view.height() * height as f32,
+        )
+    };
+    for triangle in geometry.indices.chunks_exact(3) {
+        let triangle = [0, 1, 2].map(|i| to_pixels(geometry.vertices[triangle[i].into() as usize]));
+        if area(&triangle).abs() <= f32::EPSILON {
+            continue;
+        }
+        let bounds = Box2D::from_points(triangle);
+        let x_range =
+            bounds.min.x.floor().max(0.0) as u32..(bounds.max.x.ceil().max(0.0) as u32).min(width);
+        let y_range =
+            bounds.min.y.floor().max(0.0) as u32..(bounds.max.y.ceil().max(0.0) as u32).min(height);
+        for y in y_range {
+            for x in x_range.clone() {
+                // relative to the pixel, the area of the clipped corners loses no precision
+                let local = triangle.map(|p| p - vector(x as f32, y as f32));
+                let pixel = Box2D::new(point(0.0, 0.0), point(1.0, 1.0));
+                coverage.values[(y * width + x) as usize] += area(&clip(&local, &pixel)).abs();
+            }
+        }
+    }
+    for value in &mut coverage.values {
+        *value = value.min(1.0);
+    }
+    coverage
+}
+
+// The coverage of a shape given by its signed distance, negative inside, in the units of the view.
+// Pixels away from the edge are fully in or out; the others are supersampled.
+pub fn reference(
+    signed_distance: impl Fn(Point) -> f32,
+    view: &Box2D,
+    width: u32,
+    height: u32,
+) -> MyCoverage {
+    let pixel_size = (view.width() / width as f32).max(view.height() / height as f32);
+    let to_view = |x: f32, y: f32| {
+        point(
+            view.min.x + x / width as f32 * view.width(),
+            view.min.y + y / height as f32 * view.height(),
+        )
+    };
+    let mut values = Vec::with_capacity((width * height) as usize);
+    for y in 0..height {
+        for x in 0..width {
+            let distance = signed_distance(to_view(x as f32 + 0.5, y as f32 + 0.5));
+            let value = if distance <= -pixel_size {
+                1.0
+            } else if distance >= pixel_size {
+                0.0
+            } else {
+                let step = 1.0 / MY_REFERENCE_SAMPLES as f32;
+                let mut inside = 0;
+                for sy in 0..MY_REFERENCE_SAMPLES {
+                    for sx in 0..MY_REFERENCE_SAMPLES {
+                        let p = to_view(
+                            x as f32 + (sx as f32 + 0.5) * step,
+                            y as f32 + (sy as f32 + 0.5) * step,
+                        );
+                        if signed_distance(p) < 0.0 {
+                            inside += 1;
+                        }
+                    }
+                }
+                inside as f32 / (MY_REFERENCE_SAMPLES * MY_REFERENCE_SAMPLES) as f32
+            };
+            values.push(value);
+        }
+    }
+    MyCoverage {
+        width,
+        height,
+        values,
+    }
+}
+
+impl MyCoverage {
+    // The mean and the largest difference of coverage over all pixels.
+    pub fn compare(&self, other: &MyCoverage) -> (f32, f32) {
+        assert_eq!((self.width, self.height), (other.width, other.height));
+        let mut sum = 0.0;
+        let mut max: f32 = 0.0;
+        for (a, b) in self.values.iter().zip(&other.values) {
+            let difference = (a - b).abs();
+            sum += difference;
+            max = max.max(difference);
+        }
+        (sum / self.values.len().max(1) as f32, max)
+    }
+
+    pub fn to_image(&self, color: [u8; 4], background: [u8; 4]) -> image::RgbaImage {
+        image::RgbaImage::from_fn(self.width, self.height, |x, y| {
+            let coverage = self.values[(y * self.width + x) as usize];
+            image::Rgba(std::array::from_fn(|i| {
+                let value =
+                    background[i] as f32 + (color[i] as f32 - background[i] as f32) * coverage;
+                value.round() as u8
+            }))
+        })
+    }
+}
+
+// The signed area of a polygon, by the shoelace formula.
+fn area(polygon: &[Point]) -> f32 {
+    let mut sum = 0.0;
+    for (i, a) in polygon.iter().enumerate() {
+        let b = polygon[(i + 1) % polygon.len()];
+        sum += a.x * b.y - b.x * a.y;
+    }
+    sum / 2.0
+}
+
+// Sutherland-Hodgman against each side of the box.
+fn clip(polygon: &[Point], bounds: &Box2D) -> Vec<Point> {
+    // the axis, x or y, the side, and whether inside is above it
+    let sides = [
+        (0, bounds.min.x, true),
+        (0, bounds.max.x, false),
+        (1, bounds.min.y, true),
+        (1, bounds.max.y, false),
+    ];
+    let mut output = polygon.to_vec();
+    for (axis, limit, above) in sides {
+        let input = std::mem::take(&mut output);
+        let axis = |p: Point| p.to_array()[axis];
+        let inside = |p: Point| (axis(p) >= limit) == above || axis(p) == limit;
+        for (i, &a) in input.iter().enumerate() {
+            let b = input[(i + 1) % input.len()];
+            if inside(a) {
+                output.push(a);
+            }
+            if inside(a) != inside(b) {
+                let t = (limit - axis(a)) / (axis(b) - axis(a));
+                output.push(a.lerp(b, t));
+            }
+        }
+        if output.is_empty() {
+            break;
+        }
+    }
+    output
+}
+
+// The golden images are rendered at this many pixels per unit, with this margin around the
+// shape, in these colors.
+const MY_GOLDEN_SCALE: f32 = 2.0;
+const MY_GOLDEN_MARGIN: f32 = 10.0;
+const MY_COLOR: [u8; 4] = [0xb7, 0x41, 0x0e, 0xff];
+const MY_BACKGROUND: [u8; 4] = [0xff, 0xff, 0xff, 0xff];
+// how far a channel of a pixel may be from the golden image before it counts as different
+const MY_GOLDEN_THRESHOLD: u8 = 2;
+
+// Renders the geometry and compares it with golden/NAME.png, spaces in the name made dashes.
+// With UPDATE_GOLDEN=1 in the environment, the image is written instead.
+pub fn assert_golden<I: Copy + Into<u32>>(name: &str, geometry: &VertexBuffers<Point, I>) {
+    use lyon::math::size;
+
+    let bounds = Box2D::from_points(&geometry.vertices);
+    let view = bounds.inflate(MY_GOLDEN_MARGIN, MY_GOLDEN_MARGIN);
+    let width = (view.width() * MY_GOLDEN_SCALE).ceil() as u32;
+    let height = (view.height() * MY_GOLDEN_SCALE).ceil() as u32;
+    // keep the scale exact whatever the rounding of the size
+    let view = Box2D::from_origin_and_size(
+        view.min,
+        size(width as f32, height as f32) / MY_GOLDEN_SCALE,
+    );
+    let actual = rasterize(geometry, &view, width, height).to_image(MY_COLOR, MY_BACKGROUND);
+
+    let file_name = format!("{}.png", name.replace(' ', "-"));
+    let file = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("golden")
+        .join(&file_name);
+    if std::env::var("UPDATE_GOLDEN").is_ok_and(|value| value == "1") {
+        actual.save(&file).unwrap();
+        return;
+    }
+    let expected = match image::open(&file) {
+        Ok(expected) => expected.to_rgba8(),
+        Err(err) => panic!("{}: {}, run with UPDATE_GOLDEN=1", file.display(), err),
+    };
+    let different = if expected.dimensions() != actual.dimensions() {
+        (width * height) as usize
+    } else {
+        (expected.pixels().zip(actual.pixels()))
+            .filter(|(a, b)| (0..4).any(|i| a[i].abs_diff(b[i]) > MY_GOLDEN_THRESHOLD))
+            .count()
+    };
+    if different > 0 {
+        let actual_file = std::env::temp_dir().join(&file_name);
+        actual.save(&actual_file).unwrap();
+        panic!(
+            "{}: {} pixels differ from {}, see {}",
+            name,
+            different,
+            file.display(),
+            actual_file.display()
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shapes;
+
+    // How far the coverage of the tessellated shape is from the exact shape, at each
+    // tolerance, from coarse to fine.
+    fn check_coverage(
+        tessellate: fn(f32) -> shapes::MyGeometry,
+        signed_distance: shapes::MyDistance,
+    ) {
+        const SCALE: f32 = 4.0;
+        // the reference takes 16 x 16 samples on the edges, so it is only that exact
+        const NOISE: f32 = 1.0 / 64.0;
+
+        let mut last_mean = f32::INFINITY;
+        for tolerance in [1.0, 0.5, 0.2, 0.1, 0.05, 0.01] {
+            let geometry = tessellate(tolerance);
+            let bounds = Box2D::from_points(&geometry.vertices);
+            let view = Box2D::new(
+                point(bounds.min.x.floor() - 1.0, bounds.min.y.floor() - 1.0),
+                point(bounds.max.x.ceil() + 1.0, bounds.max.y.ceil() + 1.0),
+            );
+            let width = (view.width() * SCALE) as u32;
+            let height = (view.height() * SCALE) as u32;
+            let actual = rasterize(&geometry, &view, width, height);
+            let expected = reference(signed_distance, &view, width, height);
+            let (mean, max) = actual.compare(&expected);
+
+            // the curves are cut in halves until they are flat enough, which leaves the chords
+            // up to about twice the tolerance from the curve, and a pixel is off by at most the
+            // part of it between them
+            let max_allowed = (2.0 * tolerance * SCALE).min(1.0) + NOISE;
+            assert!(max <= max_allowed, "{tolerance}: max error {max}");
+            assert!(mean <= last_mean, "{tolerance}: mean error {mean}");
+            last_mean = mean;
+        }
+        assert!(last_mean < 1e-3, "mean error {last_mean}");
+    }
+
+    #[test]
+    fn circle_coverage() {
+        check_coverage(shapes::circle, shapes::circle_distance);
+    }
+
+    #[test]
+    fn rounded_rectangle_coverage() {
+        check_coverage(
+            shapes::rounded_rectangle,
+            shapes::rounded_rectangle_distance,
+        );
+    }
+
+    #[test]
+    fn a_fill_covers_without_seams() {
+        // two triangles that share a diagonal through the pixels
+        let mut geometry: VertexBuffers<Point, u32> = VertexBuffers::new();
+        geometry.vertices = vec![
+            point(0.0, 0.0),
+            point(4.0, 0.0),
+            point(4.0, 4.0),
+            point(0.0, 4.0),
+        ];
+        geometry.indices = vec![0, 1, 2, 0, 2, 3];
+        let view = Box2D::new(point(-1.0, -1.0), point(5.0, 5.0));
+        let coverage = rasterize(&geometry, &view, 12, 12);
+        let square = |p: Point| (p.x - 2.0).abs().max((p.y - 2.0).abs()) - 2.0;
+        let (_, max) = coverage.compare(&reference(square, &view, 12, 12));
+        assert!(max < 1e-4, "max error {max}");
+    }
+}
@@ -0,0 +1,283 @@
+use lyon::math::{point, Box2D, Point};
+use lyon::path::Winding;
+use lyon::tessellation::geometry_builder::simple_builder;
+use lyon::tessellation::{FillOptions, FillTessellator, VertexBuffers};
+
+pub type MyGeometry = VertexBuffers<Point, u16>;
+// a signed distance to the edge of a shape, negative inside
+#[cfg(test)]
+pub type MyDistance = fn(Point) -> f32;
+
+// The shapes of the tutorial, by name, as printed and as rendered to the golden images.
+pub fn all(resource_dir: &std::path::Path, tolerance: f32) -> Vec<(&'static str, MyGeometry)> {
+    vec![
+        ("rectangle", rectangle(tolerance)),
+        ("rounded rectangle", rounded_rectangle(tolerance)),
+        ("star", star(tolerance)),
+        ("dashed star", dashed_star(tolerance)),
+        ("rust logo", rust_logo(resource_dir, tolerance)),
+    ]
+}
+
+pub fn rectangle(tolerance: f32) -> MyGeometry {
+    let mut geometry: MyGeometry = VertexBuffers::new();
+    let mut geometry_builder = simple_builder(&mut geometry);
+    let options = FillOptions::tolerance(tolerance);
+    let mut tessellator = FillTessellator::new();
+    let mut builder = tessellator.builder(&options, &mut geometry_builder);
+
+    builder.add_rectangle(
+        &Box2D {
+            min: point(0.0, 0.0),
+            max: point(100.0, 50.0),
+        },
+        Winding::Positive,
+    );
+    _ = builder.build();
+    geometry
+}
+
+pub const MY_ROUNDED_RECTANGLE: Box2D = Box2D {
+    min: Point::new(0.0, 0.0),
+    max: Point::new(100.0, 50.0),
+};
+pub const MY_ROUNDED_RECTANGLE_RADIUS: f32 = 5.0;
+
+pub fn rounded_rectangle(tolerance: f32) -> MyGeometry {
+    let mut geometry: MyGeometry = VertexBuffers::new();
+    let mut geometry_builder = simple_builder(&mut geometry);
+    let options = FillOptions::tolerance(tolerance);
+    let mut tessellator = FillTessellator::new();
+    let mut builder = tessellator.builder(&options, &mut geometry_builder);
+
+    use lyon::path::builder::BorderRadii;
+
+    builder.add_rounded_rectangle(
+        &MY_ROUNDED_RECTANGLE,
+        &BorderRadii::new(MY_ROUNDED_RECTANGLE_RADIUS),
+        Winding::Positive,
+    );
+    _ = builder.build();
+    geometry
+}
+
+// The MyDistance of the rounded rectangle, for its exact coverage.
+#[cfg(test)]
+pub fn rounded_rectangle_distance(p: Point) -> f32 {
+    let radius = MY_ROUNDED_RECTANGLE_RADIUS;
+    let inner = MY_ROUNDED_RECTANGLE.inflate(-radius, -radius);
+    let nearest = p.clamp(inner.min, inner.max);
+    if nearest == p {
+        // inside the inner rectangle, the nearest edge is a straight one
+        let distance = (p.x - inner.min.x)
+            .min(inner.max.x - p.x)
+            .min(p.y - inner.min.y)
+            .min(inner.max.y - p.y);
+        -distance - radius
+    } else {
+        (p - nearest).length() - radius
+    }
+}
+
+pub fn star(tolerance: f32) -> MyGeometry {
+    stroke_star(&star_path(), tolerance)
+}
+
+// The star in dashes, each with the caps of the stroke. The first dash goes on over the seam.
+pub const MY_DASH_PATTERN: [f32; 4] = [40.0, 15.0, 5.0, 15.0];
+
+pub fn dashed_star(tolerance: f32) -> MyGeometry {
+    let path = crate::dash::dash(&star_path(), &MY_DASH_PATTERN, 0.0, tolerance).unwrap();
+    stroke_star(&path, tolerance)
+}
+
+fn star_path() -> lyon::path::Path {
+    let mut builder = lyon::path::Path::builder();
+
+    const RADIUS: f32 = 150.0;
+    builder.begin(point(240.0 + RADIUS, 160.0));
+    for i in 1..24 {
+        let d = i as f32 * std::f32::consts::PI / 12.0;
+        let r = if i % 2 == 0 { RADIUS } else { RADIUS / 1.5 };
+        builder.line_to(point(240.0 + r * d.cos(), 160.0 + r * d.sin()));
+    }
+    builder.end(true);
+    builder.build()
+}
+
+fn stroke_star(path: &lyon::path::Path, tolerance: f32) -> MyGeometry {
+    use lyon::path::{LineCap, LineJoin};
+    use lyon::tessellation::{StrokeOptions, StrokeTessellator};
+
+    let mut geometry: MyGeometry = VertexBuffers::new();
+    let mut geometry_builder = simple_builder(&mut geometry);
+    let options = StrokeOptions::tolerance(tolerance)
+        .with_start_cap(LineCap::Square)
+        .with_end_cap(LineCap::Round)
+        .with_line_join(LineJoin::Round)
+        .with_line_width(10.0);
+    let mut tessellator = StrokeTessellator::new();
+    tessellator
+        .tessellate_path(path, &options, &mut geometry_builder)
+        .unwrap();
+    geometry
+}
+
+pub fn rust_logo(resource_dir: &std::path::Path, tolerance: f32) -> MyGeometry {
+    let path = tutorial_common::path_file::load(&resource_dir.join("rust-logo.path")).unwrap();
+    let mut geometry: MyGeometry = VertexBuffers::new();
+    let mut geometry_builder = simple_builder(&mut geometry);
+    let options = FillOptions::tolerance(tolerance);
+    let mut tessellator = FillTessellator::new();
+    tessellator
+        .tessellate_path(&path, &options, &mut geometry_builder)
+        .unwrap();
+    geometry
+}
+
+// The rust logo combined with a rounded rectangle over its bottom right quarter, by each operation.
+pub const MY_BOOLEAN_RECTANGLE: Box2D = Box2D {
+    min: Point::new(-40.0, -40.0),
+    max: Point::new(240.0, 240.0),
+};
+pub const MY_BOOLEAN_RECTANGLE_RADIUS: f32 = 40.0;
+
+pub fn booleans(resource_dir: &std::path::Path, tolerance: f32) -> Vec<(&'static str, MyGeometry)> {
+    use crate::boolean::{boolean, MyOperation};
+    use lyon::path::builder::BorderRadii;
+    use lyon::path::{FillRule, Path};
+
+    let logo = tutorial_common::path_file::load(&resource_dir.join("rust-logo.path")).unwrap();
+    let mut builder = Path::builder();
+    builder.add_rounded_rectangle(
+        &MY_BOOLEAN_RECTANGLE,
+        &BorderRadii::new(MY_BOOLEAN_RECTANGLE_RADIUS),
+        Winding::Positive,
+    );
+    let rectangle = builder.build();
+
+    MyOperation::ALL
+        .iter()
+        .map(|&operation| {
+            let name = match operation {
+                MyOperation::Union => "rust logo union",
+                MyOperation::Intersection => "rust logo intersection",
+                MyOperation::Difference => "rust logo difference",
+                MyOperation::Xor => "rust logo xor",
+            };
+            // the logo tessellates with the even-odd rule above, so it is combined with it too
+            let path = boolean(&logo, &rectangle, operation, FillRule::EvenOdd, tolerance);
+            let mut geometry: MyGeometry = VertexBuffers::new();
+            let mut geometry_builder = simple_builder(&mut geometry);
+            let options = FillOptions::tolerance(tolerance);
+            let mut tessellator = FillTessellator::new();
+            tessellator
+                .tessellate_path(&path, &options, &mut geometry_builder)
+                .unwrap();
+            (name, geometry)
+        })
+        .collect()
+}
+
+// A title in glyph outlines, filled, and stroked like the star.
+pub const MY_TITLE: &str = "Hello, lyon!\nfont -> path";
+pub const MY_TITLE_SIZE: f32 = 40.0;
+
+pub fn titles(resource_dir: &std::path::Path, tolerance: f32) -> Vec<(&'static str, MyGeometry)> {
+    use lyon::path::FillRule;
+    use lyon::tessellation::{StrokeOptions, StrokeTessellator};
+
+    let path = crate::text::load(resource_dir, MY_TITLE, MY_TITLE_SIZE)
+        .unwrap()
+        .path();
+
+    let mut filled: MyGeometry = VertexBuffers::new();
+    let options = FillOptions::tolerance(tolerance).with_fill_rule(FillRule::NonZero);
+    FillTessellator::new()
+        .tessellate_path(&path, &options, &mut simple_builder(&mut filled))
+        .unwrap();
+
+    let mut stroked: MyGeometry = VertexBuffers::new();
+    let options = StrokeOptions::tolerance(tolerance).with_line_width(1.5);
+    StrokeTessellator::new()
+        .tessellate_path(&path, &options, &mut simple_builder(&mut stroked))
+        .unwrap();
+
+    vec![("title", filled), ("title outline", stroked)]
+}
+
+#[cfg(test)]
+pub const MY_CIRCLE_CENTER: Point = Point::new(50.0, 50.0);
+#[cfg(test)]
+pub const MY_CIRCLE_RADIUS: f32 = 40.0;
+
+// Not in the tutorial; a shape whose coverage is known exactly, to measure the tolerance.
+// tessellate_circle puts its vertices on the circle, whereas the builder's add_circle goes
+// through quadratic curves that miss it by more than the smaller tolerances.
+#[cfg(test)]
+pub fn circle(tolerance: f32) -> MyGeometry {
+    let mut geometry: MyGeometry = VertexBuffers::new();
+    let mut geometry_builder = simple_builder(&mut geometry);
+    let options = FillOptions::tolerance(tolerance);
+    let mut tessellator = FillTessellator::new();
+    tessellator
+        .tessellate_circle(
+            MY_CIRCLE_CENTER,
+            MY_CIRCLE_RADIUS,
+            &options,
+            &mut geometry_builder,
+        )
+        .unwrap();
+    geometry
+}
+
+// The MyDistance of the circle, for its exact coverage.
+#[cfg(test)]
+pub fn circle_distance(p: Point) -> f32 {
+    (p - MY_CIRCLE_CENTER).length() - MY_CIRCLE_RADIUS
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::raster::assert_golden;
+
+    #[test]
+    fn rectangle_golden() {
+        assert_golden("rectangle", &rectangle(0.2));
+    }
+
+    #[test]
+    fn rounded_rectangle_golden() {
+        assert_golden("rounded rectangle", &rounded_rectangle(0.2));
+    }
+
+    #[test]
+    fn star_golden() {
+        assert_golden("star", &star(0.2));
+    }
+
+    #[test]
+    fn dashed_star_golden() {
+        assert_golden("dashed star", &dashed_star(0.2));
+    }
+
+    #[test]
+    fn rust_logo_golden() {
+        assert_golden("rust logo", &rust_logo(&crate::resource_dir(), 0.2));
+    }
+
+    #[test]
+    fn titles_golden() {
+        for (name, geometry) in titles(&crate::resource_dir(), 0.2) {
+            assert_golden(name, &geometry);
+        }
+    }
+
+    #[test]
+    fn booleans_golden() {
+        for (name, geometry) in booleans(&crate::resource_dir(), 0.2) {
+            assert_golden(name, &geometry);
+        }
+    }
+}
@@ -0,0 +1,397 @@
+use lyon::math::{point, Box2D, Point};
+use lyon::path::Path;
+use lyon::tessellation::VertexBuffers;
+
+// A vertex of an imported shape, in the user space of the SVG document.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MyVertex {
+    pub position: [f32; 2],
+    pub color: [f32; 4],
+}
+
+// The fill or the stroke of one SVG element, tessellated.
+pub struct MyShape {
+    // the id of the element, empty if it has none
+    pub id: String,
+    pub color: [f32; 4],
+    pub geometry: VertexBuffers<MyVertex, u32>,
+}
+
+impl MyShape {
+    pub fn bounds(&self) -> Box2D {
+        let points = (self.geometry.vertices.iter())
+            .map(|vertex| point(vertex.position[0], vertex.position[1]));
+        Box2D::from_points(points)
+    }
+}
+
+// Tessellates every fill and stroke of an SVG document, in the order they are painted.
+// Gradients are drawn with the color of their first stop.
+// Patterns, images and text are left out.
+pub fn import(data: &[u8], tolerance: f32) -> Result<Vec<MyShape>, String> {
+    use usvg::TreeParsing;
+
+    let tree =
+        usvg::Tree::from_data(data, &usvg::Options::default()).map_err(|err| err.to_string())?;
+    let mut shapes = vec![];
+    visit(
+        &tree.root,
+        usvg::Transform::identity(),
+        1.0,
+        tolerance,
+        &mut shapes,
+    )?;
+    Ok(shapes)
+}
+
+// The transform and the opacity are those of all the groups above the node.
+fn visit(
+    node: &usvg::Node,
+    transform: usvg::Transform,
+    opacity: f32,
+    tolerance: f32,
+    shapes: &mut Vec<MyShape>,
+) -> Result<(), String> {
+    use usvg::{NodeKind, PaintOrder, Visibility};
+
+    match &*node.borrow() {
+        NodeKind::Group(group) => {
+            let transform = transform.pre_concat(group.transform);
+            // group opacity goes to each shape, so overlapping shapes of a group show through
+            let opacity = opacity * group.opacity.get();
+            for child in node.children() {
+                visit(&child, transform, opacity, tolerance, shapes)?;
+            }
+        }
+        NodeKind::Path(path) if path.visibility == Visibility::Visible => {
+            let lyon_path = to_path(&path.data);
+            // the path is tessellated where it is defined and then transformed,
+            // so the tolerance is scaled to stay the same on the page
+            let scale = (transform.sx * transform.sy - transform.kx * transform.ky)
+                .abs()
+                .sqrt();
+            let tolerance = tolerance / scale.max(f32::EPSILON);
+            let mut fill = None;
+            if let Some(style) = &path.fill {
+                if let Some(color) = paint_color(&style.paint, opacity * style.opacity.get()) {
+                    let geometry = fill_path(&lyon_path, style.rule, transform, color, tolerance)?;
+                    fill = Some((color, geometry));
+                }
+            }
+            let mut stroke = None;
+            if let Some(style) = &path.stroke {
+                if let Some(color) = paint_color(&style.paint, opacity * style.opacity.get()) {
+                    let geometry = stroke_path(&lyon_path, style, transform, color, tolerance)?;
+                    stroke = Some((color, geometry));
+                }
+            }
+            let painted = match path.paint_order {
+                PaintOrder::FillAndStroke => [fill, stroke],
+                PaintOrder::StrokeAndFill => [stroke, fill],
+            };
+            for (color, geometry) in painted.into_iter().flatten() {
+                shapes.push(MyShape {
+                    id: path.id.clone(),
+                    color,
+                    geometry,
+                });
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+// usvg has already made every segment absolute and turned arcs into curves.
+fn to_path(data: &usvg::tiny_skia_path::Path) -> Path {
+    use usvg::tiny_skia_path::PathSegment;
+
+    let mut builder = Path::builder();
+    let mut open = false;
+    for segment in data.segments() {
+        match segment {
+            PathSegment::MoveTo(to) => {
+                if open {
+                    builder.end(false);
+                }
+                builder.begin(point(to.x, to.y));
+                open = true;
+            }
+            PathSegment::LineTo(to) => {
+                builder.line_to(point(to.x, to.y));
+            }
+            PathSegment::QuadTo(ctrl, to) => {
+                builder.quadratic_bezier_to(point(ctrl.x, ctrl.y), point(to.x, to.y));
+            }
+            PathSegment::CubicTo(ctrl1, ctrl2, to) => {
+                builder.cubic_bezier_to(
+                    point(ctrl1.x, ctrl1.y),
+                    point(ctrl2.x, ctrl2.y),
+                    point(to.x, to.y),
+                );
+            }
+            PathSegment::Close => {
+                if open {
+                    builder.end(true);
+                    open = false;
+                }
+            }
+        }
+    }
+    if open {
+        builder.end(false);
+    }
+    builder.build()
+}
+
+fn paint_color(paint: &usvg::Paint, opacity: f32) -> Option<[f32; 4]> {
+    use usvg::Paint;
+
+    let (color, opacity) = match paint {
+        Paint::Color(color) => (*color, opacity),
+        Paint::LinearGradient(gradient) => {
+            let stop = gradient.stops.first()?;
+            (stop.color, opacity * stop.opacity.get())
+        }
+        Paint::RadialGradient(gradient) => {
+            let stop = gradient.stops.first()?;
+            (stop.color, opacity * stop.opacity.get())
+        }
+        Paint::Pattern(pattern) => {
+            eprintln!("pattern {:?} is not supported", pattern.id);
+            return None;
+        }
+    };
+    Some([
+        color.red as f32 / 255.0,
+        color.green as f32 / 255.0,
+        color.blue as f32 / 255.0,
+        opacity,
+    ])
+}
+
+fn vertex(position: Point, transform: usvg::Transform, color: [f32; 4]) -> MyVertex {
+    let mut position = usvg::tiny_skia_path::Point::from_xy(position.x, position.y);
+    transform.map_point(&mut position);
+    MyVertex {
+        position: [position.x, position.y],
+        color,
+    }
+}
+
+fn fill_path(
+    path: &Path,
+    rule: usvg::FillRule,
+    transform: usvg::Transform,
+    color: [f32; 4],
+    tolerance: f32,
+) -> Result<VertexBuffers<MyVertex, u32>, String> {
+    use lyon::tessellation::{BuffersBuilder, FillOptions, FillRule, FillTessellator, FillVertex};
+
+    let fill_rule = match rule {
+        usvg::FillRule::NonZero => FillRule::NonZero,
+        usvg::FillRule::EvenOdd => FillRule::EvenOdd,
+    };
+    let options = FillOptions::tolerance(tolerance).with_fill_rule(fill_rule);
+    let mut geometry = VertexBuffers::new();
+    let mut geometry_builder = BuffersBuilder::new(&mut geometry, |v: FillVertex| {
+        vertex(v.position(), transform, color)
+    });
+    FillTessellator::new()
+        .tessellate_path(path, &options, &mut geometry_builder)
+        .map_err(|err| format!("{:?}", err))?;
+    Ok(geometry)
+}
+
+// Dashes are not applied; the stroke is drawn solid.
+fn stroke_path(
+    path: &Path,
+    stroke: &usvg::Stroke,
+    transform: usvg::Transform,
+    color: [f32; 4],
+    tolerance: f32,
+) -> Result<VertexBuffers<MyVertex, u32>, String> {
+    use lyon::path::{LineCap, LineJoin};
+    use lyon::tessellation::{BuffersBuilder, StrokeOptions, StrokeTessellator, StrokeVertex};
+
+    let line_cap = match stroke.linecap {
+        usvg::LineCap::Butt => LineCap::Butt,
+        usvg::LineCap::Round => LineCap::Round,
+        usvg::LineCap::Square => LineCap::Square,
+    };
+    let line_join = match stroke.linejoin {
+        usvg::LineJoin::Miter => LineJoin::Miter,
+        usvg::LineJoin::MiterClip => LineJoin::MiterClip,
+        usvg::LineJoin::Round => LineJoin::Round,
+        usvg::LineJoin::Bevel => LineJoin::Bevel,
+    };
+    let options = StrokeOptions::tolerance(tolerance)
+        .with_line_width(stroke.width.get())
+        .with_line_cap(line_cap)
+        .with_line_join(line_join)
+        .with_miter_limit(stroke.miterlimit.get());
+    let mut geometry = VertexBuffers::new();
+    let mut geometry_builder = BuffersBuilder::new(&mut geometry, |v: StrokeVertex| {
+        vertex(v.position(), transform, color)
+    });
+    StrokeTessellator::new()
+        .tessellate_path(path, &options, &mut geometry_builder)
+        .map_err(|err| format!("{:?}", err))?;
+    Ok(geometry)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MY_TOLERANCE: f32 = 0.2;
+
+    fn import_file(name: &str) -> Vec<MyShape> {
+        let data = std::fs::read(crate::resource_dir().join(name)).unwrap();
+        import(&data, MY_TOLERANCE).unwrap()
+    }
+
+    fn import_str(svg: &str) -> Vec<MyShape> {
+        import(svg.as_bytes(), MY_TOLERANCE).unwrap()
+    }
+
+    // the area that the triangles cover, counting overlaps more than once
+    fn area(shape: &MyShape) -> f32 {
+        let position = |index: u32| {
+            let [x, y] = shape.geometry.vertices[index as usize].position;
+            point(x, y)
+        };
+        (shape.geometry.indices.chunks_exact(3))
+            .map(|triangle| {
+                let (a, b, c) = (
+                    position(triangle[0]),
+                    position(triangle[1]),
+                    position(triangle[2]),
+                );
+                (b - a).cross(c - a).abs() / 2.0
+            })
+            .sum()
+    }
+
+    // Flattened curves stay within the tolerance of the outline, so the bounds do too.
+    fn assert_bounds(shape: &MyShape, [min_x, min_y, max_x, max_y]: [f32; 4]) {
+        let bounds = shape.bounds();
+        let expected = Box2D::new(point(min_x, min_y), point(max_x, max_y));
+        let close = (bounds.min - expected.min).length() < MY_TOLERANCE * 2.0
+            && (bounds.max - expected.max).length() < MY_TOLERANCE * 2.0;
+        assert!(close, "{:?} is not {:?}", bounds, expected);
+    }
+
+    fn assert_triangles(shapes: &[MyShape]) {
+        for shape in shapes {
+            let vertex_count = shape.geometry.vertices.len() as u32;
+            assert!(!shape.geometry.indices.is_empty());
+            assert_eq!(shape.geometry.indices.len() % 3, 0);
+            assert!(shape.geometry.indices.iter().all(|&i| i < vertex_count));
+        }
+    }
+
+    #[test]
+    fn rust_logo() {
+        let shapes = import_file("rust-logo-blk.svg");
+        assert_eq!(shapes.len(), 3);
+        assert_triangles(&shapes);
+        for shape in &shapes {
+            assert_eq!(shape.color, [0.0, 0.0, 0.0, 1.0]);
+        }
+        assert_bounds(&shapes[0], [23.78, 23.68, 118.32, 118.22]);
+        assert_bounds(&shapes[1], [20.3, 20.2, 121.8, 121.7]);
+        assert_bounds(&shapes[2], [18.8, 18.7, 123.3, 123.2]);
+    }
+
+    #[test]
+    fn rustacean() {
+        let shapes = import_file("rustacean-flat-happy.svg");
+        assert_eq!(shapes.len(), 9);
+        assert_triangles(&shapes);
+
+        let orange = [0.96862745, 0.29803923, 0.0, 1.0];
+        let colors = shapes.iter().map(|shape| shape.color).collect::<Vec<_>>();
+        assert_eq!(colors[1], orange);
+        assert_eq!(colors[8], orange);
+        assert_eq!(colors[4], [1.0, 1.0, 1.0, 1.0]);
+        assert_eq!(colors[6], [1.0, 1.0, 1.0, 1.0]);
+
+        // every path is drawn around the origin and moved by the matrix of its group,
+        // so without the transforms the shapes would leave the view box
+        let view_box = Box2D::new(point(0.0, 0.0), point(1200.0, 800.0));
+        for shape in &shapes {
+            assert!(
+                view_box.contains_box(&shape.bounds()),
+                "{:?}",
+                shape.bounds()
+            );
+        }
+        // the highlights of the eyes end where the groups put their origins
+        assert_bounds(&shapes[4], [678.299, 396.655, 719.761, 453.687]);
+        assert_bounds(&shapes[6], [501.208, 396.795, 543.968, 455.615]);
+    }
+
+    #[test]
+    fn fill_rule() {
+        // the inner square goes the same way around as the outer one
+        let svg = |rule: &str| {
+            format!(
+                r#"<svg xmlns="http://www.w3.org/2000/svg" width="20" height="20">
+                <path fill-rule="{rule}" d="M0 0H10V10H0Z M2 2H8V8H2Z"/></svg>"#
+            )
+        };
+        let even_odd = import_str(&svg("evenodd"));
+        let non_zero = import_str(&svg("nonzero"));
+        assert!((area(&even_odd[0]) - 64.0).abs() < 1e-3);
+        assert!((area(&non_zero[0]) - 100.0).abs() < 1e-3);
+
+        // the logo has one even-odd path; as non-zero, its holes would be filled
+        let data = std::fs::read_to_string(crate::resource_dir().join("rust-logo-blk.svg"));
+        let data = data.unwrap();
+        assert_eq!(data.matches(r#"fill-rule="evenodd""#).count(), 1);
+        let even_odd = import_str(&data);
+        let non_zero = import_str(&data.replace(r#"fill-rule="evenodd""#, ""));
+        let filled = (even_odd.iter().zip(&non_zero))
+            .filter(|(even_odd, non_zero)| area(non_zero) > area(even_odd) + 1.0)
+            .count();
+        assert_eq!(filled, 1);
+    }
+
+    #[test]
+    fn group_transforms_and_opacity() {
+        let shapes = import_str(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="100" height="100">
+            <g transform="translate(10 20)" opacity="0.5">
+                <g transform="scale(2)">
+                    <rect id="box" width="5" height="5" fill="red" fill-opacity="0.5"/>
+                </g>
+            </g>
+            <rect id="outside" x="50" y="50" width="10" height="10" fill="blue"/>
+            </svg>"#,
+        );
+        assert_eq!(shapes.len(), 2);
+        assert_eq!(shapes[0].id, "box");
+        assert_bounds(&shapes[0], [10.0, 20.0, 20.0, 30.0]);
+        assert_eq!(shapes[0].color, [1.0, 0.0, 0.0, 0.25]);
+        assert!((area(&shapes[0]) - 100.0).abs() < 1e-3);
+        assert_eq!(shapes[1].id, "outside");
+        assert_bounds(&shapes[1], [50.0, 50.0, 60.0, 60.0]);
+        assert_eq!(shapes[1].color, [0.0, 0.0, 1.0, 1.0]);
+    }
+
+    #[test]
+    fn stroke_after_fill() {
+        let shapes = import_str(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="100" height="100">
+            <rect x="10" y="10" width="20" height="20" fill="red" stroke="blue" stroke-width="4"/>
+            </svg>"#,
+        );
+        assert_eq!(shapes.len(), 2);
+        assert_eq!(shapes[0].color, [1.0, 0.0, 0.0, 1.0]);
+        assert_bounds(&shapes[0], [10.0, 10.0, 30.0, 30.0]);
+        // half of the stroke is outside the rectangle
+        assert_eq!(shapes[1].color, [0.0, 0.0, 1.0, 1.0]);
+        assert_bounds(&shapes[1], [8.0, 8.0, 32.0, 32.0]);
+    }
+}
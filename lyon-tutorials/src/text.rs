@@ -0,0 +1,375 @@
+use lyon::math::{point, Point};
+use lyon::path::Path;
+
+pub const MY_FONT_FILE: &str = "LiberationMono-Regular.ttf";
+
+// A glyph of a laid out text, its outline already in place. Glyphs without an outline, such
+// as spaces, have an empty path.
+pub struct MyGlyph {
+    pub character: char,
+    pub id: ttf_parser::GlyphId,
+    pub origin: Point,
+    pub path: Path,
+}
+
+pub struct MyText {
+    pub glyphs: Vec<MyGlyph>,
+    // font units to the units of the paths
+    pub scale: f32,
+    pub line_height: f32,
+}
+
+impl MyText {
+    // All the glyphs in one path. Glyph contours are meant for the non-zero fill rule.
+    pub fn path(&self) -> Path {
+        let mut builder = Path::builder();
+        for glyph in &self.glyphs {
+            for event in glyph.path.iter() {
+                builder.path_event(event);
+            }
+        }
+        builder.build()
+    }
+}
+
+// Lays out the text with the font of the tutorial.
+pub fn load(resource_dir: &std::path::Path, text: &str, size: f32) -> Result<MyText, String> {
+    let file = resource_dir.join(MY_FONT_FILE);
+    let data = std::fs::read(&file).map_err(|err| format!("{}: {}", file.display(), err))?;
+    let face =
+        ttf_parser::Face::parse(&data, 0).map_err(|err| format!("{}: {}", file.display(), err))?;
+    Ok(layout(&face, text, size))
+}
+
+// Feeds the outline of a glyph, in font units with y up, to a lyon path with y down.
+struct MyOutline {
+    builder: lyon::path::path::Builder,
+    origin: Point,
+    scale: f32,
+    open: bool,
+}
+
+impl MyOutline {
+    fn point(&self, x: f32, y: f32) -> Point {
+        point(
+            self.origin.x + x * self.scale,
+            self.origin.y - y * self.scale,
+        )
+    }
+}
+
+impl ttf_parser::OutlineBuilder for MyOutline {
+    fn move_to(&mut self, x: f32, y: f32) {
+        if self.open {
+            self.builder.end(false);
+        }
+        let at = self.point(x, y);
+        self.builder.begin(at);
+        self.open = true;
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        let to = self.point(x, y);
+        self.builder.line_to(to);
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        let (ctrl, to) = (self.point(x1, y1), self.point(x, y));
+        self.builder.quadratic_bezier_to(ctrl, to);
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        let (ctrl1, ctrl2, to) = (self.point(x1, y1), self.point(x2, y2), self.point(x, y));
+        self.builder.cubic_bezier_to(ctrl1, ctrl2, to);
+    }
+
+    fn close(&mut self) {
+        if self.open {
+            self.builder.end(true);
+            self.open = false;
+        }
+    }
+}
+
+// Lays the text out in lines, `size` units to the em, the first baseline one ascender below
+// y = 0. Each glyph goes one advance after the last one, moved by the kerning of the pair if
+// the font has a kern table; a new line goes back to x = 0, one line height lower.
+// Characters missing from the font take the glyph 0, the .notdef box.
+pub fn layout(face: &ttf_parser::Face, text: &str, size: f32) -> MyText {
+    use ttf_parser::GlyphId;
+
+    let scale = size / face.units_per_em() as f32;
+    let line_height =
+        (face.ascender() as f32 - face.descender() as f32 + face.line_gap() as f32) * scale;
+    let mut glyphs = vec![];
+    let mut origin = point(0.0, face.ascender() as f32 * scale);
+    let mut previous: Option<GlyphId> = None;
+    for character in text.chars() {
+        if character == '\n' {
+            origin = point(0.0, origin.y + line_height);
+            previous = None;
+            continue;
+        }
+        let id = face.glyph_index(character).unwrap_or(GlyphId(0));
+        if let Some(previous) = previous {
+            origin.x += kerning(face, previous, id) as f32 * scale;
+        }
+
+        let mut outline = MyOutline {
+            builder: Path::builder(),
+            origin,
+            scale,
+            open: false,
+        };
+        face.outline_glyph(id, &mut outline);
+        if outline.open {
+            outline.builder.end(false);
+        }
+        glyphs.push(MyGlyph {
+            character,
+            id,
+            origin,
+            path: outline.builder.build(),
+        });
+
+        origin.x += face.glyph_hor_advance(id).unwrap_or(0) as f32 * scale;
+        previous = Some(id);
+    }
+    MyText {
+        glyphs,
+        scale,
+        line_height,
+    }
+}
+
+// From the horizontal subtables of the kern table. Kerning in GPOS is not looked at.
+pub fn kerning(
+    face: &ttf_parser::Face,
+    left: ttf_parser::GlyphId,
+    right: ttf_parser::GlyphId,
+) -> i16 {
+    let Some(kern) = face.tables().kern else {
+        return 0;
+    };
+    kern.subtables
+        .into_iter()
+        .filter(|subtable| subtable.horizontal && !subtable.variable && !subtable.has_cross_stream)
+        .filter_map(|subtable| subtable.glyphs_kerning(left, right))
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lyon::math::Box2D;
+    use ttf_parser::{Face, GlyphId};
+
+    const MY_SIZE: f32 = 100.0;
+    // how far f32 rounding may move a point, in the units of the paths
+    const MY_MAX_ERROR: f32 = 1e-3 * MY_SIZE;
+
+    fn font() -> Vec<u8> {
+        std::fs::read(crate::resource_dir().join(MY_FONT_FILE)).unwrap()
+    }
+
+    fn glyph_id(face: &Face, character: char) -> GlyphId {
+        face.glyph_index(character).unwrap()
+    }
+
+    fn advance(face: &Face, character: char) -> f32 {
+        let scale = MY_SIZE / face.units_per_em() as f32;
+        face.glyph_hor_advance(glyph_id(face, character)).unwrap() as f32 * scale
+    }
+
+    fn assert_near(actual: Point, expected: Point, what: &str) {
+        let error = (actual - expected).length();
+        assert!(
+            error <= MY_MAX_ERROR,
+            "{what}: at {actual:?}, not {expected:?}"
+        );
+    }
+
+    // The font with a kern table of the pairs added. LiberationMono has none, being monospaced.
+    fn with_kerning(font: &[u8], pairs: &[(GlyphId, GlyphId, i16)]) -> Vec<u8> {
+        let u16_at = |offset: usize| u16::from_be_bytes([font[offset], font[offset + 1]]);
+        let u32_at =
+            |offset: usize| u32::from_be_bytes(font[offset..offset + 4].try_into().unwrap());
+
+        // a version 0 kern table with one horizontal format 0 subtable
+        let mut pairs = pairs.to_vec();
+        pairs.sort_by_key(|&(left, right, _)| (left, right));
+        let mut subtable = vec![];
+        for value in [pairs.len() as u16, 0, 0, 0] {
+            subtable.extend(value.to_be_bytes());
+        }
+        for (left, right, value) in pairs {
+            subtable.extend(left.0.to_be_bytes());
+            subtable.extend(right.0.to_be_bytes());
+            subtable.extend(value.to_be_bytes());
+        }
+        let mut kern = vec![];
+        for value in [0, 1, 0, 6 + subtable.len() as u16, 0x0001] {
+            kern.extend(u16::to_be_bytes(value));
+        }
+        kern.extend(subtable);
+
+        // one more table record, in the order of the tags, moves every table 16 bytes on
+        let count = u16_at(4) as usize;
+        let mut records = (0..count)
+            .map(|i| {
+                let record = 12 + 16 * i;
+                let tag: [u8; 4] = font[record..record + 4].try_into().unwrap();
+                (
+                    tag,
+                    u32_at(record + 4),
+                    u32_at(record + 8) + 16,
+                    u32_at(record + 12),
+                )
+            })
+            .collect::<Vec<_>>();
+        let end = font.len().next_multiple_of(4);
+        records.push((*b"kern", 0, (end + 16) as u32, kern.len() as u32));
+        records.sort_by_key(|&(tag, ..)| tag);
+
+        let mut data = font[..4].to_vec();
+        data.extend((count as u16 + 1).to_be_bytes());
+        data.extend(&font[6..12]);
+        for (tag, checksum, offset, length) in records {
+            data.extend(tag);
+            data.extend(checksum.to_be_bytes());
+            data.extend(offset.to_be_bytes());
+            data.extend(length.to_be_bytes());
+        }
+        data.extend(&font[12 + 16 * count..]);
+        data.resize(end + 16, 0);
+        data.extend(kern);
+        data
+    }
+
+    #[test]
+    fn glyphs_follow_their_advances() {
+        let data = font();
+        let face = Face::parse(&data, 0).unwrap();
+        let text = (' '..='~').collect::<String>();
+        let laid_out = layout(&face, &text, MY_SIZE);
+        assert_eq!(laid_out.scale, MY_SIZE / face.units_per_em() as f32);
+
+        let baseline = face.ascender() as f32 * laid_out.scale;
+        let mut x = 0.0;
+        assert_eq!(laid_out.glyphs.len(), text.len());
+        for (glyph, character) in laid_out.glyphs.iter().zip(text.chars()) {
+            assert_eq!(
+                (glyph.character, glyph.id),
+                (character, glyph_id(&face, character))
+            );
+            assert_near(glyph.origin, point(x, baseline), &format!("{character:?}"));
+            x += advance(&face, character);
+        }
+        // a monospaced font, so every advance is the same
+        assert!((x - text.len() as f32 * advance(&face, 'M')).abs() <= MY_MAX_ERROR);
+    }
+
+    #[test]
+    fn outlines_are_in_place() {
+        let data = font();
+        let face = Face::parse(&data, 0).unwrap();
+        // a character that the font does not have takes the .notdef glyph
+        let text = (' '..='~').chain(['\u{1F980}']).collect::<String>();
+        let laid_out = layout(&face, &text, MY_SIZE);
+        let notdef = laid_out.glyphs.last().unwrap();
+        assert_eq!(notdef.id, GlyphId(0));
+
+        let scale = laid_out.scale;
+        let in_place = |rect: ttf_parser::Rect, origin: Point| Box2D {
+            min: point(
+                origin.x + rect.x_min as f32 * scale,
+                origin.y - rect.y_max as f32 * scale,
+            ),
+            max: point(
+                origin.x + rect.x_max as f32 * scale,
+                origin.y - rect.y_min as f32 * scale,
+            ),
+        };
+        for glyph in &laid_out.glyphs {
+            let Some(rect) = face.glyph_bounding_box(glyph.id) else {
+                assert_eq!(glyph.path.iter().count(), 0, "{:?}", glyph.character);
+                continue;
+            };
+            // the box of the font goes through the points of the outline, control points too,
+            // and y is flipped
+            let expected = in_place(rect, glyph.origin);
+            let actual = lyon::algorithms::aabb::fast_bounding_box(glyph.path.iter());
+            let what = format!("{:?}", glyph.character);
+            assert_near(actual.min, expected.min, &what);
+            assert_near(actual.max, expected.max, &what);
+            let global = in_place(face.global_bounding_box(), glyph.origin)
+                .inflate(MY_MAX_ERROR, MY_MAX_ERROR);
+            assert!(
+                global.contains_box(&actual),
+                "{what}: out of the font bounds"
+            );
+        }
+        assert_eq!(
+            laid_out.glyphs[0].path.iter().count(),
+            0,
+            "a space has no outline"
+        );
+    }
+
+    #[test]
+    fn lines_break_back_to_the_start() {
+        let data = font();
+        let face = Face::parse(&data, 0).unwrap();
+        let laid_out = layout(&face, "ab\ncd\n\ne", MY_SIZE);
+        let line_height = laid_out.line_height;
+        let expected = (face.ascender() - face.descender() + face.line_gap()) as f32;
+        assert!((line_height - expected * laid_out.scale).abs() <= MY_MAX_ERROR);
+
+        // the line breaks have no glyphs, and an empty line still takes its height
+        let baseline = face.ascender() as f32 * laid_out.scale;
+        let origins = [
+            ('a', point(0.0, baseline)),
+            ('b', point(advance(&face, 'a'), baseline)),
+            ('c', point(0.0, baseline + line_height)),
+            ('d', point(advance(&face, 'c'), baseline + line_height)),
+            ('e', point(0.0, baseline + 3.0 * line_height)),
+        ];
+        assert_eq!(laid_out.glyphs.len(), origins.len());
+        for (glyph, (character, origin)) in laid_out.glyphs.iter().zip(origins) {
+            assert_eq!(glyph.character, character);
+            assert_near(glyph.origin, origin, &format!("{character:?}"));
+        }
+    }
+
+    #[test]
+    fn kerning_pairs_move_the_glyphs() {
+        let data = font();
+        let face = Face::parse(&data, 0).unwrap();
+        let (a, v) = (glyph_id(&face, 'A'), glyph_id(&face, 'V'));
+        assert_eq!(kerning(&face, a, v), 0);
+
+        let data = with_kerning(&data, &[(a, v, -200), (v, a, -100)]);
+        let face = Face::parse(&data, 0).unwrap();
+        assert_eq!(kerning(&face, a, v), -200);
+        assert_eq!(kerning(&face, v, a), -100);
+        assert_eq!(kerning(&face, a, a), 0);
+
+        let laid_out = layout(&face, "AVAA\nVA", MY_SIZE);
+        let scale = laid_out.scale;
+        let baseline = face.ascender() as f32 * scale;
+        let step = advance(&face, 'A');
+        let expected = [
+            point(0.0, baseline),
+            point(step - 200.0 * scale, baseline),
+            point(2.0 * step - 300.0 * scale, baseline),
+            // no pair, no kerning
+            point(3.0 * step - 300.0 * scale, baseline),
+            // nor across a line break, from the A before it
+            point(0.0, baseline + laid_out.line_height),
+            point(step - 100.0 * scale, baseline + laid_out.line_height),
+        ];
+        for (glyph, expected) in laid_out.glyphs.iter().zip(expected) {
+            assert_near(glyph.origin, expected, &format!("{:?}", glyph.character));
+        }
+    }
+}
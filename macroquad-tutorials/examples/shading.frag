@@ -0,0 +1,20 @@
+#version 330
+precision lowp float;
+
+uniform sampler2D Texture;
+uniform vec3 view;
+uniform vec3 light;
+layout(location=0) in vec3 v_position;
+layout(location=1) in vec2 v_uv;
+layout(location=2) in vec4 v_color;
+layout(location=3) in vec3 v_normal;
+out vec4 FragColor;
+
+void main() {
+    vec3 lightDir = normalize(light - v_position);
+    float diff = max(dot(lightDir, v_normal), 0.3);
+    vec3 viewDir = normalize(v_position - view);
+    float spec = 0.5 * pow(max(dot(viewDir, reflect(lightDir, v_normal)), 0.0), 64);
+    vec4 intensity = vec4(diff + spec, diff + spec, diff + spec, 1.0);
+    FragColor = v_color * texture(Texture, v_uv) * intensity;
+}
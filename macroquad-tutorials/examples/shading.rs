@@ -249,50 +249,6 @@ const MY_INDEX_DATA: [u16; 36] = [
     20, 21, 22, 21, 22, 23,
 ];
 
-const MY_VERTEX_SHADER_SOURCE: &'static str = r"
-#version 330
-precision lowp float;
+const MY_VERTEX_SHADER_SOURCE: &str = include_str!("shading.vert");
 
-uniform mat4 Model;
-uniform mat4 Projection;
-layout(location=0) in vec3 position;
-layout(location=1) in vec2 texcoord;
-layout(location=2) in vec4 color0;
-layout(location=3) in vec3 normal;
-layout(location=0) out vec3 v_position;
-layout(location=1) out vec2 v_uv;
-layout(location=2) out vec4 v_color;
-layout(location=3) out vec3 v_normal;
-
-void main() {
-    vec4 pos = Model * vec4(position, 1);
-    gl_Position = Projection * pos;
-    v_position = vec3(pos);
-    v_uv = texcoord;
-    v_color = color0 / 255.0;
-    v_normal = normalize(mat3(transpose(inverse(Model))) * normal);
-}
-";
-
-const MY_FRAGMENT_SHADER_SOURCE: &'static str = r"
-#version 330
-precision lowp float;
-
-uniform sampler2D Texture;
-uniform vec3 view;
-uniform vec3 light;
-layout(location=0) in vec3 v_position;
-layout(location=1) in vec2 v_uv;
-layout(location=2) in vec4 v_color;
-layout(location=3) in vec3 v_normal;
-out vec4 FragColor;
-
-void main() {
-    vec3 lightDir = normalize(light - v_position);
-    float diff = max(dot(lightDir, v_normal), 0.3);
-    vec3 viewDir = normalize(v_position - view);
-    float spec = 0.5 * pow(max(dot(viewDir, reflect(lightDir, v_normal)), 0.0), 64);
-    vec4 intensity = vec4(diff + spec, diff + spec, diff + spec, 1.0);
-    FragColor = v_color * texture(Texture, v_uv) * intensity;
-}
-";
+const MY_FRAGMENT_SHADER_SOURCE: &str = include_str!("shading.frag");
@@ -0,0 +1,22 @@
+#version 330
+precision lowp float;
+
+uniform mat4 Model;
+uniform mat4 Projection;
+layout(location=0) in vec3 position;
+layout(location=1) in vec2 texcoord;
+layout(location=2) in vec4 color0;
+layout(location=3) in vec3 normal;
+layout(location=0) out vec3 v_position;
+layout(location=1) out vec2 v_uv;
+layout(location=2) out vec4 v_color;
+layout(location=3) out vec3 v_normal;
+
+void main() {
+    vec4 pos = Model * vec4(position, 1);
+    gl_Position = Projection * pos;
+    v_position = vec3(pos);
+    v_uv = texcoord;
+    v_color = color0 / 255.0;
+    v_normal = normalize(mat3(transpose(inverse(Model))) * normal);
+}
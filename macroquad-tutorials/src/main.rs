@@ -1,5 +1,8 @@
 use macroquad::prelude::*;
 
+use tutorial_common::args::Args;
+use tutorial_common::locale;
+
 fn window_conf() -> Conf {
     Conf {
         window_title: "macroquad tutorial".to_owned(),
@@ -16,6 +19,13 @@ async fn main() {
     set_pc_assets_folder("../assets");
     let ferris = load_texture("ferris.png").await.unwrap();
 
+    // the same folder as the assets, read for the glyphs each font has
+    let fonts = locale::FontFallback::load(std::path::Path::new("../assets"));
+    let ttf_fonts = (fonts.fonts().iter())
+        .map(|font| load_ttf_font_from_bytes(&font.data).unwrap())
+        .collect::<Vec<_>>();
+    let strings = locale::Language::from_args(&Args::from_env(&["--lang"])).strings();
+
     while !is_key_pressed(KeyCode::Escape) {
         clear_background(Color::new(0.2, 0.2, 0.2, 1.));
 
@@ -32,8 +42,24 @@ async fn main() {
             draw_texture(&ferris, pos.x, pos.y, WHITE);
         }
         {
-            let text = format!("FPS: {:?} / POS: {:?}", get_fps(), mouse_position());
-            draw_text(&text, 0., 24. / 2., 24., WHITE);
+            let text = format!(
+                "{} / {}",
+                locale::fill(strings.fps, get_fps()),
+                locale::fill(strings.position, format!("{:?}", mouse_position()))
+            );
+            // each run is drawn with its own font, right after the one before it
+            let mut x = 0.;
+            for (font, run) in fonts.runs(&text) {
+                let font = ttf_fonts.get(font);
+                let params = TextParams {
+                    font,
+                    font_size: 24,
+                    color: WHITE,
+                    ..Default::default()
+                };
+                draw_text_ex(run, x, 24. / 2., params);
+                x += measure_text(run, font, 24, 1.).width;
+            }
         }
 
         next_frame().await
@@ -0,0 +1,356 @@
+// Translates the WGSL shaders into the GLSL and MSL sources that miniquad expects,
+// and generates ShaderMeta and the vertex attributes from their reflection.
+use naga::{AddressSpace, Binding, Module, ScalarKind, ShaderStage, TypeInner, VectorSize};
+
+fn main() {
+    let out_dir = std::env::var("OUT_DIR").unwrap();
+    for (name, path) in [
+        ("shader", "src/shader.wgsl"),
+        ("grayscale", "src/post_effects/grayscale.wgsl"),
+        ("blur", "src/post_effects/blur.wgsl"),
+        ("crt", "src/post_effects/crt.wgsl"),
+        (
+            "chromatic_aberration",
+            "src/post_effects/chromatic_aberration.wgsl",
+        ),
+    ] {
+        println!("cargo:rerun-if-changed={path}");
+        let shader = translate(path);
+        std::fs::write(format!("{out_dir}/{name}.rs"), shader).unwrap();
+    }
+}
+
+fn translate(path: &str) -> String {
+    use naga::valid::{Capabilities, ValidationFlags, Validator};
+
+    let source = std::fs::read_to_string(path).unwrap();
+    let module = naga::front::wgsl::parse_str(&source)
+        .unwrap_or_else(|err| panic!("{}", err.emit_to_string_with_path(&source, path)));
+    let info = Validator::new(ValidationFlags::all(), Capabilities::all())
+        .validate(&module)
+        .unwrap_or_else(|err| panic!("{}", err.emit_to_string_with_path(&source, path)));
+
+    let vertex_entry_point = entry_point(&module, ShaderStage::Vertex);
+    let fragment_entry_point = entry_point(&module, ShaderStage::Fragment);
+    let vertex_glsl = write_glsl(&module, &info, ShaderStage::Vertex, vertex_entry_point);
+    let fragment_glsl = write_glsl(&module, &info, ShaderStage::Fragment, fragment_entry_point);
+    let msl = write_msl(&module, &info, &[vertex_entry_point, fragment_entry_point]);
+    let meta = write_meta(&module, vertex_entry_point);
+    format!(
+        r"// Generated by build.rs from {path}.
+pub const VERTEX_SHADER_SOURCE: &str = {vertex_glsl:?};
+pub const FRAGMENT_SHADER_SOURCE: &str = {fragment_glsl:?};
+pub const METAL_SHADER_SOURCE: &str = {msl:?};
+
+pub fn source(backend: miniquad::Backend) -> miniquad::ShaderSource<'static> {{
+    match backend {{
+        miniquad::Backend::OpenGl => miniquad::ShaderSource::Glsl {{
+            vertex: VERTEX_SHADER_SOURCE,
+            fragment: FRAGMENT_SHADER_SOURCE,
+        }},
+        miniquad::Backend::Metal => miniquad::ShaderSource::Msl {{
+            program: METAL_SHADER_SOURCE,
+        }},
+    }}
+}}
+
+{meta}"
+    )
+}
+
+fn entry_point(module: &Module, stage: ShaderStage) -> &str {
+    let entry_point = module
+        .entry_points
+        .iter()
+        .find(|entry_point| entry_point.stage == stage)
+        .unwrap_or_else(|| panic!("No {stage:?} entry point."));
+    &entry_point.name
+}
+
+fn write_glsl(
+    module: &Module,
+    info: &naga::valid::ModuleInfo,
+    stage: ShaderStage,
+    entry_point: &str,
+) -> String {
+    use naga::back::glsl::{Options, PipelineOptions, Version, Writer, WriterFlags};
+
+    let options = Options {
+        version: Version::Desktop(330),
+        writer_flags: WriterFlags::empty(),
+        ..Default::default()
+    };
+    let pipeline_options = PipelineOptions {
+        shader_stage: stage,
+        entry_point: entry_point.to_owned(),
+        multiview: None,
+    };
+    let mut glsl = String::new();
+    let reflection = Writer::new(
+        &mut glsl,
+        module,
+        info,
+        &options,
+        &pipeline_options,
+        Default::default(),
+    )
+    .and_then(|mut writer| writer.write())
+    .unwrap_or_else(|err| panic!("Failed to write GLSL for {entry_point}: {err}"));
+
+    // miniquad looks up everything by the names used in the WGSL source. The names that naga
+    // gave the uniforms and textures come from its reflection, and one that is not found where
+    // it is expected fails the build rather than leaving a shader that binds nothing.
+    let mut renames = vec![];
+    for (&handle, block) in &reflection.uniforms {
+        if module.global_variables[handle].space != AddressSpace::Uniform {
+            continue;
+        }
+        // miniquad sets uniforms with glUniform*, which does not work for uniform blocks, so
+        // `uniform Block { Type instance; };` becomes `uniform Type instance;`
+        let prefix = format!("uniform {block} {{");
+        let line = glsl
+            .lines()
+            .find(|line| line.starts_with(&prefix))
+            .unwrap_or_else(|| panic!("No uniform block {block} in the GLSL for {entry_point}."));
+        let (ty, instance) = line[prefix.len()..]
+            .trim()
+            .strip_suffix("};")
+            .and_then(|member| member.trim().strip_suffix(';'))
+            .and_then(|member| member.split_once(' '))
+            .unwrap_or_else(|| panic!("Unexpected uniform block in {entry_point}: {line}"));
+        let flattened = format!("uniform {ty} {instance};");
+        renames.push((instance.to_owned(), global_name(module, handle)));
+        glsl = glsl.replacen(line, &flattened, 1);
+    }
+    for (texture, mapping) in &reflection.texture_mapping {
+        renames.push((texture.clone(), global_name(module, mapping.texture)));
+    }
+    if stage == ShaderStage::Vertex {
+        // naga only reflects the inputs when GLSL has no locations, so they are read from the
+        // declarations: `layout(location = N) in type name;`
+        for (location, name, _) in vertex_attributes(module, entry_point) {
+            let declaration = format!("layout(location = {location}) in ");
+            let input = glsl
+                .lines()
+                .find_map(|line| line.strip_prefix(&declaration))
+                .and_then(|line| line.strip_suffix(';')?.split_whitespace().last())
+                .unwrap_or_else(|| panic!("No input at location {location} in {entry_point}."));
+            renames.push((input.to_owned(), name));
+        }
+    }
+    for (from, to) in renames {
+        glsl = rename(&glsl, &from, &to)
+            .unwrap_or_else(|| panic!("'{from}' is not in the GLSL for {entry_point}."));
+    }
+    glsl
+}
+
+fn global_name(module: &Module, handle: naga::Handle<naga::GlobalVariable>) -> String {
+    let global = &module.global_variables[handle];
+    global.name.clone().expect("Bound globals must be named.")
+}
+
+// Replaces the identifier where it stands on its own, so that renaming `a1` leaves `a10` alone.
+// Returns None if it is nowhere.
+fn rename(source: &str, from: &str, to: &str) -> Option<String> {
+    let is_identifier = |c: char| c.is_ascii_alphanumeric() || c == '_';
+    let mut renamed = String::with_capacity(source.len());
+    let mut rest = source;
+    let mut found = false;
+    while let Some(index) = rest.find(from) {
+        let before = rest[..index].chars().last().or(renamed.chars().last());
+        let after = rest[index + from.len()..].chars().next();
+        renamed.push_str(&rest[..index]);
+        if before.is_some_and(is_identifier) || after.is_some_and(is_identifier) {
+            renamed.push_str(from);
+        } else {
+            renamed.push_str(to);
+            found = true;
+        }
+        rest = &rest[index + from.len()..];
+    }
+    renamed.push_str(rest);
+    found.then_some(renamed)
+}
+
+fn write_msl(module: &Module, info: &naga::valid::ModuleInfo, entry_points: &[&str]) -> String {
+    use naga::back::msl::{
+        BindSamplerTarget, BindTarget, EntryPointResources, Options, PipelineOptions,
+    };
+
+    // miniquad binds the uniforms to buffer 0, and the vertex buffers after them.
+    // The n-th image goes to texture n and sampler n.
+    let mut resources = EntryPointResources::default();
+    let (mut textures, mut samplers) = (0, 0);
+    for (_, global) in module.global_variables.iter() {
+        let Some(binding) = &global.binding else {
+            continue;
+        };
+        let target = match (global.space, &module.types[global.ty].inner) {
+            (AddressSpace::Uniform, _) => BindTarget {
+                buffer: Some(0),
+                ..Default::default()
+            },
+            (_, TypeInner::Image { .. }) => {
+                textures += 1;
+                BindTarget {
+                    texture: Some(textures - 1),
+                    ..Default::default()
+                }
+            }
+            (_, TypeInner::Sampler { .. }) => {
+                samplers += 1;
+                BindTarget {
+                    sampler: Some(BindSamplerTarget::Resource(samplers - 1)),
+                    ..Default::default()
+                }
+            }
+            _ => continue,
+        };
+        resources.resources.insert(binding.clone(), target);
+    }
+    let options = Options {
+        lang_version: (1, 2),
+        per_entry_point_map: entry_points
+            .iter()
+            .map(|entry_point| (entry_point.to_string(), resources.clone()))
+            .collect(),
+        fake_missing_bindings: false,
+        ..Default::default()
+    };
+    let (msl, _) =
+        naga::back::msl::write_string(module, info, &options, &PipelineOptions::default())
+            .unwrap_or_else(|err| panic!("Failed to write MSL: {err}"));
+    msl
+}
+
+fn write_meta(module: &Module, vertex_entry_point: &str) -> String {
+    let mut images = vec![];
+    let mut uniforms = vec![];
+    for (_, global) in module.global_variables.iter() {
+        let name = global.name.as_deref().unwrap();
+        if let TypeInner::Image { .. } = module.types[global.ty].inner {
+            images.push(format!("\"{name}\".to_string()"));
+        }
+        if global.space != AddressSpace::Uniform {
+            continue;
+        }
+        let TypeInner::Struct { members, .. } = &module.types[global.ty].inner else {
+            panic!("Uniform '{name}' must be a struct.");
+        };
+
+        // the struct is uploaded as it is, so GL and Metal must agree on its layout
+        let mut offset = 0;
+        for member in members {
+            let member_name = member.name.as_deref().unwrap();
+            assert_eq!(
+                member.offset, offset,
+                "Uniform '{name}.{member_name}' is not tightly packed."
+            );
+            let (uniform_type, size) = uniform_type(&module.types[member.ty].inner);
+            uniforms.push(format!(
+                "UniformDesc::new(\"{name}.{member_name}\", UniformType::{uniform_type})"
+            ));
+            offset += size;
+        }
+    }
+
+    let mut attributes = vertex_attributes(module, vertex_entry_point);
+    attributes.sort_by_key(|(location, ..)| *location);
+    let attributes = attributes
+        .iter()
+        .map(|(_, name, format)| {
+            format!("VertexAttribute::new(\"{name}\", VertexFormat::{format})")
+        })
+        .collect::<Vec<_>>();
+
+    format!(
+        r"use miniquad::{{ShaderMeta, UniformBlockLayout, UniformDesc, UniformType}};
+use miniquad::{{VertexAttribute, VertexFormat}};
+
+pub fn meta() -> ShaderMeta {{
+    ShaderMeta {{
+        images: vec![{}],
+        uniforms: UniformBlockLayout {{
+            uniforms: vec![{}],
+        }},
+    }}
+}}
+
+pub fn attributes() -> Vec<VertexAttribute> {{
+    vec![{}]
+}}
+",
+        images.join(", "),
+        uniforms.join(", "),
+        attributes.join(", "),
+    )
+}
+
+// Returns (location, name, VertexFormat) of each vertex input.
+fn vertex_attributes(module: &Module, entry_point: &str) -> Vec<(u32, String, &'static str)> {
+    let function = &module
+        .entry_points
+        .iter()
+        .find(|ep| ep.name == entry_point)
+        .unwrap()
+        .function;
+    let mut attributes = vec![];
+    for argument in &function.arguments {
+        match (&argument.binding, &module.types[argument.ty].inner) {
+            (Some(Binding::Location { location, .. }), inner) => {
+                let name = argument.name.clone().unwrap();
+                attributes.push((*location, name, vertex_format(inner)));
+            }
+            (None, TypeInner::Struct { members, .. }) => {
+                for member in members {
+                    if let Some(Binding::Location { location, .. }) = member.binding {
+                        let name = member.name.clone().unwrap();
+                        let inner = &module.types[member.ty].inner;
+                        attributes.push((location, name, vertex_format(inner)));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    attributes
+}
+
+fn vertex_format(inner: &TypeInner) -> &'static str {
+    match inner {
+        TypeInner::Scalar(scalar) if scalar.kind == ScalarKind::Float => "Float1",
+        TypeInner::Vector { size, scalar } if scalar.kind == ScalarKind::Float => match size {
+            VectorSize::Bi => "Float2",
+            VectorSize::Tri => "Float3",
+            VectorSize::Quad => "Float4",
+        },
+        _ => panic!("Unsupported vertex attribute type: {inner:?}"),
+    }
+}
+
+// Returns the UniformType and its size in bytes.
+fn uniform_type(inner: &TypeInner) -> (&'static str, u32) {
+    match inner {
+        TypeInner::Scalar(scalar) => match scalar.kind {
+            ScalarKind::Float => ("Float1", 4),
+            ScalarKind::Sint => ("Int1", 4),
+            _ => panic!("Unsupported uniform type: {inner:?}"),
+        },
+        TypeInner::Vector { size, scalar } => match (scalar.kind, size) {
+            (ScalarKind::Float, VectorSize::Bi) => ("Float2", 8),
+            (ScalarKind::Float, VectorSize::Tri) => ("Float3", 12),
+            (ScalarKind::Float, VectorSize::Quad) => ("Float4", 16),
+            (ScalarKind::Sint, VectorSize::Bi) => ("Int2", 8),
+            (ScalarKind::Sint, VectorSize::Tri) => ("Int3", 12),
+            (ScalarKind::Sint, VectorSize::Quad) => ("Int4", 16),
+            _ => panic!("Unsupported uniform type: {inner:?}"),
+        },
+        TypeInner::Matrix {
+            columns: VectorSize::Quad,
+            rows: VectorSize::Quad,
+            ..
+        } => ("Mat4", 64),
+        _ => panic!("Unsupported uniform type: {inner:?}"),
+    }
+}
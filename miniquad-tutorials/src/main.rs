@@ -1,13 +1,16 @@
+mod post_process;
+
 fn main() {
     use miniquad::conf::{AppleGfxApi, Conf};
+    use tutorial_common::args::Args;
 
-    let metal = std::env::args().nth(1).as_deref() == Some("metal");
+    let args = Args::from_env(&["--mesh", "--metal"]);
     let conf = Conf {
         window_title: "miniquad tutorial".to_string(),
         window_width: 480,
         window_height: 320,
         platform: miniquad::conf::Platform {
-            apple_gfx_api: if metal {
+            apple_gfx_api: if args.metal {
                 AppleGfxApi::Metal
             } else {
                 AppleGfxApi::OpenGl
@@ -17,33 +20,44 @@ fn main() {
         ..Default::default()
     };
 
-    miniquad::start(conf, move || Box::new(MyApp::new()));
+    let mesh = MeshFile::from_args(&args);
+    miniquad::start(conf, move || Box::new(MyApp::new(mesh)));
 }
 
 use miniquad::{Bindings, Pipeline, RenderingBackend};
+use post_process::PostProcess;
+use tutorial_common::mesh_file::MeshFile;
 
 struct MyApp {
     pipeline: Pipeline,
     bindings: Bindings,
+    num_indices: i32,
+    post_process: PostProcess,
     ctx: Box<dyn RenderingBackend>,
     angle_y: f32,
+    time: f32,
 }
 
 impl MyApp {
-    pub fn new() -> MyApp {
+    pub fn new(mesh: Option<MeshFile>) -> MyApp {
         use miniquad::{BufferSource, BufferType, BufferUsage};
 
         let mut ctx: Box<dyn RenderingBackend> = miniquad::window::new_rendering_backend();
 
+        // the index type follows the element size, so large meshes take 32-bit indices as well
+        let (vertices, indices) = match mesh {
+            Some(mesh) => MyVertex::from_mesh(mesh),
+            None => (MY_VERTICES.to_vec(), MY_INDICES.to_vec()),
+        };
         let vertex_buffer = ctx.new_buffer(
             BufferType::VertexBuffer,
             BufferUsage::Immutable,
-            BufferSource::slice(&MY_VERTICES),
+            BufferSource::slice(&vertices),
         );
         let index_buffer = ctx.new_buffer(
             BufferType::IndexBuffer,
             BufferUsage::Immutable,
-            BufferSource::slice(&MY_INDICES),
+            BufferSource::slice(&indices),
         );
         let bindings = Bindings {
             vertex_buffers: vec![vertex_buffer],
@@ -51,42 +65,32 @@ impl MyApp {
             images: vec![],
         };
 
-        use miniquad::{Backend, ShaderSource};
-
         let shader = ctx
-            .new_shader(
-                match ctx.info().backend {
-                    Backend::OpenGl => ShaderSource::Glsl {
-                        vertex: my_shader::MY_VERTEX_SHADER_SOURCE,
-                        fragment: my_shader::MY_FRAGMENT_SHADER_SOURCE,
-                    },
-                    Backend::Metal => ShaderSource::Msl {
-                        program: my_shader::MY_METAL_SHADER_SOURCE,
-                    },
-                },
-                my_shader::meta(),
-            )
+            .new_shader(my_shader::source(ctx.info().backend), my_shader::meta())
             .unwrap();
 
-        use miniquad::{BufferLayout, PipelineParams, VertexAttribute, VertexFormat};
+        use miniquad::{BufferLayout, PipelineParams};
 
         let pipeline = ctx.new_pipeline(
             &[BufferLayout::default()],
-            &[
-                VertexAttribute::new("in_pos", VertexFormat::Float2),
-                VertexAttribute::new("in_col", VertexFormat::Float3),
-            ],
+            &my_shader::attributes(),
             shader,
             PipelineParams {
                 ..Default::default()
             },
         );
 
+        let (width, height) = miniquad::window::screen_size();
+        let post_process = PostProcess::new(&mut *ctx, width as u32, height as u32);
+
         MyApp {
             pipeline,
             bindings,
+            num_indices: indices.len() as i32,
+            post_process,
             ctx,
             angle_y: 0.0,
+            time: 0.0,
         }
     }
 }
@@ -96,26 +100,46 @@ use miniquad::{EventHandler, KeyCode, KeyMods};
 impl EventHandler for MyApp {
     fn update(&mut self) {
         self.angle_y += std::f32::consts::PI / 60.0;
+        self.time += 1.0 / 60.0;
     }
 
     fn draw(&mut self) {
         use miniquad::UniformsSource;
 
-        self.ctx.begin_default_pass(Default::default());
+        if self.post_process.is_active() {
+            self.ctx
+                .begin_pass(Some(self.post_process.scene_pass()), Default::default());
+        } else {
+            self.ctx.begin_default_pass(Default::default());
+        }
         self.ctx.apply_pipeline(&self.pipeline);
         self.ctx.apply_bindings(&self.bindings);
         self.ctx.apply_uniforms(UniformsSource::table(&MyUniforms {
             u_angle_y: self.angle_y,
         }));
-        self.ctx.draw(0, 3, 1);
+        self.ctx.draw(0, self.num_indices, 1);
         self.ctx.end_render_pass();
 
+        if self.post_process.is_active() {
+            self.post_process.draw(&mut *self.ctx, self.time);
+        }
+
         self.ctx.commit_frame();
     }
 
+    fn resize_event(&mut self, width: f32, height: f32) {
+        self.post_process
+            .resize(&mut *self.ctx, width as u32, height as u32);
+    }
+
     fn key_down_event(&mut self, keycode: KeyCode, _keymods: KeyMods, _repeat: bool) {
-        if keycode == KeyCode::Escape {
-            miniquad::window::quit();
+        match keycode {
+            KeyCode::Escape => miniquad::window::quit(),
+            KeyCode::Key1 => self.post_process.toggle(0),
+            KeyCode::Key2 => self.post_process.toggle(1),
+            KeyCode::Key3 => self.post_process.toggle(2),
+            KeyCode::Key4 => self.post_process.toggle(3),
+            _ => {}
         }
     }
 }
@@ -126,11 +150,26 @@ pub struct MyUniforms {
 }
 
 #[repr(C)]
+#[derive(Clone, Copy)]
 struct MyVertex {
     pos: [f32; 2],
     col: [f32; 3],
 }
 
+impl MyVertex {
+    // The triangles of a mesh file, colored by where they are.
+    fn from_mesh(mut mesh: MeshFile) -> (Vec<MyVertex>, Vec<u32>) {
+        mesh.fit_to_clip_space();
+        let vertices = (mesh.positions.iter())
+            .map(|&pos| MyVertex {
+                pos,
+                col: MeshFile::color(pos),
+            })
+            .collect();
+        (vertices, mesh.indices)
+    }
+}
+
 #[rustfmt::skip]
 const MY_VERTICES: [MyVertex; 3] = [
     MyVertex { pos: [0.8, 0.0], col: [1.0, 0.0, 0.0] },
@@ -138,75 +177,144 @@ const MY_VERTICES: [MyVertex; 3] = [
     MyVertex { pos: [-0.8, -0.8], col: [0.0, 0.0, 1.0] },
 ];
 
-const MY_INDICES: [u16; 3] = [0, 1, 2];
+const MY_INDICES: [u32; 3] = [0, 1, 2];
 
 mod my_shader {
-    pub const MY_VERTEX_SHADER_SOURCE: &str = r#"
-        #version 330
-        precision mediump float;
-        uniform float u_angle_y;
-        layout(location = 0) in vec2 in_pos;
-        layout(location = 1) in vec3 in_col;
-        layout(location = 0) out vec4 v_color;
-
-        void main() {
-            gl_Position = vec4(in_pos, 0.0, 1.0);
-            gl_Position.x *= cos(u_angle_y);
-            v_color = vec4(in_col, 1.0);
-        }
-    "#;
-
-    pub const MY_FRAGMENT_SHADER_SOURCE: &str = r#"
-        #version 330
-        precision mediump float;
-        layout(location = 0) in vec4 v_color;
-        out vec4 color;
+    include!(concat!(env!("OUT_DIR"), "/shader.rs"));
+}
 
-        void main() {
-            color = v_color;
+#[cfg(test)]
+mod tests {
+    use miniquad::{ShaderMeta, ShaderSource};
+    use naga::front::glsl::{Frontend, Options};
+    use naga::{AddressSpace, ScalarKind, ShaderStage, TypeInner, VectorSize};
+
+    // naga reads GLSL 440 and later, wants a binding on each uniform and has no combined image
+    // samplers, so the source is brought into that form before it is parsed. The images are
+    // split into a texture and a sampler, and each texture() call combines them again.
+    fn parse(glsl: &str, stage: ShaderStage, images: &[String]) -> naga::Module {
+        let mut bindings = 0..;
+        let mut source = String::new();
+        for line in glsl.lines() {
+            if line == "#version 330 core" {
+                source.push_str("#version 450 core\n");
+            } else if let Some(image) = line.strip_prefix("uniform sampler2D ") {
+                let image = image.trim_end_matches(';');
+                let (texture, sampler) = (bindings.next().unwrap(), bindings.next().unwrap());
+                source.push_str(&format!(
+                    "layout(binding = {texture}) uniform texture2D {image};\n\
+                     layout(binding = {sampler}) uniform sampler {image}_sampler;\n"
+                ));
+            } else if line.starts_with("uniform ") {
+                let binding = bindings.next().unwrap();
+                source.push_str(&format!("layout(binding = {binding}) {line}\n"));
+            } else {
+                source.push_str(line);
+                source.push('\n');
+            }
         }
-    "#;
-
-    pub const MY_METAL_SHADER_SOURCE: &str = r#"
-        #include <metal_stdlib>
+        for image in images {
+            source = source.replace(
+                &format!("texture({image}, "),
+                &format!("texture(sampler2D({image}, {image}_sampler), "),
+            );
+        }
+        Frontend::default()
+            .parse(&Options::from(stage), &source)
+            .unwrap_or_else(|errors| panic!("{stage:?} shader does not parse: {errors:?}"))
+    }
 
-        using namespace metal;
+    // The uniforms of a stage as naga lays them out, as ("uniforms.u_angle_y", "Float1", 0),
+    // and the names of its textures.
+    fn reflect(module: &naga::Module) -> (Vec<(String, String, usize)>, Vec<String>) {
+        let (mut uniforms, mut images) = (vec![], vec![]);
+        for (_, global) in module.global_variables.iter() {
+            let name = global.name.clone().unwrap();
+            match (global.space, &module.types[global.ty].inner) {
+                (AddressSpace::Uniform, TypeInner::Struct { members, .. }) => {
+                    uniforms.extend(members.iter().map(|member| {
+                        let ty = uniform_type(&module.types[member.ty].inner);
+                        let member_name = member.name.as_deref().unwrap();
+                        (format!("{name}.{member_name}"), ty, member.offset as usize)
+                    }))
+                }
+                (AddressSpace::Uniform, inner) => {
+                    uniforms.push((name, uniform_type(inner), 0));
+                }
+                (AddressSpace::Handle, TypeInner::Image { .. }) => images.push(name),
+                _ => {}
+            }
+        }
+        (uniforms, images)
+    }
 
-        struct Vertex
-        {
-            float2 in_pos [[attribute(0)]];
-            float4 in_col [[attribute(1)]];
+    // the name of the miniquad UniformType for a naga type
+    fn uniform_type(inner: &TypeInner) -> String {
+        let (kind, count) = match *inner {
+            TypeInner::Scalar(scalar) => (scalar.kind, 1),
+            TypeInner::Vector { size, scalar } => (scalar.kind, size as u8),
+            TypeInner::Matrix {
+                columns: VectorSize::Quad,
+                rows: VectorSize::Quad,
+                ..
+            } => return "Mat4".to_owned(),
+            _ => return format!("{inner:?}"),
         };
+        match kind {
+            ScalarKind::Float => format!("Float{count}"),
+            ScalarKind::Sint | ScalarKind::Uint => format!("Int{count}"),
+            _ => format!("{inner:?}"),
+        }
+    }
 
-        struct RasterizerData
-        {
-            float4 position [[position]];
-            float4 color [[user(locn0)]];
+    // naga parses each stage, and each stage that uses the uniforms declares all of them with
+    // the names, types and offsets of the ShaderMeta that miniquad uploads them with. The
+    // textures are the images of the ShaderMeta, and the uniforms take as many bytes as the
+    // struct that holds them on the Rust side.
+    pub fn assert_uniform_layout(source: ShaderSource, meta: &ShaderMeta, size: usize) {
+        let ShaderSource::Glsl { vertex, fragment } = source else {
+            panic!("not GLSL");
         };
-
-        vertex RasterizerData vertexShader(Vertex v [[stage_in]])
-        {
-            RasterizerData out;
-
-            out.position = float4(v.in_pos.xy, 0.0, 1.0);
-            out.color = v.in_col;
-            return out;
+        let mut offset = 0;
+        let expected = (meta.uniforms.uniforms.iter())
+            .map(|uniform| {
+                let ty = format!("{:?}", uniform.uniform_type);
+                let uniform_offset = offset;
+                offset += uniform.uniform_type.size() * uniform.array_count;
+                (uniform.name.clone(), ty, uniform_offset)
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(offset, size);
+
+        let stages = [
+            reflect(&parse(vertex, ShaderStage::Vertex, &meta.images)),
+            reflect(&parse(fragment, ShaderStage::Fragment, &meta.images)),
+        ];
+        for (uniforms, images) in &stages {
+            if !uniforms.is_empty() {
+                assert_eq!(uniforms, &expected);
+            }
+            for image in images {
+                assert!(meta.images.contains(image), "{image} is not in the meta");
+            }
         }
-
-        fragment float4 fragmentShader(RasterizerData in [[stage_in]])
-        {
-            return in.color;
+        if !expected.is_empty() {
+            assert!(stages.iter().any(|(uniforms, _)| !uniforms.is_empty()));
         }
-    "#;
-
-    use miniquad::{ShaderMeta, UniformBlockLayout, UniformDesc};
-
-    pub fn meta() -> ShaderMeta {
-        ShaderMeta {
-            images: vec![],
-            uniforms: UniformBlockLayout {
-                uniforms: vec![UniformDesc::new("u_angle_y", miniquad::UniformType::Float1)],
-            },
+        for image in &meta.images {
+            assert!(
+                stages.iter().any(|(_, images)| images.contains(image)),
+                "{image} is used by no stage"
+            );
         }
     }
+
+    #[test]
+    fn shader_uniform_layout() {
+        assert_uniform_layout(
+            super::my_shader::source(miniquad::Backend::OpenGl),
+            &super::my_shader::meta(),
+            std::mem::size_of::<super::MyUniforms>(),
+        );
+    }
 }
@@ -0,0 +1,43 @@
+struct BlurUniforms {
+    texel_size: vec2<f32>,
+    radius: f32,
+}
+
+@group(0) @binding(0)
+var<uniform> uniforms: BlurUniforms;
+@group(0) @binding(1)
+var tex: texture_2d<f32>;
+@group(0) @binding(2)
+var tex_sampler: sampler;
+
+struct VertexInput {
+    @location(0) in_pos: vec2<f32>,
+    @location(1) in_uv: vec2<f32>,
+}
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+}
+
+@vertex
+fn vertexShader(v: VertexInput) -> VertexOutput {
+    var out: VertexOutput;
+    out.position = vec4<f32>(v.in_pos, 0.0, 1.0);
+    out.uv = v.in_uv;
+    return out;
+}
+
+// 5x5 gaussian, the taps are spread by the radius in pixels
+@fragment
+fn fragmentShader(in: VertexOutput) -> @location(0) vec4<f32> {
+    var weights = array<f32, 5>(1.0, 4.0, 6.0, 4.0, 1.0);
+    var color = vec4<f32>(0.0);
+    for (var y = 0; y < 5; y++) {
+        for (var x = 0; x < 5; x++) {
+            let offset = vec2<f32>(f32(x - 2), f32(y - 2)) * uniforms.texel_size * uniforms.radius * 0.5;
+            color += textureSample(tex, tex_sampler, in.uv + offset) * weights[x] * weights[y];
+        }
+    }
+    return color / 256.0;
+}
@@ -0,0 +1,39 @@
+struct ChromaticAberrationUniforms {
+    texel_size: vec2<f32>,
+    amount: f32,
+}
+
+@group(0) @binding(0)
+var<uniform> uniforms: ChromaticAberrationUniforms;
+@group(0) @binding(1)
+var tex: texture_2d<f32>;
+@group(0) @binding(2)
+var tex_sampler: sampler;
+
+struct VertexInput {
+    @location(0) in_pos: vec2<f32>,
+    @location(1) in_uv: vec2<f32>,
+}
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+}
+
+@vertex
+fn vertexShader(v: VertexInput) -> VertexOutput {
+    var out: VertexOutput;
+    out.position = vec4<f32>(v.in_pos, 0.0, 1.0);
+    out.uv = v.in_uv;
+    return out;
+}
+
+// shifts the red and blue channels away from the center, by amount pixels at the edges
+@fragment
+fn fragmentShader(in: VertexOutput) -> @location(0) vec4<f32> {
+    let offset = (in.uv - 0.5) * 2.0 * uniforms.texel_size * uniforms.amount;
+    let r = textureSample(tex, tex_sampler, in.uv + offset).r;
+    let color = textureSample(tex, tex_sampler, in.uv);
+    let b = textureSample(tex, tex_sampler, in.uv - offset).b;
+    return vec4<f32>(r, color.g, b, color.a);
+}
@@ -0,0 +1,42 @@
+struct CrtUniforms {
+    resolution: vec2<f32>,
+    time: f32,
+    intensity: f32,
+}
+
+@group(0) @binding(0)
+var<uniform> uniforms: CrtUniforms;
+@group(0) @binding(1)
+var tex: texture_2d<f32>;
+@group(0) @binding(2)
+var tex_sampler: sampler;
+
+struct VertexInput {
+    @location(0) in_pos: vec2<f32>,
+    @location(1) in_uv: vec2<f32>,
+}
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+}
+
+@vertex
+fn vertexShader(v: VertexInput) -> VertexOutput {
+    var out: VertexOutput;
+    out.position = vec4<f32>(v.in_pos, 0.0, 1.0);
+    out.uv = v.in_uv;
+    return out;
+}
+
+@fragment
+fn fragmentShader(in: VertexOutput) -> @location(0) vec4<f32> {
+    let color = textureSample(tex, tex_sampler, in.uv);
+
+    // one dark line every other pixel row, slowly rolling
+    let scanline = 0.5 + 0.5 * sin((in.uv.y * uniforms.resolution.y + uniforms.time * 8.0) * 3.14159265);
+    let centered = in.uv * 2.0 - 1.0;
+    let vignette = 1.0 - dot(centered, centered) * 0.25;
+    let shade = mix(1.0, scanline * vignette, uniforms.intensity);
+    return vec4<f32>(color.rgb * shade, color.a);
+}
@@ -0,0 +1,35 @@
+struct GrayscaleUniforms {
+    strength: f32,
+}
+
+@group(0) @binding(0)
+var<uniform> uniforms: GrayscaleUniforms;
+@group(0) @binding(1)
+var tex: texture_2d<f32>;
+@group(0) @binding(2)
+var tex_sampler: sampler;
+
+struct VertexInput {
+    @location(0) in_pos: vec2<f32>,
+    @location(1) in_uv: vec2<f32>,
+}
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+}
+
+@vertex
+fn vertexShader(v: VertexInput) -> VertexOutput {
+    var out: VertexOutput;
+    out.position = vec4<f32>(v.in_pos, 0.0, 1.0);
+    out.uv = v.in_uv;
+    return out;
+}
+
+@fragment
+fn fragmentShader(in: VertexOutput) -> @location(0) vec4<f32> {
+    let color = textureSample(tex, tex_sampler, in.uv);
+    let gray = dot(color.rgb, vec3<f32>(0.299, 0.587, 0.114));
+    return vec4<f32>(mix(color.rgb, vec3<f32>(gray), uniforms.strength), color.a);
+}
@@ -0,0 +1,296 @@
+use miniquad::{Bindings, Pipeline, RenderPass, RenderingBackend, TextureId};
+
+#[derive(Debug, Clone, Copy)]
+pub enum PostEffect {
+    Grayscale,
+    Blur,
+    Crt,
+    ChromaticAberration,
+}
+
+impl PostEffect {
+    pub const ALL: [PostEffect; 4] = [
+        PostEffect::Grayscale,
+        PostEffect::Blur,
+        PostEffect::Crt,
+        PostEffect::ChromaticAberration,
+    ];
+
+    fn shader(
+        &self,
+        backend: miniquad::Backend,
+    ) -> (
+        miniquad::ShaderSource<'static>,
+        miniquad::ShaderMeta,
+        Vec<miniquad::VertexAttribute>,
+    ) {
+        match self {
+            PostEffect::Grayscale => (
+                grayscale::source(backend),
+                grayscale::meta(),
+                grayscale::attributes(),
+            ),
+            PostEffect::Blur => (blur::source(backend), blur::meta(), blur::attributes()),
+            PostEffect::Crt => (crt::source(backend), crt::meta(), crt::attributes()),
+            PostEffect::ChromaticAberration => (
+                chromatic_aberration::source(backend),
+                chromatic_aberration::meta(),
+                chromatic_aberration::attributes(),
+            ),
+        }
+    }
+
+    fn apply_uniforms(&self, ctx: &mut dyn RenderingBackend, width: u32, height: u32, time: f32) {
+        use miniquad::UniformsSource;
+
+        let texel_size = [1.0 / width as f32, 1.0 / height as f32];
+        match self {
+            PostEffect::Grayscale => {
+                ctx.apply_uniforms(UniformsSource::table(&GrayscaleUniforms { strength: 1.0 }))
+            }
+            PostEffect::Blur => ctx.apply_uniforms(UniformsSource::table(&BlurUniforms {
+                texel_size,
+                radius: 2.0,
+            })),
+            PostEffect::Crt => ctx.apply_uniforms(UniformsSource::table(&CrtUniforms {
+                resolution: [width as f32, height as f32],
+                time,
+                intensity: 0.5,
+            })),
+            PostEffect::ChromaticAberration => {
+                ctx.apply_uniforms(UniformsSource::table(&ChromaticAberrationUniforms {
+                    texel_size,
+                    amount: 4.0,
+                }))
+            }
+        }
+    }
+}
+
+struct PostEffectPass {
+    effect: PostEffect,
+    pipeline: Pipeline,
+    enabled: bool,
+}
+
+// Renders the scene into an offscreen texture, and passes it through the enabled effects.
+// The effects ping-pong between two render targets, and the last one draws into the default pass.
+pub struct PostProcess {
+    passes: Vec<PostEffectPass>,
+    bindings: Bindings,
+    render_targets: Vec<(TextureId, RenderPass)>,
+    width: u32,
+    height: u32,
+}
+
+impl PostProcess {
+    pub fn new(ctx: &mut dyn RenderingBackend, width: u32, height: u32) -> Self {
+        use miniquad::PipelineParams;
+        use miniquad::{Backend, BufferLayout, BufferSource, BufferType, BufferUsage};
+
+        // render targets are upside down on Metal
+        let (top, bottom) = match ctx.info().backend {
+            Backend::OpenGl => (1.0, 0.0),
+            Backend::Metal => (0.0, 1.0),
+        };
+        #[rustfmt::skip]
+        let vertices: [QuadVertex; 4] = [
+            QuadVertex { pos: [-1.0, -1.0], uv: [0.0, bottom] },
+            QuadVertex { pos: [1.0, -1.0], uv: [1.0, bottom] },
+            QuadVertex { pos: [1.0, 1.0], uv: [1.0, top] },
+            QuadVertex { pos: [-1.0, 1.0], uv: [0.0, top] },
+        ];
+        let indices: [u16; 6] = [0, 1, 2, 0, 2, 3];
+        let vertex_buffer = ctx.new_buffer(
+            BufferType::VertexBuffer,
+            BufferUsage::Immutable,
+            BufferSource::slice(&vertices),
+        );
+        let index_buffer = ctx.new_buffer(
+            BufferType::IndexBuffer,
+            BufferUsage::Immutable,
+            BufferSource::slice(&indices),
+        );
+
+        let render_targets = Self::new_render_targets(ctx, width, height);
+        let bindings = Bindings {
+            vertex_buffers: vec![vertex_buffer],
+            index_buffer,
+            images: vec![render_targets[0].0],
+        };
+
+        let passes = PostEffect::ALL
+            .iter()
+            .map(|effect| {
+                let (source, meta, attributes) = effect.shader(ctx.info().backend);
+                let shader = ctx.new_shader(source, meta).unwrap();
+                let pipeline = ctx.new_pipeline(
+                    &[BufferLayout::default()],
+                    &attributes,
+                    shader,
+                    PipelineParams::default(),
+                );
+                PostEffectPass {
+                    effect: *effect,
+                    pipeline,
+                    enabled: false,
+                }
+            })
+            .collect();
+
+        Self {
+            passes,
+            bindings,
+            render_targets,
+            width,
+            height,
+        }
+    }
+
+    fn new_render_targets(
+        ctx: &mut dyn RenderingBackend,
+        width: u32,
+        height: u32,
+    ) -> Vec<(TextureId, RenderPass)> {
+        use miniquad::{TextureFormat, TextureParams};
+
+        (0..2)
+            .map(|_| {
+                let texture = ctx.new_render_texture(TextureParams {
+                    width,
+                    height,
+                    format: TextureFormat::RGBA8,
+                    ..Default::default()
+                });
+                let render_pass = ctx.new_render_pass(texture, None);
+                (texture, render_pass)
+            })
+            .collect()
+    }
+
+    pub fn resize(&mut self, ctx: &mut dyn RenderingBackend, width: u32, height: u32) {
+        if width == 0 || height == 0 || (width, height) == (self.width, self.height) {
+            return;
+        }
+        for (texture, render_pass) in self.render_targets.drain(..) {
+            ctx.delete_render_pass(render_pass);
+            ctx.delete_texture(texture);
+        }
+        self.render_targets = Self::new_render_targets(ctx, width, height);
+        (self.width, self.height) = (width, height);
+    }
+
+    pub fn toggle(&mut self, index: usize) {
+        if let Some(pass) = self.passes.get_mut(index) {
+            pass.enabled = !pass.enabled;
+        }
+        let enabled = self
+            .passes
+            .iter()
+            .filter(|pass| pass.enabled)
+            .map(|pass| format!("{:?}", pass.effect))
+            .collect::<Vec<_>>();
+        println!("post effects: {enabled:?}");
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.passes.iter().any(|pass| pass.enabled)
+    }
+
+    // The scene has to be drawn into this pass when any effect is enabled.
+    pub fn scene_pass(&self) -> RenderPass {
+        self.render_targets[0].1
+    }
+
+    pub fn draw(&mut self, ctx: &mut dyn RenderingBackend, time: f32) {
+        let enabled = self
+            .passes
+            .iter()
+            .filter(|pass| pass.enabled)
+            .collect::<Vec<_>>();
+        for (i, pass) in enabled.iter().enumerate() {
+            let (source, _) = self.render_targets[i % 2];
+            let target = if i + 1 == enabled.len() {
+                None
+            } else {
+                Some(self.render_targets[(i + 1) % 2].1)
+            };
+
+            ctx.begin_pass(target, Default::default());
+            ctx.apply_pipeline(&pass.pipeline);
+            self.bindings.images[0] = source;
+            ctx.apply_bindings(&self.bindings);
+            pass.effect
+                .apply_uniforms(ctx, self.width, self.height, time);
+            ctx.draw(0, 6, 1);
+            ctx.end_render_pass();
+        }
+    }
+}
+
+#[repr(C)]
+struct QuadVertex {
+    pos: [f32; 2],
+    uv: [f32; 2],
+}
+
+#[repr(C)]
+struct GrayscaleUniforms {
+    strength: f32,
+}
+
+#[repr(C)]
+struct BlurUniforms {
+    texel_size: [f32; 2],
+    radius: f32,
+}
+
+#[repr(C)]
+struct CrtUniforms {
+    resolution: [f32; 2],
+    time: f32,
+    intensity: f32,
+}
+
+#[repr(C)]
+struct ChromaticAberrationUniforms {
+    texel_size: [f32; 2],
+    amount: f32,
+}
+
+mod grayscale {
+    include!(concat!(env!("OUT_DIR"), "/grayscale.rs"));
+}
+
+mod blur {
+    include!(concat!(env!("OUT_DIR"), "/blur.rs"));
+}
+
+mod crt {
+    include!(concat!(env!("OUT_DIR"), "/crt.rs"));
+}
+
+mod chromatic_aberration {
+    include!(concat!(env!("OUT_DIR"), "/chromatic_aberration.rs"));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn post_effect_uniform_layouts() {
+        for effect in PostEffect::ALL {
+            let size = match effect {
+                PostEffect::Grayscale => std::mem::size_of::<GrayscaleUniforms>(),
+                PostEffect::Blur => std::mem::size_of::<BlurUniforms>(),
+                PostEffect::Crt => std::mem::size_of::<CrtUniforms>(),
+                PostEffect::ChromaticAberration => {
+                    std::mem::size_of::<ChromaticAberrationUniforms>()
+                }
+            };
+            let (source, meta, _) = effect.shader(miniquad::Backend::OpenGl);
+            crate::tests::assert_uniform_layout(source, &meta, size);
+        }
+    }
+}
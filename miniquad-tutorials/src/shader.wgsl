@@ -0,0 +1,30 @@
+struct MyUniforms {
+    u_angle_y: f32,
+}
+
+@group(0) @binding(0)
+var<uniform> uniforms: MyUniforms;
+
+struct VertexInput {
+    @location(0) in_pos: vec2<f32>,
+    @location(1) in_col: vec3<f32>,
+}
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) color: vec4<f32>,
+}
+
+@vertex
+fn vertexShader(v: VertexInput) -> VertexOutput {
+    var out: VertexOutput;
+    out.position = vec4<f32>(v.in_pos, 0.0, 1.0);
+    out.position.x *= cos(uniforms.u_angle_y);
+    out.color = vec4<f32>(v.in_col, 1.0);
+    return out;
+}
+
+@fragment
+fn fragmentShader(in: VertexOutput) -> @location(0) vec4<f32> {
+    return in.color;
+}
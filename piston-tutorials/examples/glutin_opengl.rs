@@ -2,6 +2,9 @@
 // use piston_window::prelude::*;
 use std::{env, f64, path};
 
+use tutorial_common::args::Args;
+use tutorial_common::locale;
+
 #[pollster::main]
 async fn main() {
     let resource_dir = if let Ok(manifest_dir) = env::var("CARGO_MANIFEST_DIR") {
@@ -23,13 +26,18 @@ async fn main() {
         .build::<PistonWindow<GlutinWindow>>()
         .unwrap();
 
-    let glyph_cache = window
-        .load_font(resource_dir.join("LiberationMono-Regular.ttf"))
-        .unwrap();
+    // a glyph cache for every font, in the order of the fallback
+    let fonts = locale::FontFallback::load(&resource_dir);
+    let glyph_caches = (fonts.fonts().iter())
+        .map(|font| window.load_font(resource_dir.join(font.name)).unwrap())
+        .collect();
 
+    let strings = locale::Language::from_args(&Args::from_env(&["--lang"])).strings();
     let mut my_app = MyApp {
-        text: "POS: ".into(),
-        glyph_cache,
+        text: locale::fill(strings.position, ""),
+        strings,
+        fonts,
+        glyph_caches,
     };
 
     use piston::{Event, EventSettings, Events, Input, Loop, Motion};
@@ -46,7 +54,9 @@ async fn main() {
             Event::Loop(Loop::Render(args)) => {
                 window.draw_2d(&e, |c, g, device| {
                     my_app.render(&args, &c, g);
-                    my_app.glyph_cache.factory.encoder.flush(device);
+                    for glyph_cache in &mut my_app.glyph_caches {
+                        glyph_cache.factory.encoder.flush(device);
+                    }
                 });
             }
             _ => {}
@@ -60,12 +70,14 @@ use piston_window::{G2dTexture, Glyphs};
 
 struct MyApp {
     text: String,
-    glyph_cache: Glyphs,
+    strings: &'static locale::HudStrings,
+    fonts: locale::FontFallback,
+    glyph_caches: Vec<Glyphs>,
 }
 
 impl MyApp {
     fn mouse_moved(self: &mut Self, args: &[f64; 2]) {
-        self.text = format!("POS: {:?}", args);
+        self.text = locale::fill(self.strings.position, format!("{:?}", args));
     }
 
     fn update(self: &mut Self, _args: &UpdateArgs) {}
@@ -79,11 +91,7 @@ impl MyApp {
         clear([0.2; 4], g);
 
         let mut transform = c.transform.trans(100., 100.);
-        let colors = [
-            [1., 0., 0., 1.],
-            [0., 1., 0., 1.],
-            [0., 0., 1., 1.],
-        ];
+        let colors = [[1., 0., 0., 1.], [0., 1., 0., 1.], [0., 0., 1., 1.]];
         colors.iter().for_each(|color| {
             use graphics::Ellipse;
 
@@ -91,17 +99,19 @@ impl MyApp {
             transform = transform.trans(200., 0.);
         });
 
+        use graphics::character::CharacterCache;
         use graphics::Text;
 
-        let transform = c.transform.trans(0., 32.);
-        Text::new_color([1.; 4], 24)
-            .draw(
-                &self.text,
-                &mut self.glyph_cache,
-                &c.draw_state,
-                transform,
-                g,
-            )
-            .unwrap();
+        // each run is drawn with its own font, right after the one before it
+        let mut transform = c.transform.trans(0., 32.);
+        for (font, run) in self.fonts.runs(&self.text) {
+            let Some(glyph_cache) = self.glyph_caches.get_mut(font) else {
+                continue;
+            };
+            Text::new_color([1.; 4], 24)
+                .draw(run, glyph_cache, &c.draw_state, transform, g)
+                .unwrap();
+            transform = transform.trans(glyph_cache.width(24, run).unwrap(), 0.);
+        }
     }
 }
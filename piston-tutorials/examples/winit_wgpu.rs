@@ -1,6 +1,9 @@
 // use piston::{*, Events, EventSettings};
 use std::{env, f64, path};
 
+use tutorial_common::args::Args;
+use tutorial_common::locale;
+
 #[pollster::main]
 async fn main() {
     let resource_dir = if let Ok(manifest_dir) = env::var("CARGO_MANIFEST_DIR") {
@@ -73,20 +76,26 @@ async fn main() {
     };
     let mut wgpu2d = Wgpu2d::new(&device, &config);
 
-    let glyph_cache = {
-        let texture_context = TextureContext::from_parts(&device, &queue);
-        let glyph_cache = GlyphCache::new(
-            resource_dir.join("LiberationMono-Regular.ttf"),
-            texture_context,
-            TextureSettings::new(),
-        )
-        .unwrap();
-        glyph_cache
-    };
+    // a glyph cache for every font, in the order of the fallback
+    let fonts = locale::FontFallback::load(&resource_dir);
+    let glyph_caches = (fonts.fonts().iter())
+        .map(|font| {
+            let texture_context = TextureContext::from_parts(&device, &queue);
+            GlyphCache::new(
+                resource_dir.join(font.name),
+                texture_context,
+                TextureSettings::new(),
+            )
+            .unwrap()
+        })
+        .collect();
 
+    let strings = locale::Language::from_args(&Args::from_env(&["--lang"])).strings();
     let mut my_app = MyApp {
-        text: "POS: ".into(),
-        glyph_cache,
+        text: locale::fill(strings.position, ""),
+        strings,
+        fonts,
+        glyph_caches,
     };
 
     use piston::{Event, EventSettings, Events, Input, Loop, Motion};
@@ -123,12 +132,14 @@ use wgpu_graphics::GlyphCache;
 
 struct MyApp<'a> {
     text: String,
-    glyph_cache: GlyphCache<'a>,
+    strings: &'static locale::HudStrings,
+    fonts: locale::FontFallback,
+    glyph_caches: Vec<GlyphCache<'a>>,
 }
 
 impl MyApp<'_> {
     fn mouse_moved(self: &mut Self, args: &[f64; 2]) {
-        self.text = format!("POS: {:?}", args);
+        self.text = locale::fill(self.strings.position, format!("{:?}", args));
     }
 
     fn update(self: &mut Self, _args: &UpdateArgs) {}
@@ -142,11 +153,7 @@ impl MyApp<'_> {
         clear([0.2; 4], g);
 
         let mut transform = c.transform.trans(100., 100.);
-        let colors = [
-            [1., 0., 0., 1.],
-            [0., 1., 0., 1.],
-            [0., 0., 1., 1.],
-        ];
+        let colors = [[1., 0., 0., 1.], [0., 1., 0., 1.], [0., 0., 1., 1.]];
         colors.iter().for_each(|color| {
             use graphics::Ellipse;
 
@@ -154,17 +161,19 @@ impl MyApp<'_> {
             transform = transform.trans(200., 0.);
         });
 
+        use graphics::character::CharacterCache;
         use graphics::Text;
 
-        let transform = c.transform.trans(0., 32.);
-        Text::new_color([1.; 4], 24)
-            .draw(
-                &self.text,
-                &mut self.glyph_cache,
-                &c.draw_state,
-                transform,
-                g,
-            )
-            .unwrap();
+        // each run is drawn with its own font, right after the one before it
+        let mut transform = c.transform.trans(0., 32.);
+        for (font, run) in self.fonts.runs(&self.text) {
+            let Some(glyph_cache) = self.glyph_caches.get_mut(font) else {
+                continue;
+            };
+            Text::new_color([1.; 4], 24)
+                .draw(run, glyph_cache, &c.draw_state, transform, g)
+                .unwrap();
+            transform = transform.trans(glyph_cache.width(24, run).unwrap(), 0.);
+        }
     }
 }
@@ -1,18 +1,33 @@
-use winit::dpi::PhysicalSize;
+use tutorial_common::args::Args;
+use tutorial_common::event_log::winit_0_29 as event_log;
+
+use event_log::{Command, EventRecorder, InputEvent};
+use winit::dpi::{LogicalSize, PhysicalSize};
 use winit::event::{Event, WindowEvent};
-use winit::event_loop::{ControlFlow, EventLoop};
-use winit::keyboard::KeyCode;
+use winit::event_loop::{ControlFlow, EventLoop, EventLoopWindowTarget};
 use winit::window::WindowBuilder;
 use winit_input_helper::WinitInputHelper;
 
 fn main() {
+    let args = Args::from_env(&["--play", "--record"]);
+    if let Some(path) = &args.play {
+        let events = event_log::load(path).unwrap();
+        let played = event_log::play(&events, |event| {
+            let command = event.command();
+            println!("{:?} -> {:?}", event, command);
+            command != Some(Command::Exit)
+        });
+        println!("played {} of {} events", played, events.len());
+        return;
+    }
+
     let event_queue = EventLoop::new().unwrap();
     event_queue.set_control_flow(ControlFlow::Poll);
     let window = WindowBuilder::new()
         .with_title("pixels tutorial")
-        .with_inner_size(PhysicalSize {
-            width: 480,
-            height: 320,
+        .with_inner_size(LogicalSize {
+            width: WIDTH as f64,
+            height: HEIGHT as f64,
         })
         .build(&event_queue)
         .unwrap();
@@ -21,10 +36,11 @@ fn main() {
     use pixels::wgpu::{BlendState, Color};
     use pixels::{PixelsBuilder, SurfaceTexture};
 
+    let window_size = window.inner_size();
+    let (buffer_width, buffer_height) = buffer_size(window_size);
     let mut pixels = {
-        let window_size = window.inner_size();
         let surface_texture = SurfaceTexture::new(window_size.width, window_size.height, &window);
-        PixelsBuilder::new(window_size.width, window_size.height, surface_texture)
+        PixelsBuilder::new(buffer_width, buffer_height, surface_texture)
             .blend_state(BlendState::ALPHA_BLENDING)
             .clear_color(Color {
                 r: 0.2,
@@ -37,22 +53,40 @@ fn main() {
     };
 
     // let mut pause = false;
-    let mut my_app = MyApp::new();
-    _ = event_queue.run(move |event, window_target| {
-        if input.update(&event) {
-            if input.key_pressed(KeyCode::Escape) || input.close_requested() {
-                window_target.exit();
-                return;
-            }
-
-            if let Some(size) = input.window_resized() {
-                println!("window resized: {:?}", size);
-                if let Err(_err) = pixels.resize_surface(size.width, size.height) {
+    let mut my_app = MyApp::new(buffer_width, buffer_height, window.scale_factor());
+    let handler = move |event: Event<()>, window_target: &EventLoopWindowTarget<()>| {
+        if let Event::WindowEvent {
+            event: ref window_event,
+            ..
+        } = event
+        {
+            match InputEvent::from_window_event(window_event).and_then(|event| event.command()) {
+                Some(Command::Exit) => {
                     window_target.exit();
                     return;
                 }
+                Some(Command::Resize(size)) => {
+                    println!("window resized: {:?}", size);
+                    if let Err(_err) = resize(&mut pixels, &mut my_app, size, window.scale_factor())
+                    {
+                        window_target.exit();
+                        return;
+                    }
+                }
+                Some(Command::Rescale(scale_factor)) => {
+                    println!("scale factor changed: {}", scale_factor);
+                    if let Err(_err) =
+                        resize(&mut pixels, &mut my_app, window.inner_size(), scale_factor)
+                    {
+                        window_target.exit();
+                        return;
+                    }
+                }
+                None => {}
             }
+        }
 
+        if input.update(&event) {
             my_app.update();
             // if input.key_pressed(KeyCode::Enter) {
             //     pause = !pause;
@@ -74,13 +108,48 @@ fn main() {
                 return;
             }
         }
-    });
+    };
+    _ = if let Some(path) = &args.record {
+        println!("recording to {}", path);
+        let recorder = EventRecorder::create(path).unwrap();
+        event_queue.run(recorder.wrap(handler))
+    } else {
+        event_queue.run(handler)
+    };
 }
 
+// The buffer has a pixel for each pixel of the surface. pixels scales a buffer up by whole
+// numbers only, so a buffer at the logical size would be letterboxed at a scale factor of 1.5.
+fn buffer_size(physical_size: PhysicalSize<u32>) -> (u32, u32) {
+    (physical_size.width.max(1), physical_size.height.max(1))
+}
+
+fn resize(
+    pixels: &mut pixels::Pixels,
+    my_app: &mut MyApp,
+    physical_size: PhysicalSize<u32>,
+    scale_factor: f64,
+) -> Result<(), pixels::TextureError> {
+    if physical_size.width == 0 || physical_size.height == 0 {
+        return Ok(());
+    }
+    let (width, height) = buffer_size(physical_size);
+    pixels.resize_surface(physical_size.width, physical_size.height)?;
+    pixels.resize_buffer(width, height)?;
+    my_app.resize(width, height, scale_factor);
+    Ok(())
+}
+
+// the logical size of the window
 const WIDTH: u32 = 480;
 const HEIGHT: u32 = 320;
 
+// The box moves in logical pixels, so that it looks the same at any scale factor.
 struct MyApp {
+    // the size of the frame in physical pixels
+    width: u32,
+    height: u32,
+    scale_factor: f64,
     box_size: i16,
     box_x: i16,
     box_y: i16,
@@ -89,8 +158,11 @@ struct MyApp {
 }
 
 impl MyApp {
-    fn new() -> Self {
+    fn new(width: u32, height: u32, scale_factor: f64) -> Self {
         Self {
+            width,
+            height,
+            scale_factor,
             box_size: 32,
             box_x: 16,
             box_y: 16,
@@ -99,11 +171,24 @@ impl MyApp {
         }
     }
 
+    fn resize(&mut self, width: u32, height: u32, scale_factor: f64) {
+        (self.width, self.height, self.scale_factor) = (width, height, scale_factor);
+        let (width, height) = self.logical_size();
+        self.box_x = self.box_x.min((width - self.box_size).max(0));
+        self.box_y = self.box_y.min((height - self.box_size).max(0));
+    }
+
+    fn logical_size(&self) -> (i16, i16) {
+        let size = PhysicalSize::new(self.width, self.height).to_logical::<f64>(self.scale_factor);
+        (size.width as i16, size.height as i16)
+    }
+
     fn update(&mut self) {
-        if self.box_x <= 0 || self.box_x + self.box_size >= WIDTH as i16 {
+        let (width, height) = self.logical_size();
+        if self.box_x <= 0 || self.box_x + self.box_size >= width {
             self.velocity_x *= -1;
         }
-        if self.box_y <= 0 || self.box_y + self.box_size >= HEIGHT as i16 {
+        if self.box_y <= 0 || self.box_y + self.box_size >= height {
             self.velocity_y *= -1;
         }
 
@@ -113,8 +198,9 @@ impl MyApp {
 
     fn render(&self, frame: &mut [u8]) {
         for (i, pixel) in frame.chunks_exact_mut(4).enumerate() {
-            let x = (i % WIDTH as usize) as i16;
-            let y = (i / WIDTH as usize) as i16;
+            // the logical pixel that the physical pixel is in
+            let x = ((i % self.width as usize) as f64 / self.scale_factor) as i16;
+            let y = ((i / self.width as usize) as f64 / self.scale_factor) as i16;
 
             let edge_of_the_box = (x == self.box_x || x == self.box_x + self.box_size - 1)
                 && (y >= self.box_y && y <= self.box_y + self.box_size - 1)
@@ -134,3 +220,40 @@ impl MyApp {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{buffer_size, MyApp, HEIGHT, WIDTH};
+    use winit::dpi::LogicalSize;
+
+    const MY_SCALE_FACTORS: [f64; 4] = [1.0, 1.25, 1.5, 2.0];
+
+    #[test]
+    fn box_keeps_its_logical_size() {
+        for scale_factor in MY_SCALE_FACTORS {
+            let physical_size = LogicalSize::new(WIDTH, HEIGHT).to_physical::<u32>(scale_factor);
+            let (width, height) = buffer_size(physical_size);
+            let my_app = MyApp::new(width, height, scale_factor);
+            let mut frame = vec![0; (width * height * 4) as usize];
+            my_app.render(&mut frame);
+
+            // the row through the middle of the box
+            let y = ((my_app.box_y + my_app.box_size / 2) as f64 * scale_factor) as u32;
+            let row = &frame[(y * width * 4) as usize..((y + 1) * width * 4) as usize];
+            let drawn = row.chunks_exact(4).filter(|pixel| pixel[3] != 0).count();
+            let expected = (my_app.box_size as f64 * scale_factor).round() as usize;
+            assert_eq!(drawn, expected, "scale factor {scale_factor}");
+        }
+    }
+
+    #[test]
+    fn box_stays_inside_after_a_rescale() {
+        let mut my_app = MyApp::new(WIDTH, HEIGHT, 1.0);
+        (my_app.box_x, my_app.box_y) = (440, 280);
+        // the same physical size at twice the scale is half the logical size
+        my_app.resize(WIDTH, HEIGHT, 2.0);
+        assert_eq!(my_app.logical_size(), (240, 160));
+        assert!(my_app.box_x + my_app.box_size <= 240);
+        assert!(my_app.box_y + my_app.box_size <= 160);
+    }
+}
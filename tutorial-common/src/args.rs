@@ -0,0 +1,208 @@
+// The command line of the tutorials, parsed once at the start of main. Every option is a named
+// flag, so that they combine in any order, as in `--record events.jsonl --mesh cube.mesh`.
+#[derive(Debug, Default, PartialEq)]
+pub struct Args {
+    // an event log to play back without a window
+    pub play: Option<String>,
+    // an event log to record the input of the window to
+    pub record: Option<String>,
+    // a mesh file to draw in place of the triangle
+    pub mesh: Option<String>,
+    // reload the shaders when their files change
+    pub watch: bool,
+    // Metal instead of OpenGL on macOS
+    pub metal: bool,
+    // render without a window
+    pub headless: bool,
+    // the language of the HUD, such as "ja"
+    pub lang: Option<String>,
+    // a file of SVG path data to tessellate
+    pub input: Option<String>,
+    // a text to lay out and tessellate in place of a file
+    pub text: Option<String>,
+    // the size of the text
+    pub size: Option<f32>,
+    // the line width to stroke with instead of filling
+    pub stroke: Option<f32>,
+    // how far the tessellation may stray from the curves
+    pub tolerance: Option<f32>,
+    // the files to write the tessellation to, each in the format of its extension
+    pub output: Vec<String>,
+}
+
+// the flags that are followed by a value, and what the value is
+const MY_VALUE_FLAGS: [(&str, &str); 10] = [
+    ("--play", "FILE"),
+    ("--record", "FILE"),
+    ("--mesh", "FILE"),
+    ("--lang", "LANG"),
+    ("--input", "FILE"),
+    ("--text", "TEXT"),
+    ("--size", "SIZE"),
+    ("--stroke", "WIDTH"),
+    ("--tolerance", "TOLERANCE"),
+    ("--output", "FILE"),
+];
+
+impl Args {
+    // The arguments of the process, with only the flags that the tutorial has. On anything
+    // else, prints what was wrong and the usage, and exits.
+    pub fn from_env(flags: &[&str]) -> Self {
+        match Self::parse(std::env::args().skip(1), flags) {
+            Ok(args) => args,
+            Err(err) => {
+                let name = std::env::args().next().unwrap_or_default();
+                eprintln!("{err}");
+                eprintln!("usage: {} {}", name, usage(flags));
+                std::process::exit(2);
+            }
+        }
+    }
+
+    pub fn parse<I: IntoIterator<Item = String>>(args: I, flags: &[&str]) -> Result<Self, String> {
+        let mut parsed = Args::default();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            if !flags.contains(&arg.as_str()) {
+                return Err(format!("unknown argument {arg:?}"));
+            }
+            let mut value = || {
+                let name = value_name(&arg).unwrap_or_default();
+                args.next().ok_or_else(|| format!("{arg} needs a {name}"))
+            };
+            match arg.as_str() {
+                "--play" => parsed.play = Some(value()?),
+                "--record" => parsed.record = Some(value()?),
+                "--mesh" => parsed.mesh = Some(value()?),
+                "--lang" => parsed.lang = Some(value()?),
+                "--input" => parsed.input = Some(value()?),
+                "--text" => parsed.text = Some(value()?),
+                "--size" => parsed.size = Some(positive(&arg, value()?)?),
+                "--stroke" => parsed.stroke = Some(positive(&arg, value()?)?),
+                "--tolerance" => parsed.tolerance = Some(positive(&arg, value()?)?),
+                // the one flag that can be given more than once
+                "--output" => parsed.output.push(value()?),
+                "--watch" => parsed.watch = true,
+                "--metal" => parsed.metal = true,
+                "--headless" => parsed.headless = true,
+                _ => return Err(format!("unknown argument {arg:?}")),
+            }
+        }
+        if parsed.play.is_some() && parsed.record.is_some() {
+            return Err("--play and --record cannot be combined".to_owned());
+        }
+        if parsed.input.is_some() && parsed.text.is_some() {
+            return Err("--input and --text cannot be combined".to_owned());
+        }
+        Ok(parsed)
+    }
+}
+
+pub fn usage(flags: &[&str]) -> String {
+    let flags = flags.iter().map(|flag| match value_name(flag) {
+        Some(name) => format!("[{flag} {name}]"),
+        None => format!("[{flag}]"),
+    });
+    flags.collect::<Vec<_>>().join(" ")
+}
+
+fn positive(flag: &str, value: String) -> Result<f32, String> {
+    (value.parse().ok())
+        .filter(|number: &f32| *number > 0.0)
+        .ok_or_else(|| format!("{flag} needs a positive number, got {value:?}"))
+}
+
+fn value_name(flag: &str) -> Option<&'static str> {
+    (MY_VALUE_FLAGS.iter())
+        .find(|(value_flag, _)| *value_flag == flag)
+        .map(|(_, name)| *name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MY_FLAGS: [&str; 4] = ["--play", "--record", "--mesh", "--watch"];
+
+    fn parse(args: &[&str]) -> Result<Args, String> {
+        Args::parse(args.iter().map(|arg| arg.to_string()), &MY_FLAGS)
+    }
+
+    #[test]
+    fn flags_combine_in_any_order() {
+        let expected = Args {
+            record: Some("events.jsonl".to_owned()),
+            mesh: Some("cube.mesh".to_owned()),
+            watch: true,
+            ..Args::default()
+        };
+        let args = ["--record", "events.jsonl", "--mesh", "cube.mesh", "--watch"];
+        assert_eq!(parse(&args), Ok(expected));
+        let args = ["--watch", "--mesh", "cube.mesh", "--record", "events.jsonl"];
+        let Ok(parsed) = parse(&args) else {
+            panic!("{args:?}");
+        };
+        assert_eq!(parsed.mesh.as_deref(), Some("cube.mesh"));
+        assert_eq!(parsed.record.as_deref(), Some("events.jsonl"));
+        assert!(parsed.watch);
+        assert_eq!(parse(&[]), Ok(Args::default()));
+    }
+
+    #[test]
+    fn a_file_is_not_taken_for_a_mode() {
+        // "play" used to be the first argument; now it is only ever a file name
+        let parsed = parse(&["--mesh", "play"]).unwrap();
+        assert_eq!(parsed.mesh.as_deref(), Some("play"));
+        assert_eq!(parsed.play, None);
+    }
+
+    #[test]
+    fn rejects_what_the_tutorial_does_not_have() {
+        assert!(parse(&["play"]).is_err());
+        assert!(parse(&["--metal"]).is_err());
+        assert!(parse(&["--mesh"]).is_err());
+        assert!(parse(&["--play", "a.jsonl", "--record", "b.jsonl"]).is_err());
+    }
+
+    #[test]
+    fn reads_numbers_and_repeated_outputs() {
+        let flags = ["--input", "--stroke", "--tolerance", "--output"];
+        let args = [
+            "--output",
+            "a.obj",
+            "--stroke",
+            "2.5",
+            "--input",
+            "logo.path",
+            "--output",
+            "a.mesh",
+        ];
+        let parsed = Args::parse(args.map(str::to_owned), &flags).unwrap();
+        let expected = Args {
+            input: Some("logo.path".to_owned()),
+            stroke: Some(2.5),
+            output: vec!["a.obj".to_owned(), "a.mesh".to_owned()],
+            ..Args::default()
+        };
+        assert_eq!(parsed, expected);
+
+        for value in ["0", "-1", "wide", "NaN"] {
+            let err = Args::parse(["--stroke".to_owned(), value.to_owned()], &flags).unwrap_err();
+            assert_eq!(
+                err,
+                format!("--stroke needs a positive number, got {value:?}")
+            );
+        }
+        let both = ["--input", "logo.path", "--text", "Hi"].map(str::to_owned);
+        assert!(Args::parse(both, &["--input", "--text"]).is_err());
+    }
+
+    #[test]
+    fn usage_lists_the_flags() {
+        assert_eq!(
+            usage(&MY_FLAGS),
+            "[--play FILE] [--record FILE] [--mesh FILE] [--watch]"
+        );
+        assert_eq!(usage(&["--lang"]), "[--lang LANG]");
+    }
+}
@@ -0,0 +1,98 @@
+// Records window input to a JSON Lines log, and plays the log back without a window.
+// The log is the same for both versions of winit that the tutorials use; what differs is how
+// the recorder is put between the event loop and the app.
+
+#[cfg(feature = "winit-0-29")]
+pub mod winit_0_29 {
+    use ::winit_0_29 as winit;
+
+    use winit::event::Event;
+    use winit::event_loop::EventLoopWindowTarget;
+
+    impl EventRecorder {
+        // Records the window events, and passes every event on to the handler as it is.
+        pub fn wrap<T, F>(
+            mut self,
+            mut handler: F,
+        ) -> impl FnMut(Event<T>, &EventLoopWindowTarget<T>)
+        where
+            F: FnMut(Event<T>, &EventLoopWindowTarget<T>),
+        {
+            move |event, window_target| {
+                if let Event::WindowEvent { event, .. } = &event {
+                    self.record(event);
+                }
+                handler(event, window_target);
+            }
+        }
+    }
+
+    include!("event_log/common.rs");
+}
+
+#[cfg(feature = "winit-0-30")]
+pub mod winit_0_30 {
+    use ::winit_0_30 as winit;
+
+    use winit::application::ApplicationHandler;
+    use winit::event::{DeviceEvent, DeviceId, StartCause};
+    use winit::event_loop::ActiveEventLoop;
+    use winit::window::WindowId;
+
+    impl EventRecorder {
+        // Records the window events of the app, and passes every event on to it as it is.
+        pub fn wrap<A: ApplicationHandler>(self, app: A) -> RecordingApp<A> {
+            RecordingApp {
+                app,
+                recorder: self,
+            }
+        }
+    }
+
+    pub struct RecordingApp<A> {
+        app: A,
+        recorder: EventRecorder,
+    }
+
+    impl<A: ApplicationHandler> ApplicationHandler for RecordingApp<A> {
+        fn new_events(&mut self, event_loop: &ActiveEventLoop, cause: StartCause) {
+            self.app.new_events(event_loop, cause);
+        }
+
+        fn resumed(&mut self, event_loop: &ActiveEventLoop) {
+            self.app.resumed(event_loop);
+        }
+
+        fn window_event(&mut self, event_loop: &ActiveEventLoop, id: WindowId, event: WindowEvent) {
+            self.recorder.record(&event);
+            self.app.window_event(event_loop, id, event);
+        }
+
+        fn device_event(
+            &mut self,
+            event_loop: &ActiveEventLoop,
+            device_id: DeviceId,
+            event: DeviceEvent,
+        ) {
+            self.app.device_event(event_loop, device_id, event);
+        }
+
+        fn about_to_wait(&mut self, event_loop: &ActiveEventLoop) {
+            self.app.about_to_wait(event_loop);
+        }
+
+        fn suspended(&mut self, event_loop: &ActiveEventLoop) {
+            self.app.suspended(event_loop);
+        }
+
+        fn exiting(&mut self, event_loop: &ActiveEventLoop) {
+            self.app.exiting(event_loop);
+        }
+
+        fn memory_warning(&mut self, event_loop: &ActiveEventLoop) {
+            self.app.memory_warning(event_loop);
+        }
+    }
+
+    include!("event_log/common.rs");
+}
@@ -0,0 +1,263 @@
+// Included once for each version of winit, with `winit` naming that version.
+
+use std::fs::File;
+use std::io::{BufRead, BufReader, LineWriter, Write};
+use std::path::Path;
+use std::time::Instant;
+
+use serde::{Deserialize, Serialize};
+use winit::dpi::{PhysicalPosition, PhysicalSize};
+use winit::event::{ElementState, MouseButton, MouseScrollDelta, WindowEvent};
+use winit::keyboard::{Key, KeyCode, PhysicalKey};
+
+// The part of WindowEvent that can be recorded, and played back without a window.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum InputEvent {
+    KeyboardInput {
+        physical_key: PhysicalKey,
+        logical_key: Key,
+        state: ElementState,
+        repeat: bool,
+    },
+    CursorMoved {
+        position: PhysicalPosition<f64>,
+    },
+    MouseInput {
+        button: MouseButton,
+        state: ElementState,
+    },
+    MouseWheel {
+        delta: MouseScrollDelta,
+    },
+    Resized(PhysicalSize<u32>),
+    ScaleFactorChanged {
+        scale_factor: f64,
+    },
+    CloseRequested,
+}
+
+impl InputEvent {
+    pub fn from_window_event(event: &WindowEvent) -> Option<Self> {
+        let event = match event {
+            WindowEvent::KeyboardInput { event, .. } => InputEvent::KeyboardInput {
+                physical_key: event.physical_key,
+                logical_key: event.logical_key.clone(),
+                state: event.state,
+                repeat: event.repeat,
+            },
+            WindowEvent::CursorMoved { position, .. } => InputEvent::CursorMoved {
+                position: *position,
+            },
+            WindowEvent::MouseInput { button, state, .. } => InputEvent::MouseInput {
+                button: *button,
+                state: *state,
+            },
+            WindowEvent::MouseWheel { delta, .. } => InputEvent::MouseWheel { delta: *delta },
+            WindowEvent::Resized(size) => InputEvent::Resized(*size),
+            WindowEvent::ScaleFactorChanged { scale_factor, .. } => {
+                InputEvent::ScaleFactorChanged {
+                    scale_factor: *scale_factor,
+                }
+            }
+            WindowEvent::CloseRequested => InputEvent::CloseRequested,
+            _ => return None,
+        };
+        Some(event)
+    }
+
+    // The same for every tutorial: pressing Escape or closing the window exits.
+    pub fn command(&self) -> Option<Command> {
+        match self {
+            InputEvent::CloseRequested
+            | InputEvent::KeyboardInput {
+                physical_key: PhysicalKey::Code(KeyCode::Escape),
+                state: ElementState::Pressed,
+                ..
+            } => Some(Command::Exit),
+            InputEvent::Resized(size) => Some(Command::Resize(*size)),
+            InputEvent::ScaleFactorChanged { scale_factor } => Some(Command::Rescale(*scale_factor)),
+            _ => None,
+        }
+    }
+}
+
+// What the input asks of a tutorial, decided apart from the window so that it can be played back.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    Exit,
+    Resize(PhysicalSize<u32>),
+    Rescale(f64),
+}
+
+// One line of the log. The time is in seconds since the recording started.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedEvent {
+    pub time: f64,
+    pub event: InputEvent,
+}
+
+// Appends the input events to a JSON Lines file as they arrive.
+pub struct EventRecorder {
+    started: Instant,
+    file: LineWriter<File>,
+}
+
+impl EventRecorder {
+    pub fn create<P: AsRef<Path>>(path: P) -> std::io::Result<Self> {
+        let file = LineWriter::new(File::create(path)?);
+        Ok(Self {
+            started: Instant::now(),
+            file,
+        })
+    }
+
+    pub fn record(&mut self, event: &WindowEvent) {
+        let Some(event) = InputEvent::from_window_event(event) else {
+            return;
+        };
+        let recorded_event = RecordedEvent {
+            time: self.started.elapsed().as_secs_f64(),
+            event,
+        };
+        let line = serde_json::to_string(&recorded_event).unwrap();
+        if let Err(err) = writeln!(self.file, "{line}") {
+            eprintln!("{err}");
+        }
+    }
+}
+
+pub fn load<P: AsRef<Path>>(path: P) -> std::io::Result<Vec<RecordedEvent>> {
+    let file = BufReader::new(File::open(path)?);
+    let mut events = vec![];
+    for line in file.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        events.push(serde_json::from_str(&line)?);
+    }
+    Ok(events)
+}
+
+// Feeds the events to the handler in order, as fast as it takes them.
+// Stops when the handler returns false, and returns the number of events played.
+pub fn play<F: FnMut(&InputEvent) -> bool>(events: &[RecordedEvent], mut handler: F) -> usize {
+    let mut played = 0;
+    for recorded_event in events {
+        played += 1;
+        if !handler(&recorded_event.event) {
+            break;
+        }
+    }
+    played
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // the recording that the tests of the tutorials play back
+    const MY_ESCAPE_LOG: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../assets/escape.jsonl");
+
+    #[test]
+    fn loads_the_escape_recording() {
+        let events = load(MY_ESCAPE_LOG).unwrap();
+        assert_eq!(events.len(), 10);
+        assert_eq!(
+            events[0].event,
+            InputEvent::Resized(PhysicalSize::new(800, 600))
+        );
+        assert!(events.windows(2).all(|pair| pair[0].time <= pair[1].time));
+        let InputEvent::KeyboardInput { logical_key, .. } = &events[9].event else {
+            panic!("{:?}", events[9].event);
+        };
+        assert_eq!(
+            *logical_key,
+            Key::Named(winit::keyboard::NamedKey::Escape)
+        );
+    }
+
+    #[test]
+    fn plays_until_the_handler_stops() {
+        let events = load(MY_ESCAPE_LOG).unwrap();
+        let mut seen = vec![];
+        let played = play(&events, |event| {
+            seen.push(event.clone());
+            !matches!(event, InputEvent::MouseWheel { .. })
+        });
+        assert_eq!(played, 6);
+        assert_eq!(seen.len(), 6);
+        assert_eq!(play(&events, |_| true), events.len());
+    }
+
+    #[test]
+    fn escape_in_a_recording_exits() {
+        let events = load(MY_ESCAPE_LOG).unwrap();
+        let mut commands = vec![];
+        let played = play(&events, |event| {
+            let command = event.command();
+            let exit = command == Some(Command::Exit);
+            commands.extend(command);
+            !exit
+        });
+        // the release of Escape is not played
+        assert_eq!(played, events.len() - 1);
+        assert_eq!(
+            commands,
+            [
+                Command::Resize(PhysicalSize::new(800, 600)),
+                Command::Rescale(1.0),
+                Command::Exit
+            ]
+        );
+    }
+
+    #[test]
+    fn only_pressing_escape_exits() {
+        let escape = |state| InputEvent::KeyboardInput {
+            physical_key: PhysicalKey::Code(KeyCode::Escape),
+            logical_key: Key::Named(winit::keyboard::NamedKey::Escape),
+            state,
+            repeat: false,
+        };
+        assert_eq!(escape(ElementState::Pressed).command(), Some(Command::Exit));
+        assert_eq!(escape(ElementState::Released).command(), None);
+        assert_eq!(InputEvent::CloseRequested.command(), Some(Command::Exit));
+        let key_q = InputEvent::KeyboardInput {
+            physical_key: PhysicalKey::Code(KeyCode::KeyQ),
+            logical_key: Key::Named(winit::keyboard::NamedKey::Escape),
+            state: ElementState::Pressed,
+            repeat: false,
+        };
+        // the key is told by where it is on the keyboard, not by what it types
+        assert_eq!(key_q.command(), None);
+        let wheel = InputEvent::MouseWheel {
+            delta: MouseScrollDelta::LineDelta(0.0, 1.0),
+        };
+        assert_eq!(wheel.command(), None);
+    }
+
+    #[test]
+    fn records_what_it_loads() {
+        // one file for each version of winit, as their tests run at the same time
+        let name = format!("{}-{}.jsonl", module_path!(), std::process::id());
+        let path = std::env::temp_dir().join(name.replace("::", "-"));
+        let mut recorder = EventRecorder::create(&path).unwrap();
+        recorder.record(&WindowEvent::Resized(PhysicalSize::new(320, 240)));
+        // not input, so not recorded
+        recorder.record(&WindowEvent::Focused(true));
+        recorder.record(&WindowEvent::CloseRequested);
+        drop(recorder);
+
+        let events = load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        let events: Vec<_> = events.into_iter().map(|event| event.event).collect();
+        assert_eq!(
+            events,
+            [
+                InputEvent::Resized(PhysicalSize::new(320, 240)),
+                InputEvent::CloseRequested,
+            ]
+        );
+    }
+}
@@ -0,0 +1,13 @@
+// Modules that several tutorials share, so that a fix lands in all of them at once.
+
+pub mod args;
+#[cfg(any(feature = "winit-0-29", feature = "winit-0-30"))]
+pub mod event_log;
+#[cfg(feature = "locale")]
+pub mod locale;
+pub mod mesh_file;
+#[cfg(feature = "lyon")]
+pub mod path_file;
+#[cfg(feature = "gl")]
+pub mod shader_program;
+pub mod shader_watcher;
@@ -0,0 +1,269 @@
+use std::collections::HashSet;
+use std::path::Path;
+
+use crate::args::Args;
+
+// The language of the HUD, chosen at startup by `--lang ja` or `--lang en`,
+// or by LANG when there is no flag.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Language {
+    English,
+    Japanese,
+}
+
+impl Language {
+    pub const ALL: [Language; 2] = [Language::English, Language::Japanese];
+
+    pub fn from_args(args: &Args) -> Self {
+        let lang = (args.lang.clone())
+            .or_else(|| std::env::var("LANG").ok())
+            .unwrap_or_default();
+        if lang.starts_with("ja") {
+            Language::Japanese
+        } else {
+            Language::English
+        }
+    }
+
+    pub fn strings(self) -> &'static HudStrings {
+        match self {
+            Language::English => &MY_ENGLISH,
+            Language::Japanese => &MY_JAPANESE,
+        }
+    }
+}
+
+// Every string that the HUDs of the tutorials show; each tutorial uses only some of them.
+// "{}" is replaced with a value by fill.
+pub struct HudStrings {
+    pub fps: &'static str,
+    pub position: &'static str,
+    pub spawn_explosion: &'static str,
+    pub spawn_bomb: &'static str,
+    pub pause_resume: &'static str,
+    pub chains: &'static str,
+    pub assets: &'static str,
+    pub path_editor: &'static str,
+    pub shader_playground: &'static str,
+    pub select_file: &'static str,
+}
+
+impl HudStrings {
+    pub fn all(&self) -> [&'static str; 10] {
+        [
+            self.fps,
+            self.position,
+            self.spawn_explosion,
+            self.spawn_bomb,
+            self.pause_resume,
+            self.chains,
+            self.assets,
+            self.path_editor,
+            self.shader_playground,
+            self.select_file,
+        ]
+    }
+}
+
+pub fn fill(template: &str, value: impl std::fmt::Display) -> String {
+    template.replacen("{}", &value.to_string(), 1)
+}
+
+const MY_ENGLISH: HudStrings = HudStrings {
+    fps: "FPS: {}",
+    position: "POS: {}",
+    spawn_explosion: "Mouse L: Spawn Explosion",
+    spawn_bomb: "Mouse M: Spawn Bomb",
+    pause_resume: "Mouse R: Pause/Resume",
+    chains: "{} Chain(s)",
+    assets: "Assets",
+    path_editor: "Path editor",
+    shader_playground: "Shader playground",
+    select_file: "Select a file on the left.",
+};
+
+const MY_JAPANESE: HudStrings = HudStrings {
+    fps: "フレームレート: {}",
+    position: "座標: {}",
+    spawn_explosion: "左クリック: 爆発を起こす",
+    spawn_bomb: "中クリック: 爆弾を置く",
+    pause_resume: "右クリック: 一時停止/再開",
+    chains: "{} 連鎖",
+    assets: "アセット",
+    path_editor: "パスエディター",
+    shader_playground: "シェーダー実験場",
+    select_file: "左のファイルを選んでください。",
+};
+
+// The primary font first, then the fallbacks in the order they are tried.
+pub const MY_FONT_FILES: [&str; 2] = ["LiberationMono-Regular.ttf", MY_CJK_FONT_FILE];
+// Noto Sans JP, under the SIL Open Font License; see NotoSansJP-OFL.txt next to it.
+pub const MY_CJK_FONT_FILE: &str = "NotoSansJP-Regular.otf";
+
+pub struct FallbackFont {
+    pub name: &'static str,
+    pub data: Vec<u8>,
+    // the characters that the font has glyphs for, read from its cmap once in load
+    chars: HashSet<char>,
+}
+
+impl FallbackFont {
+    pub fn has_glyph(&self, c: char) -> bool {
+        self.chars.contains(&c)
+    }
+}
+
+// A font and the fonts that draw the characters it has no glyphs for.
+// The engines draw one font at a time, so text is split into runs that one font can draw.
+pub struct FontFallback {
+    fonts: Vec<FallbackFont>,
+}
+
+impl FontFallback {
+    // Loads MY_FONT_FILES from dir. A font that cannot be read is left out with a warning,
+    // so the text still shows, with tofu for what the others do not cover.
+    pub fn load(dir: &Path) -> Self {
+        let mut fonts = vec![];
+        for name in MY_FONT_FILES {
+            let path = dir.join(name);
+            let data = match std::fs::read(&path) {
+                Ok(data) => data,
+                Err(err) => {
+                    eprintln!("{}: {}", path.display(), err);
+                    continue;
+                }
+            };
+            let chars = match ttf_parser::Face::parse(&data, 0) {
+                Ok(face) => chars(&face),
+                Err(err) => {
+                    eprintln!("{}: {}", path.display(), err);
+                    continue;
+                }
+            };
+            fonts.push(FallbackFont { name, data, chars });
+        }
+        Self { fonts }
+    }
+
+    pub fn fonts(&self) -> &[FallbackFont] {
+        &self.fonts
+    }
+
+    // Splits text where the font changes. Every run is drawn with fonts()[index].
+    // Characters that no font has, and whitespace, stay with the font before them.
+    pub fn runs<'a>(&self, text: &'a str) -> Vec<(usize, &'a str)> {
+        let mut runs: Vec<(usize, &'a str)> = vec![];
+        let mut start = 0;
+        let mut current = None;
+        for (i, c) in text.char_indices() {
+            let font = (self.fonts.iter())
+                .position(|font| font.has_glyph(c))
+                .filter(|_| !c.is_whitespace());
+            match (current, font) {
+                (Some(current_font), Some(font)) if font != current_font => {
+                    runs.push((current_font, &text[start..i]));
+                    start = i;
+                    current = Some(font);
+                }
+                (None, Some(font)) => current = Some(font),
+                _ => {}
+            }
+        }
+        if start < text.len() {
+            runs.push((current.unwrap_or(0), &text[start..]));
+        }
+        runs
+    }
+
+    // The characters of text that none of the fonts has.
+    pub fn missing(&self, text: &str) -> Vec<char> {
+        let mut missing = vec![];
+        for c in text.chars() {
+            let covered = c.is_whitespace() || self.fonts.iter().any(|font| font.has_glyph(c));
+            if !covered && !missing.contains(&c) {
+                missing.push(c);
+            }
+        }
+        missing
+    }
+}
+
+// Every character that the Unicode subtables of the cmap map to a glyph.
+fn chars(face: &ttf_parser::Face) -> HashSet<char> {
+    let mut chars = HashSet::new();
+    let Some(cmap) = face.tables().cmap else {
+        return chars;
+    };
+    for subtable in cmap
+        .subtables
+        .into_iter()
+        .filter(|table| table.is_unicode())
+    {
+        subtable.codepoints(|codepoint| {
+            let glyph = subtable.glyph_index(codepoint).filter(|glyph| glyph.0 != 0);
+            if let (Some(c), Some(_)) = (char::from_u32(codepoint), glyph) {
+                chars.insert(c);
+            }
+        });
+    }
+    chars
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fonts() -> FontFallback {
+        FontFallback::load(Path::new(concat!(env!("CARGO_MANIFEST_DIR"), "/../assets")))
+    }
+
+    #[test]
+    fn every_string_has_glyphs() {
+        let fonts = fonts();
+        assert_eq!(fonts.fonts().len(), MY_FONT_FILES.len());
+        for language in Language::ALL {
+            for text in language.strings().all() {
+                assert_eq!(fonts.missing(text), [], "{language:?}: {text:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn chars_agree_with_the_face() {
+        let fonts = fonts();
+        for font in fonts.fonts() {
+            let face = ttf_parser::Face::parse(&font.data, 0).unwrap();
+            for c in ['A', ':', '0', 'ア', '座', '\u{e000}', '\u{10ffff}'] {
+                let expected = face.glyph_index(c).is_some_and(|glyph| glyph.0 != 0);
+                assert_eq!(font.has_glyph(c), expected, "{} {c:?}", font.name);
+            }
+        }
+    }
+
+    #[test]
+    fn runs_switch_to_the_fallback_for_japanese() {
+        let fonts = fonts();
+        let text = fill(MY_ENGLISH.fps, 60);
+        assert_eq!(fonts.runs(&text), [(0, "FPS: 60")]);
+        // the primary font comes first wherever both have the glyph, as for ':'
+        let text = fill(MY_JAPANESE.fps, 60);
+        assert_eq!(fonts.runs(&text), [(1, "フレームレート"), (0, ": 60")]);
+        let text = fill(MY_JAPANESE.chains, 3);
+        assert_eq!(fonts.runs(&text), [(0, "3 "), (1, "連鎖")]);
+        assert_eq!(fonts.runs(""), []);
+    }
+
+    #[test]
+    fn language_from_the_flag_before_lang() {
+        let args = Args {
+            lang: Some("ja".to_owned()),
+            ..Args::default()
+        };
+        assert_eq!(Language::from_args(&args), Language::Japanese);
+        let args = Args {
+            lang: Some("en_US.UTF-8".to_owned()),
+            ..Args::default()
+        };
+        assert_eq!(Language::from_args(&args), Language::English);
+    }
+}
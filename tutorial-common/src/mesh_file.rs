@@ -0,0 +1,202 @@
+// Reads the meshes that lyon-tutorials writes with "--output FILE.mesh".
+// All little-endian: b"MESH", version: u32, vertex count: u32, index count: u32,
+// then [x: f32, y: f32] for each vertex and u32 for each index.
+use crate::args::Args;
+
+pub struct MeshFile {
+    pub positions: Vec<[f32; 2]>,
+    pub indices: Vec<u32>,
+}
+
+const MY_MAGIC: &[u8; 4] = b"MESH";
+const MY_VERSION: u32 = 1;
+const MY_HEADER_SIZE: usize = 16;
+// the triangle of the tutorial spans this much of clip space
+const MY_FIT_SIZE: f32 = 1.6;
+
+impl MeshFile {
+    // The mesh of `--mesh FILE`, if there is one. A file that cannot be loaded is not
+    // something to draw around, so this prints why and exits.
+    pub fn from_args(args: &Args) -> Option<Self> {
+        let path = args.mesh.as_deref()?;
+        match Self::load(path) {
+            Ok(mesh) => Some(mesh),
+            Err(err) => {
+                eprintln!("{err}");
+                std::process::exit(1);
+            }
+        }
+    }
+
+    pub fn load(path: &str) -> Result<Self, String> {
+        let bytes = std::fs::read(path).map_err(|err| format!("{path}: {err}"))?;
+        Self::parse(&bytes).map_err(|err| format!("{path}: {err}"))
+    }
+
+    pub fn parse(bytes: &[u8]) -> Result<Self, String> {
+        let word = |offset: usize| {
+            let b = bytes
+                .get(offset..offset + 4)
+                .ok_or("unexpected end of file")?;
+            Ok::<_, String>([b[0], b[1], b[2], b[3]])
+        };
+        if bytes.get(..4) != Some(MY_MAGIC) {
+            return Err("not a mesh file".to_owned());
+        }
+        let version = u32::from_le_bytes(word(4)?);
+        if version != MY_VERSION {
+            return Err(format!("version {version} is not supported"));
+        }
+        let vertex_count = u32::from_le_bytes(word(8)?) as usize;
+        let index_count = u32::from_le_bytes(word(12)?) as usize;
+        // the counts come from the file, so they are checked against its size before anything
+        // is allocated for them
+        let size = (vertex_count.checked_mul(8))
+            .zip(index_count.checked_mul(4))
+            .and_then(|(positions, indices)| positions.checked_add(indices))
+            .and_then(|data| data.checked_add(MY_HEADER_SIZE));
+        if size != Some(bytes.len()) {
+            return Err(format!(
+                "{vertex_count} vertices and {index_count} indices do not fit {} bytes",
+                bytes.len()
+            ));
+        }
+        let mut offset = MY_HEADER_SIZE;
+        let mut positions = Vec::with_capacity(vertex_count);
+        for _ in 0..vertex_count {
+            let x = f32::from_le_bytes(word(offset)?);
+            let y = f32::from_le_bytes(word(offset + 4)?);
+            positions.push([x, y]);
+            offset += 8;
+        }
+        let mut indices = Vec::with_capacity(index_count);
+        for _ in 0..index_count {
+            indices.push(u32::from_le_bytes(word(offset)?));
+            offset += 4;
+        }
+        if !index_count.is_multiple_of(3) {
+            return Err(format!("{index_count} indices do not make triangles"));
+        }
+        if let Some(index) = indices
+            .iter()
+            .find(|&&index| index as usize >= vertex_count)
+        {
+            return Err(format!("index {index} is out of {vertex_count} vertices"));
+        }
+        Ok(Self { positions, indices })
+    }
+
+    // Centers the mesh in clip space, scaled to the size of the triangle.
+    // Paths have y down, so y is flipped, and then every triangle is made counter-clockwise
+    // so that back-face culling treats them as the triangle.
+    pub fn fit_to_clip_space(&mut self) {
+        let mut min = [f32::INFINITY; 2];
+        let mut max = [f32::NEG_INFINITY; 2];
+        for position in &self.positions {
+            for axis in 0..2 {
+                min[axis] = min[axis].min(position[axis]);
+                max[axis] = max[axis].max(position[axis]);
+            }
+        }
+        let size = (max[0] - min[0]).max(max[1] - min[1]).max(f32::EPSILON);
+        let scale = MY_FIT_SIZE / size;
+        let center = [(min[0] + max[0]) / 2.0, (min[1] + max[1]) / 2.0];
+        for position in &mut self.positions {
+            *position = [
+                (position[0] - center[0]) * scale,
+                -(position[1] - center[1]) * scale,
+            ];
+        }
+        for triangle in self.indices.chunks_exact_mut(3) {
+            let [a, b, c] = [0, 1, 2].map(|i| self.positions[triangle[i] as usize]);
+            let area = (b[0] - a[0]) * (c[1] - a[1]) - (c[0] - a[0]) * (b[1] - a[1]);
+            if area < 0.0 {
+                triangle.swap(1, 2);
+            }
+        }
+    }
+
+    // Red to the right, green to the top and blue to the bottom left, as the triangle's corners.
+    pub fn color(position: [f32; 2]) -> [f32; 3] {
+        let [x, y] = position.map(|value| ((value + 1.0) / 2.0).clamp(0.0, 1.0));
+        [x, y, 1.0 - (x + y) / 2.0]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bytes(vertex_count: u32, index_count: u32, data: &[u32]) -> Vec<u8> {
+        let mut bytes = MY_MAGIC.to_vec();
+        for word in [MY_VERSION, vertex_count, index_count].iter().chain(data) {
+            bytes.extend(word.to_le_bytes());
+        }
+        bytes
+    }
+
+    fn triangle() -> Vec<u32> {
+        let positions = [0.0f32, 0.0, 1.0, 0.0, 0.0, 1.0].map(f32::to_bits);
+        [&positions[..], &[0, 1, 2]].concat()
+    }
+
+    #[test]
+    fn parses_a_triangle() {
+        let mesh = MeshFile::parse(&bytes(3, 3, &triangle())).unwrap();
+        assert_eq!(mesh.positions, [[0.0, 0.0], [1.0, 0.0], [0.0, 1.0]]);
+        assert_eq!(mesh.indices, [0, 1, 2]);
+        let mesh = MeshFile::parse(&bytes(0, 0, &[])).unwrap();
+        assert!(mesh.positions.is_empty() && mesh.indices.is_empty());
+    }
+
+    #[test]
+    fn rejects_a_size_that_does_not_match_the_counts() {
+        let mut truncated = bytes(3, 3, &triangle());
+        truncated.pop();
+        assert!(MeshFile::parse(&truncated).is_err());
+        let mut extra = bytes(3, 3, &triangle());
+        extra.push(0);
+        assert!(MeshFile::parse(&extra).is_err());
+        assert!(MeshFile::parse(&bytes(3, 3, &[])).is_err());
+        assert!(MeshFile::parse(&bytes(3, 3, &triangle())[..12]).is_err());
+    }
+
+    #[test]
+    fn rejects_huge_counts_without_allocating_them() {
+        // would ask for gigabytes if the counts were trusted
+        assert!(MeshFile::parse(&bytes(u32::MAX, u32::MAX, &triangle())).is_err());
+        assert!(MeshFile::parse(&bytes(u32::MAX, 0, &[])).is_err());
+        assert!(MeshFile::parse(&bytes(0, u32::MAX, &[])).is_err());
+    }
+
+    #[test]
+    fn rejects_what_is_not_triangles() {
+        assert!(MeshFile::parse(b"MES").is_err());
+        assert!(MeshFile::parse(&[b"OBJ ", &bytes(0, 0, &[])[4..]].concat()).is_err());
+        let mut data = triangle();
+        data.pop();
+        assert!(MeshFile::parse(&bytes(3, 2, &data)).is_err());
+        let mut data = triangle();
+        data[8] = 3;
+        assert!(MeshFile::parse(&bytes(3, 3, &data)).is_err());
+    }
+
+    #[test]
+    fn fits_counter_clockwise_in_clip_space() {
+        let data = [[10.0f32, 10.0], [30.0, 10.0], [10.0, 20.0]]
+            .concat()
+            .into_iter()
+            .map(f32::to_bits)
+            .chain([0, 1, 2])
+            .collect::<Vec<_>>();
+        let mut mesh = MeshFile::parse(&bytes(3, 3, &data)).unwrap();
+        mesh.fit_to_clip_space();
+        let expected = [[-0.8, 0.4], [0.8, 0.4], [-0.8, -0.4]];
+        for (position, expected) in mesh.positions.iter().zip(expected) {
+            let error = (position[0] - expected[0]).abs() + (position[1] - expected[1]).abs();
+            assert!(error < 1e-6, "{position:?} is not {expected:?}");
+        }
+        // y down in the file is clockwise in clip space, so the winding is turned
+        assert_eq!(mesh.indices, [0, 2, 1]);
+    }
+}
@@ -0,0 +1,168 @@
+use lyon::path::Path;
+
+// How many characters of the source are shown on each side of a parse error.
+const MY_CONTEXT_CHARS: usize = 24;
+
+// Reads a file of SVG path data, such as assets/rust-logo.path.
+pub fn load(file: &std::path::Path) -> Result<Path, String> {
+    let source =
+        std::fs::read_to_string(file).map_err(|err| format!("{}: {}", file.display(), err))?;
+    parse(&source).map_err(|err| format!("{}: {}", file.display(), err))
+}
+
+// The error tells the byte where parsing stopped and shows the source around it.
+pub fn parse(source: &str) -> Result<Path, String> {
+    use lyon::extra::parser::{ParseError, ParserOptions, PathParser, Source};
+
+    // lyon panics instead of failing when the path does not start with a move-to.
+    let first = (source.char_indices()).find(|&(_, c)| !c.is_whitespace() && c != ',');
+    if let Some((byte, command)) = first.filter(|&(_, c)| c != 'M' && c != 'm') {
+        return Err(at_byte(
+            source,
+            byte,
+            &format!("Expected move-to command, got {:?}.", command),
+        ));
+    }
+
+    let mut builder = Path::builder();
+    let mut parser = PathParser::new();
+    let options = ParserOptions::DEFAULT;
+    let mut chars = Source::with_position(0, 0, source.chars());
+    if let Err(err) = parser.parse(&options, &mut chars, &mut builder) {
+        let position = match err {
+            ParseError::Number { line, column, .. }
+            | ParseError::Flag { line, column, .. }
+            | ParseError::Command { line, column, .. }
+            | ParseError::MissingMoveTo { line, column, .. } => Some((line, column)),
+            _ => None,
+        };
+        let byte = position.and_then(|(line, column)| byte_at(source, line, column));
+        return Err(match byte {
+            Some(byte) => at_byte(source, byte, &err.to_string()),
+            None => err.to_string(),
+        });
+    }
+    Ok(builder.build())
+}
+
+fn at_byte(source: &str, byte: usize, message: &str) -> String {
+    format!("byte {}: {}\n{}", byte, message, context(source, byte))
+}
+
+// The parser counts lines and columns from 0, and columns in characters.
+fn byte_at(source: &str, line: i32, column: i32) -> Option<usize> {
+    let line = usize::try_from(line).ok()?;
+    let column = usize::try_from(column).ok()?;
+    let start = if line == 0 {
+        0
+    } else {
+        let (newline, _) = source.match_indices('\n').nth(line - 1)?;
+        newline + 1
+    };
+    let line_text = source[start..].split('\n').next().unwrap_or_default();
+    let offset = (line_text.char_indices())
+        .map(|(i, _)| i)
+        .chain(std::iter::once(line_text.len()))
+        .nth(column)?;
+    Some(start + offset)
+}
+
+// The line of the error, cut to MY_CONTEXT_CHARS around it, with a caret under the byte.
+fn context(source: &str, byte: usize) -> String {
+    let line_start = source[..byte].rfind('\n').map_or(0, |i| i + 1);
+    let line_end = source[byte..].find('\n').map_or(source.len(), |i| byte + i);
+    let before = source[line_start..byte]
+        .chars()
+        .rev()
+        .take(MY_CONTEXT_CHARS)
+        .collect::<Vec<_>>();
+    let after = source[byte..line_end].chars().take(MY_CONTEXT_CHARS + 1);
+    let mut text = String::new();
+    if line_start + before.iter().map(|c| c.len_utf8()).sum::<usize>() < byte {
+        text.push_str("...");
+    }
+    let caret = text.chars().count() + before.len();
+    text.extend(before.iter().rev());
+    text.extend(after);
+    if source[byte..line_end].chars().count() > MY_CONTEXT_CHARS + 1 {
+        text.push_str("...");
+    }
+    format!("{}\n{}^", text.trim_end_matches('\r'), " ".repeat(caret))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_path_data() {
+        let path = parse("M 0 0 L 10 0 L 10 10 Z M 20 20 L 30 30").unwrap();
+        assert_eq!(path.iter().count(), 7);
+    }
+
+    #[test]
+    fn reads_the_rust_logo() {
+        let file =
+            std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("../assets/rust-logo.path");
+        assert!(load(&file).unwrap().iter().count() > 0);
+    }
+
+    #[test]
+    fn an_invalid_command_points_at_its_byte() {
+        let err = parse("M 0 0 L 1 1\nL 2 2 X 3 3").unwrap_err();
+        assert_eq!(byte_at("M 0 0 L 1 1\nL 2 2 X 3 3", 1, 6), Some(18));
+        assert_eq!(
+            err,
+            "byte 18: Line 1 Column 6: Invalid command 'X'.\nL 2 2 X 3 3\n      ^"
+        );
+    }
+
+    #[test]
+    fn columns_count_characters_not_bytes() {
+        // 'é' is one column but two bytes.
+        let source = "M 0 0\nL 1 1 é 2 2";
+        assert_eq!(byte_at(source, 1, 6), Some(12));
+        assert_eq!(byte_at(source, 1, 7), Some(14));
+        assert_eq!(byte_at(source, 1, 11), Some(18));
+        assert_eq!(byte_at(source, 1, 12), None);
+        assert_eq!(byte_at(source, 2, 0), None);
+        assert_eq!(context(source, 14), "L 1 1 é 2 2\n       ^");
+
+        let err = parse("M 0 0 L é 2 2").unwrap_err();
+        assert!(err.starts_with("byte 8: "), "{}", err);
+        assert!(err.ends_with("M 0 0 L é 2 2\n        ^"), "{}", err);
+    }
+
+    #[test]
+    fn a_missing_move_to_is_an_error() {
+        assert_eq!(
+            parse("L 1 1").unwrap_err(),
+            "byte 0: Expected move-to command, got 'L'.\nL 1 1\n^"
+        );
+        assert!(parse("\n  l 1 1").unwrap_err().starts_with("byte 3: "));
+        assert!(parse("1 1 L 2 2").unwrap_err().starts_with("byte 0: "));
+        assert!(parse("M 0 0 Z L 1 1").unwrap_err().starts_with("byte 8: "));
+        assert_eq!(parse("").unwrap().iter().count(), 0);
+        assert_eq!(parse(" \n").unwrap().iter().count(), 0);
+    }
+
+    #[test]
+    fn context_is_cut_on_long_lines() {
+        let source = format!("{}X{}", "a".repeat(40), "b".repeat(40));
+        let text = context(&source, 40);
+        let expected = format!(
+            "...{}X{}...\n{}^",
+            "a".repeat(MY_CONTEXT_CHARS),
+            "b".repeat(MY_CONTEXT_CHARS),
+            " ".repeat(MY_CONTEXT_CHARS + 3)
+        );
+        assert_eq!(text, expected);
+
+        // Short lines and lines that end exactly at the limit are not cut.
+        assert_eq!(context("abXcd", 2), "abXcd\n  ^");
+        let source = format!("{}X{}", "a".repeat(24), "b".repeat(24));
+        assert!(!context(&source, 24).contains("..."));
+        let source = format!("{}X{}", "a".repeat(25), "b".repeat(25));
+        assert_eq!(context(&source, 25).matches("...").count(), 2);
+    }
+}
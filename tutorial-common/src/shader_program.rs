@@ -0,0 +1,237 @@
+use std::collections::HashMap;
+use std::ffi::{c_char, c_void, CString};
+use std::sync::OnceLock;
+
+// GL_ARB_gl_spirv is not part of the generated bindings.
+const SHADER_BINARY_FORMAT_SPIR_V: u32 = 0x9551;
+type SpecializeShader = unsafe extern "system" fn(u32, *const c_char, u32, *const u32, *const u32);
+static SPECIALIZE_SHADER: OnceLock<SpecializeShader> = OnceLock::new();
+
+// Returns false if glSpecializeShader is not available.
+pub fn load_spirv_with<F: FnMut(&str) -> *const c_void>(mut loadfn: F) -> bool {
+    let ptr = ["glSpecializeShader", "glSpecializeShaderARB"]
+        .into_iter()
+        .map(&mut loadfn)
+        .find(|ptr| !ptr.is_null());
+    match ptr {
+        Some(ptr) => {
+            let specialize_shader =
+                unsafe { std::mem::transmute::<*const c_void, SpecializeShader>(ptr) };
+            SPECIALIZE_SHADER.get_or_init(|| specialize_shader);
+            true
+        }
+        None => false,
+    }
+}
+
+pub struct ShaderProgram {
+    id: u32,
+    uniform_locations: HashMap<String, i32>,
+}
+
+impl ShaderProgram {
+    pub fn new(shader_sources: &[(u32, &str)]) -> Result<Self, String> {
+        let shader_version = if cfg!(target_arch = "wasm32") {
+            "#version 300 es"
+        } else {
+            "#version 330"
+        };
+        Self::with_version(shader_version, shader_sources)
+    }
+
+    // For when the version line depends on the API of the current context.
+    pub fn with_version(
+        shader_version: &str,
+        shader_sources: &[(u32, &str)],
+    ) -> Result<Self, String> {
+        unsafe {
+            let program = Self {
+                id: gl::CreateProgram(),
+                uniform_locations: HashMap::new(),
+            };
+            let mut shaders = vec![];
+            let mut result = Ok(());
+            for (shader_type, shader_source) in shader_sources {
+                let shader = gl::CreateShader(*shader_type);
+                let shader_source = format!("{shader_version}\n{shader_source}");
+                let length = shader_source.len() as _;
+                gl::ShaderSource(shader, 1, [shader_source.as_ptr() as _].as_ptr(), &length);
+                gl::CompileShader(shader);
+                gl::AttachShader(program.id, shader);
+                shaders.push(shader);
+                if let Err(message) = shader_status(shader) {
+                    result = Err(format!("Failed to compile {shader_type}: {message}"));
+                    break;
+                }
+            }
+            if result.is_ok() {
+                gl::LinkProgram(program.id);
+                if let Err(message) = program_status(program.id) {
+                    result = Err(format!("Failed to link: {message}"));
+                }
+            }
+            shaders.iter().for_each(|shader| {
+                gl::DetachShader(program.id, *shader);
+                gl::DeleteShader(*shader);
+            });
+
+            // the program is deleted on drop if any of the steps failed
+            result.map(|_| program)
+        }
+    }
+
+    // Takes SPIR-V modules whose entry points are named "main".
+    pub fn from_spirv(shader_binaries: &[(u32, &[u8])]) -> Result<Self, String> {
+        let specialize_shader = SPECIALIZE_SHADER
+            .get()
+            .ok_or("glSpecializeShader is not loaded")?;
+
+        unsafe {
+            let program = Self {
+                id: gl::CreateProgram(),
+                uniform_locations: HashMap::new(),
+            };
+            let mut shaders = vec![];
+            let mut result = Ok(());
+            for (shader_type, shader_binary) in shader_binaries {
+                let shader = gl::CreateShader(*shader_type);
+                gl::ShaderBinary(
+                    1,
+                    &shader,
+                    SHADER_BINARY_FORMAT_SPIR_V,
+                    shader_binary.as_ptr() as _,
+                    shader_binary.len() as _,
+                );
+                specialize_shader(
+                    shader,
+                    c"main".as_ptr(),
+                    0,
+                    std::ptr::null(),
+                    std::ptr::null(),
+                );
+                gl::AttachShader(program.id, shader);
+                shaders.push(shader);
+                if let Err(message) = shader_status(shader) {
+                    result = Err(format!("Failed to specialize {shader_type}: {message}"));
+                    break;
+                }
+            }
+            if result.is_ok() {
+                gl::LinkProgram(program.id);
+                if let Err(message) = program_status(program.id) {
+                    result = Err(format!("Failed to link: {message}"));
+                }
+            }
+            shaders.iter().for_each(|shader| {
+                gl::DetachShader(program.id, *shader);
+                gl::DeleteShader(*shader);
+            });
+
+            result.map(|_| program)
+        }
+    }
+
+    pub fn use_program(&self) {
+        unsafe {
+            gl::UseProgram(self.id);
+        }
+    }
+
+    pub fn uniform_location(&mut self, name: &str) -> i32 {
+        let id = self.id;
+        *self
+            .uniform_locations
+            .entry(name.to_owned())
+            .or_insert_with(|| {
+                let c_name = CString::new(name).unwrap();
+                let location = unsafe { gl::GetUniformLocation(id, c_name.as_ptr()) };
+                if location < 0 {
+                    eprintln!("Uniform '{name}' is not active.");
+                }
+                location
+            })
+    }
+
+    // The program must be in use.
+    pub fn set_uniform<T: Uniform>(&mut self, name: &str, value: T) {
+        let location = self.uniform_location(name);
+        unsafe {
+            value.set(location);
+        }
+    }
+}
+
+impl Drop for ShaderProgram {
+    fn drop(&mut self) {
+        unsafe {
+            gl::DeleteProgram(self.id);
+        }
+    }
+}
+
+pub trait Uniform {
+    /// # Safety
+    /// The program that the location belongs to must be in use on the current context.
+    unsafe fn set(&self, location: i32);
+}
+
+impl Uniform for f32 {
+    unsafe fn set(&self, location: i32) {
+        gl::Uniform1f(location, *self);
+    }
+}
+
+impl Uniform for [f32; 2] {
+    unsafe fn set(&self, location: i32) {
+        gl::Uniform2fv(location, 1, self.as_ptr());
+    }
+}
+
+impl Uniform for [f32; 3] {
+    unsafe fn set(&self, location: i32) {
+        gl::Uniform3fv(location, 1, self.as_ptr());
+    }
+}
+
+impl Uniform for [f32; 4] {
+    unsafe fn set(&self, location: i32) {
+        gl::Uniform4fv(location, 1, self.as_ptr());
+    }
+}
+
+// column-major, as GLSL expects
+impl Uniform for [[f32; 4]; 4] {
+    unsafe fn set(&self, location: i32) {
+        gl::UniformMatrix4fv(location, 1, gl::FALSE, self.as_ptr().cast());
+    }
+}
+
+unsafe fn shader_status(shader: u32) -> Result<(), String> {
+    let mut status = 0;
+    gl::GetShaderiv(shader, gl::COMPILE_STATUS, &mut status);
+    if status != gl::FALSE as gl::types::GLint {
+        return Ok(());
+    }
+
+    let mut length = 0;
+    gl::GetShaderiv(shader, gl::INFO_LOG_LENGTH, &mut length);
+    let mut message = vec![0u8; length.max(1) as usize];
+    gl::GetShaderInfoLog(shader, length, &mut length, message.as_mut_ptr() as _);
+    message.truncate(length as usize);
+    Err(String::from_utf8_lossy(&message).into_owned())
+}
+
+unsafe fn program_status(program: u32) -> Result<(), String> {
+    let mut status = 0;
+    gl::GetProgramiv(program, gl::LINK_STATUS, &mut status);
+    if status != gl::FALSE as gl::types::GLint {
+        return Ok(());
+    }
+
+    let mut length = 0;
+    gl::GetProgramiv(program, gl::INFO_LOG_LENGTH, &mut length);
+    let mut message = vec![0u8; length.max(1) as usize];
+    gl::GetProgramInfoLog(program, length, &mut length, message.as_mut_ptr() as _);
+    message.truncate(length as usize);
+    Err(String::from_utf8_lossy(&message).into_owned())
+}
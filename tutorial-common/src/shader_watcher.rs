@@ -0,0 +1,48 @@
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+// Polls the modification time of shader files so that they can be rebuilt at runtime.
+pub struct ShaderWatcher {
+    files: Vec<(PathBuf, Option<SystemTime>)>,
+}
+
+impl ShaderWatcher {
+    pub fn new<P: AsRef<Path>>(paths: &[P]) -> Self {
+        let files = paths
+            .iter()
+            .map(|path| {
+                let path = path.as_ref().to_path_buf();
+                let modified = Self::modified(&path);
+                (path, modified)
+            })
+            .collect();
+        Self { files }
+    }
+
+    pub fn path(&self, index: usize) -> &Path {
+        &self.files[index].0
+    }
+
+    pub fn read(&self, index: usize) -> std::io::Result<String> {
+        std::fs::read_to_string(self.path(index))
+    }
+
+    // Returns true once for every change of any of the watched files.
+    pub fn changed(&mut self) -> bool {
+        let mut changed = false;
+        for (path, modified) in self.files.iter_mut() {
+            let latest = Self::modified(path);
+            if latest.is_some() && latest != *modified {
+                *modified = latest;
+                changed = true;
+            }
+        }
+        changed
+    }
+
+    fn modified(path: &Path) -> Option<SystemTime> {
+        std::fs::metadata(path)
+            .and_then(|metadata| metadata.modified())
+            .ok()
+    }
+}
@@ -1,6 +1,23 @@
+use tutorial_common::args::Args;
+use tutorial_common::event_log::winit_0_29 as event_log;
+
+use event_log::Command;
+
 use std::sync::Arc;
 
 fn main() {
+    let args = Args::from_env(&["--play", "--record"]);
+    if let Some(path) = &args.play {
+        let events = event_log::load(path).unwrap();
+        let played = event_log::play(&events, |event| {
+            let command = event.command();
+            println!("{:?} -> {:?}", event, command);
+            command != Some(Command::Exit)
+        });
+        println!("played {} of {} events", played, events.len());
+        return;
+    }
+
     let event_queue = {
         use winit::event_loop::{ControlFlow, EventLoop};
 
@@ -27,13 +44,13 @@ fn main() {
     };
 
     let window = {
-        use winit::{dpi::PhysicalSize, window::WindowBuilder};
+        use winit::{dpi::LogicalSize, window::WindowBuilder};
 
         let window = WindowBuilder::new()
             .with_title("vulkano tutorial")
-            .with_inner_size(PhysicalSize {
-                width: 480,
-                height: 320,
+            .with_inner_size(LogicalSize {
+                width: 480.0,
+                height: 320.0,
             })
             .build(&event_queue)
             .unwrap();
@@ -266,36 +283,12 @@ fn main() {
 
     let mut previous_frame_end = Some(sync::now(device.clone()).boxed());
 
-    _ = event_queue.run(move |event, window_target| {
-        use winit::event::{ElementState, Event, KeyEvent, WindowEvent};
-        use winit::keyboard::{KeyCode, PhysicalKey};
+    use event_log::{EventRecorder, InputEvent};
+    use winit::event::{Event, WindowEvent};
+    use winit::event_loop::EventLoopWindowTarget;
 
+    let handler = move |event: Event<()>, window_target: &EventLoopWindowTarget<()>| {
         match event {
-            Event::WindowEvent {
-                event: WindowEvent::CloseRequested,
-                ..
-            }
-            | Event::WindowEvent {
-                event:
-                    WindowEvent::KeyboardInput {
-                        event:
-                            KeyEvent {
-                                state: ElementState::Pressed,
-                                physical_key: PhysicalKey::Code(KeyCode::Escape),
-                                ..
-                            },
-                        ..
-                    },
-                ..
-            } => {
-                window_target.exit();
-            }
-            Event::WindowEvent {
-                event: WindowEvent::Resized(_),
-                ..
-            } => {
-                recreate_swapchain = true;
-            }
             Event::WindowEvent {
                 event: WindowEvent::RedrawRequested,
                 ..
@@ -413,10 +406,32 @@ fn main() {
                     }
                 }
             }
+            Event::WindowEvent { event, .. } => {
+                match InputEvent::from_window_event(&event).and_then(|event| event.command()) {
+                    Some(Command::Exit) => {
+                        window_target.exit();
+                    }
+                    Some(Command::Resize(_)) => {
+                        recreate_swapchain = true;
+                    }
+                    Some(Command::Rescale(scale_factor)) => {
+                        println!("scale factor changed: {}", scale_factor);
+                        recreate_swapchain = true;
+                    }
+                    None => {}
+                }
+            }
             Event::AboutToWait => window.request_redraw(),
             _ => (),
         }
-    });
+    };
+    _ = if let Some(path) = &args.record {
+        println!("recording to {}", path);
+        let recorder = EventRecorder::create(path).unwrap();
+        event_queue.run(recorder.wrap(handler))
+    } else {
+        event_queue.run(handler)
+    };
 }
 
 use vulkano::buffer::BufferContents;
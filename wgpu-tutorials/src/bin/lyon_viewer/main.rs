@@ -0,0 +1,553 @@
+mod tessellation;
+mod zoom;
+
+use lyon::math::{point, Box2D, Point};
+use tessellation::{MyDoubleBuffer, MyGeometry, MyShape, MyTessellator, MyVertex};
+use tutorial_common::path_file;
+use winit::{dpi::PhysicalSize, window::Window};
+use zoom::MyView;
+
+const MY_USAGE: &str = "usage: lyon_viewer [FILE.path]...";
+// how many levels of detail are kept, each one a doubling of the zoom
+const MY_CACHE_CAPACITY: usize = 6;
+const MY_SAMPLE_COUNT: u32 = 4;
+// how much a line of the mouse wheel zooms
+const MY_ZOOM_STEP: f32 = 1.2;
+// how many pixels of a touchpad scroll make a line
+const MY_PIXELS_PER_LINE: f32 = 50.0;
+const MY_COLORS: [[f32; 4]; 4] = [
+    [0.72, 0.25, 0.05, 1.0],
+    [0.05, 0.45, 0.72, 1.0],
+    [0.25, 0.62, 0.15, 1.0],
+    [0.62, 0.15, 0.55, 1.0],
+];
+const MY_OUTLINE_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 1.0];
+const MY_OUTLINE_WIDTH: f32 = 0.25;
+
+// Draws the paths of the files, or the rust logo, filled and outlined.
+// Drag to pan, scroll to zoom, 0 to fit the paths in the window again.
+#[pollster::main]
+async fn main() {
+    use winit::dpi::LogicalSize;
+    use winit::event_loop::{ControlFlow, EventLoop};
+    use winit::window::WindowBuilder;
+
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    let shapes = match load_shapes(&args) {
+        Ok(shapes) => shapes,
+        Err(err) => {
+            eprintln!("{}\n{}", err, MY_USAGE);
+            std::process::exit(1);
+        }
+    };
+
+    let event_queue = EventLoop::new().unwrap();
+    event_queue.set_control_flow(ControlFlow::Poll);
+    let window = &WindowBuilder::new()
+        .with_title("lyon viewer")
+        .with_inner_size(LogicalSize::new(800.0, 600.0))
+        .build(&event_queue)
+        .unwrap();
+    let mut my_app = MyApp::new(window, shapes).await;
+
+    use winit::event::{ElementState, Event, MouseButton, MouseScrollDelta, WindowEvent};
+    use winit::keyboard::{KeyCode, PhysicalKey};
+
+    _ = event_queue.run(move |event, window_target| match event {
+        Event::AboutToWait => window.request_redraw(),
+        Event::WindowEvent {
+            window_id,
+            ref event,
+        } if window_id == window.id() => match event {
+            WindowEvent::CloseRequested => window_target.exit(),
+            WindowEvent::KeyboardInput { event, .. } if event.state == ElementState::Pressed => {
+                match event.physical_key {
+                    PhysicalKey::Code(KeyCode::Escape) => window_target.exit(),
+                    PhysicalKey::Code(KeyCode::Digit0 | KeyCode::Home) => my_app.fit(),
+                    _ => {}
+                }
+            }
+            WindowEvent::Resized(size) => my_app.resize(*size),
+            WindowEvent::CursorMoved { position, .. } => {
+                let position = point(position.x as f32, position.y as f32);
+                if let (true, Some(cursor)) = (my_app.dragging, my_app.cursor) {
+                    my_app.view.pan(position - cursor);
+                }
+                my_app.cursor = Some(position);
+            }
+            WindowEvent::CursorLeft { .. } => my_app.cursor = None,
+            WindowEvent::MouseInput {
+                state,
+                button: MouseButton::Left,
+                ..
+            } => my_app.dragging = *state == ElementState::Pressed,
+            WindowEvent::MouseWheel { delta, .. } => {
+                let lines = match delta {
+                    MouseScrollDelta::LineDelta(_, y) => *y,
+                    MouseScrollDelta::PixelDelta(position) => {
+                        position.y as f32 / MY_PIXELS_PER_LINE
+                    }
+                };
+                my_app.zoom(MY_ZOOM_STEP.powf(lines));
+            }
+            WindowEvent::RedrawRequested => {
+                use wgpu::SurfaceError;
+
+                my_app.update();
+                match my_app.render() {
+                    Ok(_) => {}
+                    Err(SurfaceError::Lost) => my_app.resize(my_app.size),
+                    Err(SurfaceError::OutOfMemory) => window_target.exit(),
+                    Err(err) => eprintln!("{:?}", err),
+                }
+            }
+            _ => {}
+        },
+        _ => {}
+    });
+}
+
+fn load_shapes(files: &[String]) -> Result<Vec<MyShape>, String> {
+    let files = if files.is_empty() {
+        vec![resource_dir().join("rust-logo.path")]
+    } else {
+        files.iter().map(std::path::PathBuf::from).collect()
+    };
+    let mut shapes = vec![];
+    for (i, file) in files.iter().enumerate() {
+        let path = path_file::load(file)?;
+        shapes.push(MyShape {
+            path: path.clone(),
+            color: MY_COLORS[i % MY_COLORS.len()],
+            stroke: None,
+        });
+        shapes.push(MyShape {
+            path,
+            color: MY_OUTLINE_COLOR,
+            stroke: Some(MY_OUTLINE_WIDTH),
+        });
+    }
+    Ok(shapes)
+}
+
+fn resource_dir() -> std::path::PathBuf {
+    use std::{env, path::PathBuf};
+
+    if let Ok(manifest_dir) = env::var("CARGO_MANIFEST_DIR") {
+        let mut path = PathBuf::from(manifest_dir);
+        path.push("../assets");
+        path
+    } else {
+        PathBuf::from("../assets")
+    }
+}
+
+// A vertex and an index buffer, grown as needed.
+struct MyBuffers {
+    vertex_buffer: wgpu::Buffer,
+    index_buffer: wgpu::Buffer,
+    num_indices: u32,
+}
+
+impl MyBuffers {
+    fn new(device: &wgpu::Device) -> Self {
+        Self {
+            vertex_buffer: Self::create_buffer(
+                device,
+                "Vertex Buffer",
+                wgpu::BufferUsages::VERTEX,
+                0,
+            ),
+            index_buffer: Self::create_buffer(device, "Index Buffer", wgpu::BufferUsages::INDEX, 0),
+            num_indices: 0,
+        }
+    }
+
+    fn create_buffer(
+        device: &wgpu::Device,
+        label: &str,
+        usage: wgpu::BufferUsages,
+        size: usize,
+    ) -> wgpu::Buffer {
+        device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some(label),
+            size: size.max(256).next_power_of_two() as wgpu::BufferAddress,
+            usage: usage | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        })
+    }
+
+    fn upload(&mut self, device: &wgpu::Device, queue: &wgpu::Queue, geometry: &MyGeometry) {
+        let vertices: &[u8] = bytemuck::cast_slice(&geometry.vertices);
+        let indices: &[u8] = bytemuck::cast_slice(&geometry.indices);
+        if vertices.len() as wgpu::BufferAddress > self.vertex_buffer.size() {
+            self.vertex_buffer = Self::create_buffer(
+                device,
+                "Vertex Buffer",
+                wgpu::BufferUsages::VERTEX,
+                vertices.len(),
+            );
+        }
+        if indices.len() as wgpu::BufferAddress > self.index_buffer.size() {
+            self.index_buffer = Self::create_buffer(
+                device,
+                "Index Buffer",
+                wgpu::BufferUsages::INDEX,
+                indices.len(),
+            );
+        }
+        queue.write_buffer(&self.vertex_buffer, 0, vertices);
+        queue.write_buffer(&self.index_buffer, 0, indices);
+        self.num_indices = geometry.indices.len() as u32;
+    }
+}
+
+struct MyApp<'a> {
+    window: &'a Window,
+    size: PhysicalSize<u32>,
+    surface: wgpu::Surface<'a>,
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+    config: wgpu::SurfaceConfiguration,
+    sample_count: u32,
+    multisampled_view: Option<wgpu::TextureView>,
+    uniform_buffer: wgpu::Buffer,
+    uniform_bind_group: wgpu::BindGroup,
+    render_pipeline: wgpu::RenderPipeline,
+    buffers: MyDoubleBuffer<MyBuffers>,
+    tessellator: MyTessellator,
+    bounds: Box2D,
+    view: MyView,
+    cursor: Option<Point>,
+    dragging: bool,
+}
+
+impl<'a> MyApp<'a> {
+    async fn new(window: &'a Window, shapes: Vec<MyShape>) -> Self {
+        //
+        // size, surface, device, queue, config
+        //
+        let size = window.inner_size();
+        let instance = wgpu::Instance::new(wgpu::InstanceDescriptor {
+            flags: Default::default(),
+            backends: wgpu::Backends::all(),
+            dx12_shader_compiler: Default::default(),
+            gles_minor_version: Default::default(),
+        });
+        let surface = instance.create_surface(window).unwrap();
+        let adapter = instance
+            .request_adapter(&wgpu::RequestAdapterOptions {
+                power_preference: wgpu::PowerPreference::HighPerformance,
+                compatible_surface: Some(&surface),
+                force_fallback_adapter: false,
+            })
+            .await
+            .unwrap();
+        let (device, queue) = adapter
+            .request_device(
+                &wgpu::DeviceDescriptor {
+                    label: None,
+                    required_features: wgpu::Features::empty(),
+                    required_limits: wgpu::Limits::default(),
+                },
+                None,
+            )
+            .await
+            .unwrap();
+        let surface_caps = surface.get_capabilities(&adapter);
+        let surface_format = surface_caps
+            .formats
+            .iter()
+            .copied()
+            .find(|f| f.is_srgb())
+            .unwrap_or(surface_caps.formats[0]);
+        let config = wgpu::SurfaceConfiguration {
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            format: surface_format,
+            width: size.width,
+            height: size.height,
+            desired_maximum_frame_latency: 2,
+            present_mode: surface_caps.present_modes[0],
+            alpha_mode: surface_caps.alpha_modes[0],
+            view_formats: vec![],
+        };
+        surface.configure(&device, &config);
+
+        // not every adapter can multisample every format
+        let sample_count = if adapter
+            .get_texture_format_features(surface_format)
+            .flags
+            .sample_count_supported(MY_SAMPLE_COUNT)
+        {
+            MY_SAMPLE_COUNT
+        } else {
+            println!(
+                "{:?} can't be multisampled, drawing without MSAA",
+                surface_format
+            );
+            1
+        };
+
+        //
+        // uniform_buffer, uniform_bind_group_layout, uniform_bind_group
+        //
+        let uniform_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Uniform Buffer"),
+            size: std::mem::size_of::<[f32; 4]>() as wgpu::BufferAddress,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let uniform_bind_group_layout =
+            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                label: Some("Uniform Bind Group Layout"),
+                entries: &[wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::VERTEX,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                }],
+            });
+        let uniform_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Uniform Bind Group"),
+            layout: &uniform_bind_group_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: uniform_buffer.as_entire_binding(),
+            }],
+        });
+
+        //
+        // render_pipeline
+        //
+        let shader = device.create_shader_module(wgpu::include_wgsl!("viewer.wgsl"));
+        let render_pipeline_layout =
+            device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: Some("Render Pipeline Layout"),
+                bind_group_layouts: &[&uniform_bind_group_layout],
+                push_constant_ranges: &[],
+            });
+        let render_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Render Pipeline"),
+            layout: Some(&render_pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: "vs_main",
+                buffers: &[MyVertex::desc()],
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: "fs_main",
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: config.format,
+                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                strip_index_format: None,
+                front_face: wgpu::FrontFace::Ccw,
+                // lyon does not promise a winding for its triangles
+                cull_mode: None,
+                polygon_mode: wgpu::PolygonMode::Fill,
+                unclipped_depth: false,
+                conservative: false,
+            },
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState {
+                count: sample_count,
+                mask: !0,
+                alpha_to_coverage_enabled: false,
+            },
+            multiview: None,
+        });
+
+        //
+        // buffers, tessellator, view
+        //
+        let buffers = MyDoubleBuffer::new([MyBuffers::new(&device), MyBuffers::new(&device)]);
+        let mut bounds = Box2D::zero();
+        for shape in &shapes {
+            use lyon::algorithms::aabb::bounding_box;
+
+            let shape_bounds = bounding_box(&shape.path);
+            bounds = if bounds.is_empty() {
+                shape_bounds
+            } else {
+                bounds.union(&shape_bounds)
+            };
+        }
+        let tessellator = MyTessellator::new(shapes, MY_CACHE_CAPACITY);
+        let view = MyView::fit(&bounds, [size.width as f32, size.height as f32]);
+
+        let mut my_app = Self {
+            window,
+            size,
+            surface,
+            device,
+            queue,
+            config,
+            sample_count,
+            multisampled_view: None,
+            uniform_buffer,
+            uniform_bind_group,
+            render_pipeline,
+            buffers,
+            tessellator,
+            bounds,
+            view,
+            cursor: None,
+            dragging: false,
+        };
+        my_app.multisampled_view = my_app.create_multisampled_view();
+        my_app
+    }
+
+    // What the surface is drawn into before it is resolved to the surface.
+    fn create_multisampled_view(&self) -> Option<wgpu::TextureView> {
+        if self.sample_count == 1 {
+            return None;
+        }
+        let texture = self.device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Multisampled Texture"),
+            size: wgpu::Extent3d {
+                width: self.config.width,
+                height: self.config.height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: self.sample_count,
+            dimension: wgpu::TextureDimension::D2,
+            format: self.config.format,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            view_formats: &[],
+        });
+        Some(texture.create_view(&wgpu::TextureViewDescriptor::default()))
+    }
+
+    fn window_size(&self) -> [f32; 2] {
+        [self.size.width as f32, self.size.height as f32]
+    }
+
+    fn fit(&mut self) {
+        self.view = MyView::fit(&self.bounds, self.window_size());
+    }
+
+    // Around the cursor, or the center of the window.
+    fn zoom(&mut self, factor: f32) {
+        let size = self.window_size();
+        let pixel = self.cursor.unwrap_or(point(size[0], size[1]) / 2.0);
+        self.view.zoom_at(pixel, size, factor);
+    }
+
+    fn resize(&mut self, new_size: PhysicalSize<u32>) {
+        if new_size.width > 0 && new_size.height > 0 {
+            self.size = new_size;
+            (self.config.width, self.config.height) = (new_size.width, new_size.height);
+            self.surface.configure(&self.device, &self.config);
+            self.multisampled_view = self.create_multisampled_view();
+        }
+    }
+
+    // Keeps drawing the front buffers until the geometry for the zoom is ready, then uploads it
+    // to the back buffers and swaps them.
+    fn update(&mut self) {
+        self.queue.write_buffer(
+            &self.uniform_buffer,
+            0,
+            bytemuck::cast_slice(&self.view.transform(self.window_size())),
+        );
+
+        let level = zoom::tolerance_level(self.view.zoom);
+        let (device, queue) = (&self.device, &self.queue);
+        let swapped = self
+            .buffers
+            .update(level, &mut self.tessellator, |buffers, geometry| {
+                buffers.upload(device, queue, geometry)
+            });
+        if let Some(geometry) = swapped {
+            self.window.set_title(&format!(
+                "lyon viewer: tolerance {}, {} triangles",
+                zoom::tolerance(level),
+                geometry.indices.len() / 3
+            ));
+        }
+    }
+
+    fn render(&mut self) -> Result<(), wgpu::SurfaceError> {
+        let output = self.surface.get_current_texture()?;
+        let view = output
+            .texture
+            .create_view(&wgpu::TextureViewDescriptor::default());
+        let mut encoder = self
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("Render Encoder"),
+            });
+        {
+            let (attachment, resolve_target) = match &self.multisampled_view {
+                Some(multisampled_view) => (multisampled_view, Some(&view)),
+                None => (&view, None),
+            };
+            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("Render Pass"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: attachment,
+                    resolve_target,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(wgpu::Color {
+                            a: 1.0,
+                            r: 0.2,
+                            g: 0.2,
+                            b: 0.2,
+                        }),
+                        // only the resolved surface is kept
+                        store: match resolve_target {
+                            Some(_) => wgpu::StoreOp::Discard,
+                            None => wgpu::StoreOp::Store,
+                        },
+                    },
+                })],
+                ..Default::default()
+            });
+            let buffers = self.buffers.front();
+            if buffers.num_indices > 0 {
+                render_pass.set_pipeline(&self.render_pipeline);
+                render_pass.set_bind_group(0, &self.uniform_bind_group, &[]);
+                render_pass.set_vertex_buffer(0, buffers.vertex_buffer.slice(..));
+                render_pass
+                    .set_index_buffer(buffers.index_buffer.slice(..), wgpu::IndexFormat::Uint32);
+                render_pass.draw_indexed(0..buffers.num_indices, 0, 0..1);
+            }
+        }
+        self.queue.submit(std::iter::once(encoder.finish()));
+        output.present();
+
+        Ok(())
+    }
+}
+
+impl MyVertex {
+    fn desc() -> wgpu::VertexBufferLayout<'static> {
+        wgpu::VertexBufferLayout {
+            array_stride: std::mem::size_of::<MyVertex>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Vertex,
+            attributes: &[
+                wgpu::VertexAttribute {
+                    offset: 0,
+                    shader_location: 0,
+                    format: wgpu::VertexFormat::Float32x2,
+                },
+                wgpu::VertexAttribute {
+                    offset: std::mem::size_of::<[f32; 2]>() as wgpu::BufferAddress,
+                    shader_location: 1,
+                    format: wgpu::VertexFormat::Float32x4,
+                },
+            ],
+        }
+    }
+}
@@ -0,0 +1,331 @@
+use lyon::path::Path;
+use lyon::tessellation::VertexBuffers;
+use std::collections::VecDeque;
+use std::sync::mpsc::{channel, Receiver, Sender};
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct MyVertex {
+    pub position: [f32; 2],
+    pub color: [f32; 4],
+}
+
+pub type MyGeometry = VertexBuffers<MyVertex, u32>;
+
+// A path as it is drawn: filled, or stroked with a width.
+pub struct MyShape {
+    pub path: Path,
+    pub color: [f32; 4],
+    pub stroke: Option<f32>,
+}
+
+pub fn tessellate(shapes: &[MyShape], tolerance: f32) -> MyGeometry {
+    use lyon::tessellation::{
+        BuffersBuilder, FillOptions, FillTessellator, FillVertex, StrokeOptions, StrokeTessellator,
+        StrokeVertex,
+    };
+
+    let mut geometry: MyGeometry = VertexBuffers::new();
+    let mut fill_tessellator = FillTessellator::new();
+    let mut stroke_tessellator = StrokeTessellator::new();
+    for shape in shapes {
+        let color = shape.color;
+        let result = match shape.stroke {
+            Some(width) => stroke_tessellator.tessellate_path(
+                &shape.path,
+                &StrokeOptions::tolerance(tolerance).with_line_width(width),
+                &mut BuffersBuilder::new(&mut geometry, |vertex: StrokeVertex| MyVertex {
+                    position: vertex.position().to_array(),
+                    color,
+                }),
+            ),
+            None => fill_tessellator.tessellate_path(
+                &shape.path,
+                &FillOptions::tolerance(tolerance),
+                &mut BuffersBuilder::new(&mut geometry, |vertex: FillVertex| MyVertex {
+                    position: vertex.position().to_array(),
+                    color,
+                }),
+            ),
+        };
+        if let Err(err) = result {
+            eprintln!("tolerance {}: {:?}", tolerance, err);
+        }
+    }
+    geometry
+}
+
+// The geometry of the last few levels of detail, so that zooming back and forth does not
+// tessellate again. The most recently used is at the back.
+pub struct MyCache {
+    capacity: usize,
+    entries: VecDeque<(i32, MyGeometry)>,
+}
+
+impl MyCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: VecDeque::new(),
+        }
+    }
+
+    pub fn get(&mut self, level: i32) -> Option<&MyGeometry> {
+        let index = self.entries.iter().position(|(l, _)| *l == level)?;
+        let entry = self.entries.remove(index)?;
+        self.entries.push_back(entry);
+        self.entries.back().map(|(_, geometry)| geometry)
+    }
+
+    // Replaces the geometry of the same level, and drops the least recently used over capacity.
+    pub fn insert(&mut self, level: i32, geometry: MyGeometry) {
+        self.entries.retain(|(l, _)| *l != level);
+        self.entries.push_back((level, geometry));
+        while self.entries.len() > self.capacity {
+            self.entries.pop_front();
+        }
+    }
+}
+
+// Tessellates on a thread of its own, so that the window keeps drawing the last geometry while
+// the next one is made. Only the latest of the waiting requests is tessellated.
+pub struct MyTessellator {
+    requests: Sender<i32>,
+    results: Receiver<(i32, MyGeometry)>,
+    cache: MyCache,
+    pending: Option<i32>,
+}
+
+impl MyTessellator {
+    pub fn new(shapes: Vec<MyShape>, cache_capacity: usize) -> Self {
+        let (requests, request_receiver) = channel::<i32>();
+        let (result_sender, results) = channel();
+        std::thread::spawn(move || {
+            while let Ok(mut level) = request_receiver.recv() {
+                while let Ok(newer) = request_receiver.try_recv() {
+                    level = newer;
+                }
+                let geometry = tessellate(&shapes, crate::zoom::tolerance(level));
+                if result_sender.send((level, geometry)).is_err() {
+                    break;
+                }
+            }
+        });
+        Self {
+            requests,
+            results,
+            cache: MyCache::new(cache_capacity),
+            pending: None,
+        }
+    }
+
+    // The geometry of the level if it is ready. If not, it is requested, and None until then.
+    pub fn geometry(&mut self, level: i32) -> Option<&MyGeometry> {
+        while let Ok((done, geometry)) = self.results.try_recv() {
+            if self.pending == Some(done) {
+                self.pending = None;
+            }
+            self.cache.insert(done, geometry);
+        }
+        if self.cache.get(level).is_none() && self.pending != Some(level) {
+            self.pending = Some(level);
+            // the thread only ends when this is dropped
+            self.requests.send(level).unwrap();
+        }
+        self.cache.get(level)
+    }
+}
+
+// Two sets of buffers, each holding the geometry of a level. The front one is drawn while the
+// geometry of the next level goes into the other, which then becomes the front.
+pub struct MyDoubleBuffer<T> {
+    buffers: [T; 2],
+    levels: [Option<i32>; 2],
+    front: usize,
+}
+
+impl<T> MyDoubleBuffer<T> {
+    pub fn new(buffers: [T; 2]) -> Self {
+        Self {
+            buffers,
+            levels: [None; 2],
+            front: 0,
+        }
+    }
+
+    pub fn front(&self) -> &T {
+        &self.buffers[self.front]
+    }
+
+    pub fn front_level(&self) -> Option<i32> {
+        self.levels[self.front]
+    }
+
+    // Once the tessellator has the geometry of the level, fills the back buffers with it and
+    // swaps them to the front, and returns it. Until then the front stays as it is, and so it
+    // does when it already holds the level.
+    pub fn update<'a>(
+        &mut self,
+        level: i32,
+        tessellator: &'a mut MyTessellator,
+        fill: impl FnOnce(&mut T, &MyGeometry),
+    ) -> Option<&'a MyGeometry> {
+        if self.front_level() == Some(level) {
+            return None;
+        }
+        let geometry = tessellator.geometry(level)?;
+        let back = 1 - self.front;
+        fill(&mut self.buffers[back], geometry);
+        self.levels[back] = Some(level);
+        self.front = back;
+        Some(geometry)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lyon::math::point;
+    use lyon::path::Winding;
+    use std::time::{Duration, Instant};
+
+    fn circle() -> Vec<MyShape> {
+        let mut builder = Path::builder();
+        builder.add_circle(point(0.0, 0.0), 100.0, Winding::Positive);
+        vec![MyShape {
+            path: builder.build(),
+            color: [1.0; 4],
+            stroke: None,
+        }]
+    }
+
+    fn geometry(vertex_count: usize) -> MyGeometry {
+        let mut geometry = MyGeometry::new();
+        let vertex = MyVertex {
+            position: [0.0; 2],
+            color: [1.0; 4],
+        };
+        geometry.vertices = vec![vertex; vertex_count];
+        geometry
+    }
+
+    // From the least to the most recently used.
+    fn levels(cache: &MyCache) -> Vec<i32> {
+        cache.entries.iter().map(|(level, _)| *level).collect()
+    }
+
+    // Calls f until it is Some, as the tessellator takes its time on its thread.
+    fn wait<T>(mut f: impl FnMut() -> Option<T>) -> T {
+        let start = Instant::now();
+        loop {
+            if let Some(value) = f() {
+                return value;
+            }
+            assert!(start.elapsed() < Duration::from_secs(10), "nothing came");
+            std::thread::sleep(Duration::from_millis(1));
+        }
+    }
+
+    #[test]
+    fn cache_hit_and_miss() {
+        let mut cache = MyCache::new(3);
+        assert!(cache.get(0).is_none());
+        cache.insert(0, geometry(1));
+        cache.insert(1, geometry(2));
+        assert_eq!(
+            cache.get(0).map(|geometry| geometry.vertices.len()),
+            Some(1)
+        );
+        assert!(cache.get(2).is_none());
+        // the same level again replaces the geometry
+        cache.insert(0, geometry(3));
+        assert_eq!(
+            cache.get(0).map(|geometry| geometry.vertices.len()),
+            Some(3)
+        );
+        assert_eq!(levels(&cache), [1, 0]);
+    }
+
+    #[test]
+    fn cache_evicts_the_least_recently_used() {
+        let mut cache = MyCache::new(3);
+        for level in 0..3 {
+            cache.insert(level, geometry(0));
+        }
+        // using 0 makes 1 the oldest
+        cache.get(0);
+        cache.insert(3, geometry(0));
+        assert_eq!(levels(&cache), [2, 0, 3]);
+        assert!(cache.get(1).is_none());
+        cache.insert(4, geometry(0));
+        assert_eq!(levels(&cache), [0, 3, 4]);
+    }
+
+    #[test]
+    fn tessellator_misses_then_hits() {
+        let mut tessellator = MyTessellator::new(circle(), 2);
+        // a miss asks the thread for the level, and only once
+        assert!(tessellator.geometry(-2).is_none());
+        assert_eq!(tessellator.pending, Some(-2));
+        let coarse = wait(|| {
+            tessellator
+                .geometry(-2)
+                .map(|geometry| geometry.vertices.len())
+        });
+        assert_eq!(tessellator.pending, None);
+        let fine = wait(|| {
+            tessellator
+                .geometry(-6)
+                .map(|geometry| geometry.vertices.len())
+        });
+        assert!(fine > coarse, "{fine} vertices at -6, {coarse} at -2");
+        // both are cached, so both are there at once
+        assert!(tessellator.geometry(-2).is_some());
+        assert!(tessellator.geometry(-6).is_some());
+        assert_eq!(tessellator.pending, None);
+        // a third level evicts the least recently used
+        wait(|| tessellator.geometry(-4).map(|_| ()));
+        assert_eq!(levels(&tessellator.cache), [-6, -4]);
+    }
+
+    #[test]
+    fn double_buffer_swaps_once_the_geometry_is_ready() {
+        let mut tessellator = MyTessellator::new(circle(), 2);
+        // each buffer holds the number of vertices it was filled with
+        let mut buffers = MyDoubleBuffer::new([0, 0]);
+        let fill = |buffer: &mut usize, geometry: &MyGeometry| *buffer = geometry.vertices.len();
+
+        wait(|| buffers.update(-2, &mut tessellator, fill).map(|_| ()));
+        assert_eq!(buffers.front_level(), Some(-2));
+        let coarse = *buffers.front();
+        assert!(coarse > 0);
+        // the front holds the level, so nothing is filled
+        assert!(buffers.update(-2, &mut tessellator, fill).is_none());
+
+        // while the next level is tessellated, the front is drawn as it is
+        let mut waited = 0;
+        wait(|| {
+            let swapped = buffers.update(-6, &mut tessellator, fill).map(|_| ());
+            if swapped.is_none() {
+                assert_eq!(
+                    (buffers.front_level(), *buffers.front()),
+                    (Some(-2), coarse)
+                );
+                waited += 1;
+            }
+            swapped
+        });
+        assert!(waited > 0, "level -6 was ready before it was asked for");
+        assert_eq!(buffers.front_level(), Some(-6));
+        assert!(*buffers.front() > coarse);
+        // the old front is now the back, to be filled next
+        assert_eq!(buffers.buffers[1 - buffers.front], coarse);
+
+        // a cached level swaps in at once
+        assert!(buffers.update(-2, &mut tessellator, fill).is_some());
+        assert_eq!(
+            (buffers.front_level(), *buffers.front()),
+            (Some(-2), coarse)
+        );
+    }
+}
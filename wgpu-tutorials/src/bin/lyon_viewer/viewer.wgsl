@@ -0,0 +1,33 @@
+// From the paths to clip space: position * scale + offset.
+struct MyTransform {
+    scale: vec2<f32>,
+    offset: vec2<f32>,
+}
+
+@group(0) @binding(0)
+var<uniform> transform: MyTransform;
+
+struct VertexInput {
+    @location(0) position: vec2<f32>,
+    @location(1) color: vec4<f32>,
+}
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) color: vec4<f32>,
+}
+
+@vertex
+fn vs_main(
+    model: VertexInput,
+) -> VertexOutput {
+    var out: VertexOutput;
+    out.clip_position = vec4<f32>(model.position * transform.scale + transform.offset, 0.0, 1.0);
+    out.color = model.color;
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    return in.color;
+}
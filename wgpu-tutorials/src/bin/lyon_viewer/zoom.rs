@@ -0,0 +1,157 @@
+use lyon::math::{point, Box2D, Point, Vector};
+
+// How far, in physical pixels, the flattened curves may be from the true ones.
+pub const MY_SCREEN_TOLERANCE: f32 = 0.25;
+pub const MY_MIN_ZOOM: f32 = 0.01;
+pub const MY_MAX_ZOOM: f32 = 1000.0;
+// tolerances are powers of two between these, so that zooming re-tessellates once per doubling
+pub const MY_MIN_LEVEL: i32 = -16;
+pub const MY_MAX_LEVEL: i32 = 4;
+// the share of the window around the paths when they are fit in it
+const MY_FIT_MARGIN: f32 = 0.1;
+
+// What part of the paths is in the window: the point at the center of the window, and how
+// many physical pixels a unit of the paths takes.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MyView {
+    pub center: Point,
+    pub zoom: f32,
+}
+
+impl MyView {
+    pub fn fit(bounds: &Box2D, size: [f32; 2]) -> Self {
+        let zoom = (size[0] / bounds.width().max(f32::EPSILON))
+            .min(size[1] / bounds.height().max(f32::EPSILON));
+        Self {
+            center: bounds.center(),
+            zoom: (zoom * (1.0 - MY_FIT_MARGIN)).clamp(MY_MIN_ZOOM, MY_MAX_ZOOM),
+        }
+    }
+
+    // The point of the paths under a pixel of the window.
+    pub fn world_at(&self, pixel: Point, size: [f32; 2]) -> Point {
+        self.center + (pixel - point(size[0], size[1]) / 2.0) / self.zoom
+    }
+
+    // Moves the paths along with the cursor.
+    pub fn pan(&mut self, pixels: Vector) {
+        self.center -= pixels / self.zoom;
+    }
+
+    // Zooms by the factor, keeping the point under the pixel where it is.
+    pub fn zoom_at(&mut self, pixel: Point, size: [f32; 2], factor: f32) {
+        let before = self.world_at(pixel, size);
+        self.zoom = (self.zoom * factor).clamp(MY_MIN_ZOOM, MY_MAX_ZOOM);
+        let after = self.world_at(pixel, size);
+        self.center += before - after;
+    }
+
+    // From the paths, y down, to clip space, y up: [scale x, scale y, offset x, offset y].
+    pub fn transform(&self, size: [f32; 2]) -> [f32; 4] {
+        let scale_x = 2.0 * self.zoom / size[0];
+        let scale_y = -2.0 * self.zoom / size[1];
+        [
+            scale_x,
+            scale_y,
+            -self.center.x * scale_x,
+            -self.center.y * scale_y,
+        ]
+    }
+}
+
+// The level of detail for a zoom, the tolerance being 2^level units. It is rounded down so that
+// the curves stay within MY_SCREEN_TOLERANCE of the true ones on screen.
+pub fn tolerance_level(zoom: f32) -> i32 {
+    let tolerance = MY_SCREEN_TOLERANCE / zoom;
+    (tolerance.log2().floor() as i32).clamp(MY_MIN_LEVEL, MY_MAX_LEVEL)
+}
+
+pub fn tolerance(level: i32) -> f32 {
+    2f32.powi(level)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lyon::math::vector;
+
+    const MY_SIZE: [f32; 2] = [800.0, 600.0];
+
+    // How far, in physical pixels, the curves of a level may be from the true ones at a zoom.
+    fn screen_error(zoom: f32) -> f32 {
+        tolerance(tolerance_level(zoom)) * zoom
+    }
+
+    #[test]
+    fn tolerance_stays_within_the_screen_tolerance() {
+        // 1.1 apart, so that every level comes up several times
+        let mut zoom = MY_MIN_ZOOM;
+        let mut last_level = i32::MAX;
+        while zoom <= MY_MAX_ZOOM {
+            let level = tolerance_level(zoom);
+            let error = screen_error(zoom);
+            let clamped = level == MY_MIN_LEVEL || level == MY_MAX_LEVEL;
+            assert!(error <= MY_SCREEN_TOLERANCE, "zoom {zoom}: {error} pixels");
+            // and not finer than it needs to be
+            assert!(
+                error > MY_SCREEN_TOLERANCE / 2.0 || clamped,
+                "zoom {zoom}: {error} pixels"
+            );
+            assert!(level <= last_level, "zoom {zoom}: level {level}");
+            last_level = level;
+            zoom *= 1.1;
+        }
+    }
+
+    #[test]
+    fn levels_at_some_zooms() {
+        // 0.25 units, 1 / 4 of a unit on screen
+        assert_eq!(tolerance_level(1.0), -2);
+        assert_eq!(screen_error(1.0), MY_SCREEN_TOLERANCE);
+        // 0.083 rounds down to 1 / 16
+        assert_eq!(tolerance_level(3.0), -4);
+        assert_eq!(screen_error(3.0), 0.1875);
+        assert_eq!(tolerance_level(1000.0), -12);
+        // far out, the tolerance stops at 16 units, well within the screen tolerance
+        assert_eq!(tolerance_level(MY_MIN_ZOOM), MY_MAX_LEVEL);
+        assert_eq!(tolerance_level(0.001), MY_MAX_LEVEL);
+        assert_eq!(tolerance_level(1e6), MY_MIN_LEVEL);
+    }
+
+    #[test]
+    fn zoom_keeps_the_point_under_the_cursor() {
+        let mut view = MyView::fit(&Box2D::new(point(0.0, 0.0), point(144.0, 144.0)), MY_SIZE);
+        assert_eq!(view.center, point(72.0, 72.0));
+        let pixel = point(100.0, 50.0);
+        let before = view.world_at(pixel, MY_SIZE);
+        view.zoom_at(pixel, MY_SIZE, 3.0);
+        assert!((view.world_at(pixel, MY_SIZE) - before).length() < 1e-3);
+        view.pan(vector(10.0, 0.0));
+        let after = view.world_at(pixel + vector(10.0, 0.0), MY_SIZE);
+        assert!((after - before).length() < 1e-3);
+
+        // zooming stops at the limits
+        view.zoom_at(pixel, MY_SIZE, 1e9);
+        assert_eq!(view.zoom, MY_MAX_ZOOM);
+        view.zoom_at(pixel, MY_SIZE, 1e-12);
+        assert_eq!(view.zoom, MY_MIN_ZOOM);
+    }
+
+    #[test]
+    fn transform_maps_the_window_to_clip_space() {
+        let view = MyView {
+            center: point(10.0, 20.0),
+            zoom: 2.0,
+        };
+        let [scale_x, scale_y, offset_x, offset_y] = view.transform(MY_SIZE);
+        let error = |pixel: Point, expected: [f32; 2]| {
+            let p = view.world_at(pixel, MY_SIZE);
+            let clip = [p.x * scale_x + offset_x, p.y * scale_y + offset_y];
+            (clip[0] - expected[0]).abs() + (clip[1] - expected[1]).abs()
+        };
+        assert!(error(point(400.0, 300.0), [0.0, 0.0]) < 1e-6);
+        // y down in the window, y up in clip space
+        assert!(error(point(0.0, 0.0), [-1.0, 1.0]) < 1e-6);
+        assert!(error(point(800.0, 600.0), [1.0, -1.0]) < 1e-6);
+    }
+}
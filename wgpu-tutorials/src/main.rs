@@ -1,53 +1,78 @@
+use tutorial_common::args::Args;
+use tutorial_common::event_log::winit_0_29 as event_log;
+
+use event_log::Command;
+
 #[pollster::main]
 async fn main() {
-    use winit::dpi::PhysicalSize;
+    use winit::dpi::LogicalSize;
     use winit::event_loop::{ControlFlow, EventLoop};
     use winit::window::WindowBuilder;
 
+    let args = Args::from_env(&["--play", "--record", "--mesh", "--watch"]);
+    let mesh = MeshFile::from_args(&args);
+    if let Some(path) = &args.play {
+        let events = event_log::load(path).unwrap();
+        let played = event_log::play(&events, |event| {
+            let command = event.command();
+            println!("{:?} -> {:?}", event, command);
+            command != Some(Command::Exit)
+        });
+        println!("played {} of {} events", played, events.len());
+        return;
+    }
+
     let event_queue = EventLoop::new().unwrap();
     event_queue.set_control_flow(ControlFlow::Poll);
     let window = &WindowBuilder::new()
         .with_title("wgpu tutorial")
-        .with_inner_size(PhysicalSize::new(480, 320))
+        .with_inner_size(LogicalSize::new(480.0, 320.0))
         .build(&event_queue)
         .unwrap();
-    let mut my_app = MyApp::new(&window).await;
+    let mut my_app = MyApp::new(window, mesh).await;
+    if args.watch {
+        my_app.watch_shader(MY_SHADER_PATH);
+    }
 
-    use winit::event::{ElementState, Event, KeyEvent, WindowEvent};
-    use winit::keyboard::{KeyCode, PhysicalKey};
+    use event_log::{EventRecorder, InputEvent};
+    use winit::event::{Event, WindowEvent};
+    use winit::event_loop::EventLoopWindowTarget;
 
-    _ = event_queue.run(move |event, window_target| match event {
+    let handler = move |event: Event<()>, window_target: &EventLoopWindowTarget<()>| match event {
         Event::AboutToWait => window.request_redraw(),
         Event::WindowEvent {
             window_id,
             ref event,
         } if window_id == window.id() => match event {
-            WindowEvent::CloseRequested
-            | WindowEvent::KeyboardInput {
-                event:
-                    KeyEvent {
-                        state: ElementState::Pressed,
-                        physical_key: PhysicalKey::Code(KeyCode::Escape),
-                        ..
-                    },
-                ..
-            } => window_target.exit(),
-            WindowEvent::Resized(resized) => my_app.resize(*resized),
             WindowEvent::RedrawRequested => {
                 use wgpu::SurfaceError;
 
                 my_app.update();
                 match my_app.render() {
                     Ok(_) => {}
-                    Err(SurfaceError::Lost) => my_app.resize(my_app.size),
+                    Err(SurfaceError::Lost) => my_app.resize(my_app.size, window.scale_factor()),
                     Err(SurfaceError::OutOfMemory) => window_target.exit(),
                     Err(err) => eprintln!("{:?}", err),
                 }
             }
-            _ => {}
+            _ => match InputEvent::from_window_event(event).and_then(|event| event.command()) {
+                Some(Command::Exit) => window_target.exit(),
+                Some(Command::Resize(size)) => my_app.resize(size, window.scale_factor()),
+                Some(Command::Rescale(scale_factor)) => {
+                    my_app.resize(window.inner_size(), scale_factor)
+                }
+                None => {}
+            },
         },
         _ => {}
-    });
+    };
+    _ = if let Some(path) = &args.record {
+        println!("recording to {}", path);
+        let recorder = EventRecorder::create(path).unwrap();
+        event_queue.run(recorder.wrap(handler))
+    } else {
+        event_queue.run(handler)
+    };
 }
 
 struct MyApp<'a> {
@@ -58,16 +83,21 @@ struct MyApp<'a> {
     config: wgpu::SurfaceConfiguration,
     uniform_buffer: wgpu::Buffer,
     uniform_bind_group: wgpu::BindGroup,
+    render_pipeline_layout: wgpu::PipelineLayout,
     render_pipeline: wgpu::RenderPipeline,
+    shader_watcher: Option<ShaderWatcher>,
     vertex_buffer: wgpu::Buffer,
     index_buffer: wgpu::Buffer,
+    num_indices: u32,
     angle_y: f32,
 }
 
+use tutorial_common::mesh_file::MeshFile;
+use tutorial_common::shader_watcher::ShaderWatcher;
 use winit::{dpi::PhysicalSize, window::Window};
 
 impl<'a> MyApp<'a> {
-    async fn new(window: &'a Window) -> Self {
+    async fn new(window: &'a Window, mesh: Option<MeshFile>) -> Self {
         //
         // size, surface, device, queue, config
         //
@@ -165,19 +195,64 @@ impl<'a> MyApp<'a> {
                 bind_group_layouts: &[&uniform_bind_group_layout],
                 push_constant_ranges: &[],
             });
-        let render_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        let render_pipeline =
+            Self::create_render_pipeline(&device, &render_pipeline_layout, &shader, config.format);
+
+        //
+        // vertex_buffer, index_buffer
+        //
+        let (vertices, indices) = match mesh {
+            Some(mesh) => MyVertex::from_mesh(mesh),
+            None => (MY_VERTICES.to_vec(), MY_INDICES.to_vec()),
+        };
+        let vertex_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Vertex Buffer"),
+            usage: wgpu::BufferUsages::VERTEX,
+            contents: bytemuck::cast_slice(&vertices),
+        });
+        let index_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Index Buffer"),
+            usage: wgpu::BufferUsages::INDEX,
+            contents: bytemuck::cast_slice(&indices),
+        });
+
+        Self {
+            size,
+            surface,
+            device,
+            queue,
+            config,
+            uniform_buffer,
+            uniform_bind_group,
+            render_pipeline_layout,
+            render_pipeline,
+            shader_watcher: None,
+            vertex_buffer,
+            index_buffer,
+            num_indices: indices.len() as u32,
+            angle_y: 0.0,
+        }
+    }
+
+    fn create_render_pipeline(
+        device: &wgpu::Device,
+        layout: &wgpu::PipelineLayout,
+        shader: &wgpu::ShaderModule,
+        format: wgpu::TextureFormat,
+    ) -> wgpu::RenderPipeline {
+        device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
             label: Some("Render Pipeline"),
-            layout: Some(&render_pipeline_layout),
+            layout: Some(layout),
             vertex: wgpu::VertexState {
-                module: &shader,
+                module: shader,
                 entry_point: "vs_main",
                 buffers: &[MyVertex::desc()],
             },
             fragment: Some(wgpu::FragmentState {
-                module: &shader,
+                module: shader,
                 entry_point: "fs_main",
                 targets: &[Some(wgpu::ColorTargetState {
-                    format: config.format,
+                    format,
                     blend: Some(wgpu::BlendState::REPLACE),
                     write_mask: wgpu::ColorWrites::ALL,
                 })],
@@ -198,39 +273,84 @@ impl<'a> MyApp<'a> {
                 alpha_to_coverage_enabled: false,
             },
             multiview: None,
-        });
+        })
+    }
+
+    fn watch_shader(&mut self, path: &str) {
+        println!("watching {path}");
+        self.shader_watcher = Some(ShaderWatcher::new(&[path]));
+    }
+
+    fn reload_shader(&mut self) {
+        let Some(shader_watcher) = &self.shader_watcher else {
+            return;
+        };
+        let path = shader_watcher.path(0).display().to_string();
+        let source = match shader_watcher.read(0) {
+            Ok(source) => source,
+            Err(err) => {
+                eprintln!("{path}: {err}");
+                return;
+            }
+        };
 
         //
-        // vertex_buffer, index_buffer
+        // validate with naga first, the diagnostics point at the line and column
         //
-        let vertex_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-            label: Some("Vertex Buffer"),
-            usage: wgpu::BufferUsages::VERTEX,
-            contents: bytemuck::cast_slice(MY_VERTICES),
-        });
-        let index_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-            label: Some("Index Buffer"),
-            usage: wgpu::BufferUsages::INDEX,
-            contents: bytemuck::cast_slice(MY_INDICES),
-        });
+        use naga::valid::{Capabilities, ValidationFlags, Validator};
 
-        Self {
-            size,
-            surface,
-            device,
-            queue,
-            config,
-            uniform_buffer,
-            uniform_bind_group,
-            render_pipeline,
-            vertex_buffer,
-            index_buffer,
-            angle_y: 0.0,
+        let module = match naga::front::wgsl::parse_str(&source) {
+            Ok(module) => module,
+            Err(err) => {
+                eprintln!("{}", err.emit_to_string_with_path(&source, &path));
+                return;
+            }
+        };
+        if let Err(err) =
+            Validator::new(ValidationFlags::all(), Capabilities::all()).validate(&module)
+        {
+            eprintln!("{}", err.emit_to_string_with_path(&source, &path));
+            return;
+        }
+
+        //
+        // errors such as mismatched entry points are only detected by wgpu
+        //
+        self.device.push_error_scope(wgpu::ErrorFilter::Validation);
+        let shader = self
+            .device
+            .create_shader_module(wgpu::ShaderModuleDescriptor {
+                label: Some(&path),
+                source: wgpu::ShaderSource::Wgsl(source.into()),
+            });
+        let render_pipeline = Self::create_render_pipeline(
+            &self.device,
+            &self.render_pipeline_layout,
+            &shader,
+            self.config.format,
+        );
+        match pollster::block_on(self.device.pop_error_scope()) {
+            Some(err) => eprintln!("{path}: {err}"),
+            None => {
+                println!("{path}: reloaded");
+                self.render_pipeline = render_pipeline;
+            }
         }
     }
 
-    fn resize(&mut self, new_size: PhysicalSize<u32>) {
+    // The surface is in physical pixels. The triangle is in clip space, so the scale factor
+    // does not change how it looks.
+    fn resize(&mut self, new_size: PhysicalSize<u32>, scale_factor: f64) {
         if new_size.width > 0 && new_size.height > 0 {
+            let logical_size = new_size.to_logical::<f64>(scale_factor);
+            println!(
+                "surface: {}x{} physical, {}x{} logical, scale factor {}",
+                new_size.width,
+                new_size.height,
+                logical_size.width,
+                logical_size.height,
+                scale_factor
+            );
             self.size = new_size;
             (self.config.width, self.config.height) = (new_size.width, new_size.height);
             self.surface.configure(&self.device, &self.config);
@@ -238,6 +358,12 @@ impl<'a> MyApp<'a> {
     }
 
     fn update(&mut self) {
+        if let Some(shader_watcher) = &mut self.shader_watcher {
+            if shader_watcher.changed() {
+                self.reload_shader();
+            }
+        }
+
         self.angle_y += std::f32::consts::PI / 60.0;
         self.queue.write_buffer(
             &self.uniform_buffer,
@@ -257,7 +383,6 @@ impl<'a> MyApp<'a> {
                 label: Some("Render Encoder"),
             });
         {
-            let num_vertices = MY_INDICES.len() as u32;
             let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
                 label: Some("Render Pass"),
                 color_attachments: &[Some(wgpu::RenderPassColorAttachment {
@@ -278,8 +403,8 @@ impl<'a> MyApp<'a> {
             render_pass.set_pipeline(&self.render_pipeline);
             render_pass.set_bind_group(0, &self.uniform_bind_group, &[]);
             render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
-            render_pass.set_index_buffer(self.index_buffer.slice(..), wgpu::IndexFormat::Uint16);
-            render_pass.draw_indexed(0..num_vertices, 0, 0..1);
+            render_pass.set_index_buffer(self.index_buffer.slice(..), wgpu::IndexFormat::Uint32);
+            render_pass.draw_indexed(0..self.num_indices, 0, 0..1);
         }
         self.queue.submit(std::iter::once(encoder.finish()));
         output.present();
@@ -296,6 +421,18 @@ struct MyVertex {
 }
 
 impl MyVertex {
+    // The triangles of a mesh file, colored by where they are.
+    fn from_mesh(mut mesh: MeshFile) -> (Vec<MyVertex>, Vec<u32>) {
+        mesh.fit_to_clip_space();
+        let vertices = (mesh.positions.iter())
+            .map(|&[x, y]| MyVertex {
+                pos: [x, y, 0.0],
+                col: MeshFile::color([x, y]),
+            })
+            .collect();
+        (vertices, mesh.indices)
+    }
+
     fn desc() -> wgpu::VertexBufferLayout<'static> {
         wgpu::VertexBufferLayout {
             array_stride: std::mem::size_of::<MyVertex>() as wgpu::BufferAddress,
@@ -331,4 +468,6 @@ const MY_VERTICES: &[MyVertex] = &[
     },
 ];
 
-const MY_INDICES: &[u16] = &[0, 1, 2];
+const MY_INDICES: &[u32] = &[0, 1, 2];
+
+const MY_SHADER_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/src/shader.wgsl");
@@ -1,52 +1,155 @@
+mod scene;
+
+use std::collections::HashMap;
+use std::num::NonZeroU32;
 use std::rc::Rc;
+use std::time::Instant;
+use tutorial_common::args::Args;
+use tutorial_common::event_log::winit_0_30 as event_log;
+use winit::event_loop::OwnedDisplayHandle;
 use winit::window::{Window, WindowAttributes};
 
 fn main() {
-    use winit::dpi::PhysicalSize;
+    let args = Args::from_env(&["--play", "--record"]);
+    if let Some(path) = &args.play {
+        let events = event_log::load(path).unwrap();
+        let played = event_log::play(&events, |event| {
+            let command = my_command(event);
+            println!("{:?} -> {:?}", event, command);
+            command != Some(MyCommand::Common(Command::Exit))
+        });
+        println!("played {} of {} events", played, events.len());
+        return;
+    }
+
+    use winit::dpi::LogicalSize;
     use winit::event_loop::{ControlFlow, EventLoop};
 
     let event_queue = EventLoop::new().unwrap();
     event_queue.set_control_flow(ControlFlow::Poll);
     let window_attributes = WindowAttributes::default()
         .with_title("winit tutorial")
-        .with_inner_size(PhysicalSize {
-            width: 480,
-            height: 320,
+        .with_inner_size(LogicalSize {
+            width: 480.0,
+            height: 320.0,
         });
 
     let mut my_app = MyApp {
         window_attributes,
         ..Default::default()
     };
-    _ = event_queue.run_app(&mut my_app);
+    _ = if let Some(path) = &args.record {
+        println!("recording to {}", path);
+        let recorder = event_log::EventRecorder::create(path).unwrap();
+        event_queue.run_app(&mut recorder.wrap(&mut my_app))
+    } else {
+        event_queue.run_app(&mut my_app)
+    };
 }
 
 #[derive(Default)]
 struct MyApp {
     window_attributes: WindowAttributes,
-    window: Option<Rc<Window>>,
+    context: Option<softbuffer::Context<OwnedDisplayHandle>>,
+    windows: HashMap<WindowId, MyWindow>,
+    opened: usize,
+    started: Option<Instant>,
+    time: f32,
+}
+
+// Every window has its own softbuffer surface, so nothing here needs a GPU.
+struct MyWindow {
+    window: Rc<Window>,
+    surface: softbuffer::Surface<OwnedDisplayHandle, Rc<Window>>,
+    size: PhysicalSize<u32>,
+    scale_factor: f64,
+    color: u32,
+}
+
+impl MyApp {
+    fn open_window(&mut self, event_loop: &ActiveEventLoop) {
+        let context = self.context.get_or_insert_with(|| {
+            softbuffer::Context::new(event_loop.owned_display_handle()).unwrap()
+        });
+        let window_attributes = self
+            .window_attributes
+            .clone()
+            .with_title(format!("winit tutorial #{}", self.opened));
+        let window = Rc::new(event_loop.create_window(window_attributes).unwrap());
+        let surface = softbuffer::Surface::new(context, window.clone()).unwrap();
+        let color = MY_WINDOW_COLORS[self.opened % MY_WINDOW_COLORS.len()];
+        self.opened += 1;
+
+        println!("opened {:?}", window.id());
+        let mut my_window = MyWindow {
+            window,
+            surface,
+            size: PhysicalSize::new(0, 0),
+            scale_factor: 1.0,
+            color,
+        };
+        my_window.resize(
+            my_window.window.inner_size(),
+            my_window.window.scale_factor(),
+        );
+        self.windows.insert(my_window.window.id(), my_window);
+    }
+
+    fn update(&mut self) {
+        let started = self.started.get_or_insert_with(Instant::now);
+        self.time = started.elapsed().as_secs_f32();
+    }
+}
+
+impl MyWindow {
+    // The buffer is in physical pixels, and the scene is scaled to keep its logical size.
+    fn resize(&mut self, size: PhysicalSize<u32>, scale_factor: f64) {
+        self.scale_factor = scale_factor;
+        if let (Some(width), Some(height)) =
+            (NonZeroU32::new(size.width), NonZeroU32::new(size.height))
+        {
+            self.surface.resize(width, height).unwrap();
+            self.size = size;
+        }
+    }
+
+    // The buffer has the size of the last resize, which may lag behind inner_size().
+    fn render(&mut self, time: f32) {
+        if self.size.width == 0 || self.size.height == 0 {
+            return;
+        }
+        let mut buffer = self.surface.buffer_mut().unwrap();
+        scene::draw(
+            &mut buffer,
+            self.size.width as usize,
+            self.size.height as usize,
+            self.scale_factor as f32,
+            time,
+            self.color,
+        );
+        buffer.present().unwrap();
+    }
 }
 
+use event_log::{Command, InputEvent};
 use winit::application::ApplicationHandler;
+use winit::dpi::PhysicalSize;
 use winit::event::{DeviceEvent, DeviceId, WindowEvent};
 use winit::event_loop::ActiveEventLoop;
 use winit::window::WindowId;
 
 impl ApplicationHandler for MyApp {
     fn about_to_wait(&mut self, event_loop: &ActiveEventLoop) {
-        if let Some(window) = &self.window {
-            window.request_redraw();
+        self.update();
+        for my_window in self.windows.values() {
+            my_window.window.request_redraw();
         }
     }
 
     fn resumed(&mut self, event_loop: &ActiveEventLoop) {
-        let window = {
-            let window = event_loop
-                .create_window(self.window_attributes.clone())
-                .unwrap();
-            Rc::new(window)
-        };
-        self.window = Some(window);
+        if self.windows.is_empty() {
+            self.open_window(event_loop);
+        }
     }
 
     fn suspended(&mut self, event_loop: &ActiveEventLoop) {
@@ -68,35 +171,111 @@ impl ApplicationHandler for MyApp {
     }
 
     fn window_event(&mut self, event_loop: &ActiveEventLoop, id: WindowId, event: WindowEvent) {
-        use winit::event::{ElementState, KeyEvent};
-        use winit::keyboard::{KeyCode, PhysicalKey};
-
-        match event {
-            WindowEvent::CloseRequested
-            | WindowEvent::KeyboardInput {
-                event:
-                    KeyEvent {
-                        state: ElementState::Pressed,
-                        physical_key: PhysicalKey::Code(KeyCode::Escape),
-                        ..
-                    },
-                ..
-            } => {
+        let Some(my_window) = self.windows.get_mut(&id) else {
+            return;
+        };
+        if let WindowEvent::RedrawRequested = event {
+            my_window.render(self.time);
+            return;
+        }
+        let command = InputEvent::from_window_event(&event).and_then(|event| my_command(&event));
+        match command {
+            Some(MyCommand::Common(Command::Exit)) => {
                 event_loop.exit();
             }
-            WindowEvent::CursorMoved { .. } | WindowEvent::MouseInput { .. } => {
-                println!("{:?}", event);
+            Some(MyCommand::OpenWindow) => {
+                self.open_window(event_loop);
+            }
+            Some(MyCommand::CloseWindow) => {
+                println!("closed {:?}", id);
+                self.windows.remove(&id);
+                if self.windows.is_empty() {
+                    event_loop.exit();
+                }
             }
-            WindowEvent::Resized(_) => {
+            Some(MyCommand::Common(Command::Resize(size))) => {
                 println!("{:?}", event);
+                my_window.resize(size, my_window.window.scale_factor());
             }
-            WindowEvent::RedrawRequested => {
-                // self.update();
-                // self.render();
+            Some(MyCommand::Common(Command::Rescale(scale_factor))) => {
+                println!("{:?}", event);
+                my_window.resize(my_window.window.inner_size(), scale_factor);
             }
-            _ => {
+            None => {
                 println!("{:?}", event);
             }
         }
     }
 }
+
+// What the input does to the app besides what it does to every tutorial,
+// decided apart from the windows so that it can be played back.
+#[derive(Debug, PartialEq)]
+enum MyCommand {
+    OpenWindow,
+    CloseWindow,
+    Common(Command),
+}
+
+fn my_command(event: &InputEvent) -> Option<MyCommand> {
+    use winit::event::ElementState;
+    use winit::keyboard::{KeyCode, PhysicalKey};
+
+    match event {
+        // closes only the window it is sent to
+        InputEvent::CloseRequested => Some(MyCommand::CloseWindow),
+        InputEvent::KeyboardInput {
+            physical_key: PhysicalKey::Code(KeyCode::KeyN),
+            state: ElementState::Pressed,
+            repeat: false,
+            ..
+        } => Some(MyCommand::OpenWindow),
+        _ => event.command().map(MyCommand::Common),
+    }
+}
+
+const MY_WINDOW_COLORS: [u32; 4] = [0xe04040, 0x40c040, 0x4060e0, 0xe0c040];
+
+#[cfg(test)]
+mod tests {
+    use super::{my_command, Command, InputEvent, MyCommand};
+    use winit::event::ElementState;
+    use winit::keyboard::{Key, KeyCode, NativeKey, PhysicalKey};
+
+    fn key(code: KeyCode, state: ElementState, repeat: bool) -> InputEvent {
+        InputEvent::KeyboardInput {
+            physical_key: PhysicalKey::Code(code),
+            logical_key: Key::Unidentified(NativeKey::Unidentified),
+            state,
+            repeat,
+        }
+    }
+
+    #[test]
+    fn n_opens_a_window_once_per_press() {
+        let open = Some(MyCommand::OpenWindow);
+        assert_eq!(
+            my_command(&key(KeyCode::KeyN, ElementState::Pressed, false)),
+            open
+        );
+        assert_eq!(
+            my_command(&key(KeyCode::KeyN, ElementState::Pressed, true)),
+            None
+        );
+        assert_eq!(
+            my_command(&key(KeyCode::KeyN, ElementState::Released, false)),
+            None
+        );
+    }
+
+    #[test]
+    fn closing_a_window_does_not_exit() {
+        let close = Some(MyCommand::CloseWindow);
+        assert_eq!(my_command(&InputEvent::CloseRequested), close);
+        let exit = Some(MyCommand::Common(Command::Exit));
+        assert_eq!(
+            my_command(&key(KeyCode::Escape, ElementState::Pressed, false)),
+            exit
+        );
+    }
+}
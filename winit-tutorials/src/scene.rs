@@ -0,0 +1,100 @@
+// Draws the animated scene into 0RGB pixels, row by row from the top-left corner.
+// It only touches the slice, so it works with any CPU framebuffer.
+// The size is in physical pixels, and the tiles are scale_factor times as large on HiDPI screens.
+pub fn draw(
+    pixels: &mut [u32],
+    width: usize,
+    height: usize,
+    scale_factor: f32,
+    time: f32,
+    color: u32,
+) {
+    // the buffer can lag a resize by a frame; that frame is skipped
+    if pixels.len() != width * height || width == 0 || height == 0 {
+        return;
+    }
+
+    // a ball going around the center of the window
+    let radius = width.min(height) as f32 / 8.0;
+    let center_x = width as f32 / 2.0 + time.cos() * width as f32 / 4.0;
+    let center_y = height as f32 / 2.0 + time.sin() * height as f32 / 4.0;
+    let tile_size = ((MY_TILE_SIZE * scale_factor).round() as usize).max(1);
+    let scroll = (time * MY_SCROLL_SPEED * scale_factor) as usize;
+
+    for (y, row) in pixels.chunks_exact_mut(width).enumerate() {
+        let shade = 255 - (y * 128 / height) as u32;
+        let background = scale(color, shade);
+        for (x, pixel) in row.iter_mut().enumerate() {
+            let (dx, dy) = (x as f32 + 0.5 - center_x, y as f32 + 0.5 - center_y);
+            *pixel = if dx * dx + dy * dy <= radius * radius {
+                0xffffff
+            } else if ((x + scroll) / tile_size + y / tile_size).is_multiple_of(2) {
+                background
+            } else {
+                scale(background, 160)
+            };
+        }
+    }
+}
+
+// Multiplies each channel by factor / 255.
+fn scale(color: u32, factor: u32) -> u32 {
+    let channel = |shift: u32| ((color >> shift & 0xff) * factor / 255) << shift;
+    channel(16) | channel(8) | channel(0)
+}
+
+// in logical pixels
+const MY_TILE_SIZE: f32 = 16.0;
+const MY_SCROLL_SPEED: f32 = 32.0;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COLOR: u32 = 0x4080c0;
+
+    fn scene(width: usize, height: usize, scale_factor: f32) -> Vec<u32> {
+        let mut pixels = vec![0; width * height];
+        draw(&mut pixels, width, height, scale_factor, 0.0, COLOR);
+        pixels
+    }
+
+    #[test]
+    fn tiles_follow_the_scale_factor() {
+        for scale_factor in [1.0, 2.0] {
+            // at time 0 the ball is on the right, away from the first row
+            let pixels = scene(512, 256, scale_factor);
+            let tile_size = (MY_TILE_SIZE * scale_factor) as usize;
+            assert_eq!(pixels[0], COLOR);
+            assert_eq!(pixels[tile_size - 1], COLOR);
+            assert_eq!(pixels[tile_size], scale(COLOR, 160));
+            assert_eq!(pixels[2 * tile_size], COLOR);
+        }
+    }
+
+    #[test]
+    fn same_scene_at_twice_the_pixels() {
+        let ball = |pixels: &[u32]| pixels.iter().filter(|&&pixel| pixel == 0xffffff).count();
+        let (small, large) = (scene(200, 100, 1.0), scene(400, 200, 2.0));
+        let (small_ball, large_ball) = (ball(&small), ball(&large));
+        assert!(small_ball > 0);
+        assert!((large_ball as f32 / (4 * small_ball) as f32 - 1.0).abs() < 0.05);
+
+        // away from the edge of the ball, each pixel is a 2x2 block
+        for y in 0..100 {
+            for x in 0..200 {
+                let pixel = small[y * 200 + x];
+                if pixel != 0xffffff && large[2 * y * 400 + 2 * x] != 0xffffff {
+                    assert_eq!(large[2 * y * 400 + 2 * x], pixel, "at ({x}, {y})");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn skips_a_buffer_of_another_size() {
+        let mut pixels = vec![7; 10];
+        draw(&mut pixels, 4, 4, 1.0, 0.0, COLOR);
+        assert_eq!(pixels, vec![7; 10]);
+    }
+}
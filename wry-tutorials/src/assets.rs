@@ -0,0 +1,256 @@
+use std::borrow::Cow;
+use std::path::{Component, Path, PathBuf};
+
+use wry::http::{header, Response, StatusCode};
+
+// Serves the files under root for the app:// protocol.
+// The webview is not needed here, so the lookup can be tried on its own.
+pub fn respond(root: &Path, request_path: &str) -> Response<Cow<'static, [u8]>> {
+    let result = resolve(root, request_path).and_then(|path| {
+        let body = std::fs::read(&path).map_err(|_| StatusCode::NOT_FOUND)?;
+        Ok((mime_type(&path), body))
+    });
+    match result {
+        Ok((mime_type, body)) => Response::builder()
+            .header(header::CONTENT_TYPE, mime_type)
+            .body(Cow::Owned(body))
+            .unwrap(),
+        Err(status) => {
+            eprintln!("{} {}", status, request_path);
+            Response::builder()
+                .status(status)
+                .header(header::CONTENT_TYPE, "text/plain")
+                .body(Cow::Owned(status.to_string().into_bytes()))
+                .unwrap()
+        }
+    }
+}
+
+// Maps the path of a request, like "/ferris.png", to a file under root.
+// Paths that could leave root are FORBIDDEN, and files that are not there are NOT_FOUND.
+pub fn resolve(root: &Path, request_path: &str) -> Result<PathBuf, StatusCode> {
+    let decoded = percent_decode(request_path).ok_or(StatusCode::BAD_REQUEST)?;
+    let relative = decoded.trim_start_matches('/');
+    if relative.is_empty() {
+        return Err(StatusCode::NOT_FOUND);
+    }
+    // backslashes are separators on Windows, so they are not allowed anywhere
+    if relative.contains('\\') {
+        return Err(StatusCode::FORBIDDEN);
+    }
+    let relative = Path::new(relative);
+    if !relative
+        .components()
+        .all(|component| matches!(component, Component::Normal(_)))
+    {
+        return Err(StatusCode::FORBIDDEN);
+    }
+
+    // symlinks can still point outside, so the final answer comes from the real paths
+    let root = root.canonicalize().map_err(|_| StatusCode::NOT_FOUND)?;
+    let path = root
+        .join(relative)
+        .canonicalize()
+        .map_err(|_| StatusCode::NOT_FOUND)?;
+    if !path.starts_with(&root) {
+        return Err(StatusCode::FORBIDDEN);
+    }
+    if !path.is_file() {
+        return Err(StatusCode::NOT_FOUND);
+    }
+    Ok(path)
+}
+
+pub fn mime_type(path: &Path) -> &'static str {
+    let extension = path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .map(|extension| extension.to_ascii_lowercase());
+    match extension.as_deref() {
+        Some("html") => "text/html",
+        Some("css") => "text/css",
+        Some("js") => "text/javascript",
+        Some("json") => "application/json",
+        Some("png") => "image/png",
+        Some("svg") => "image/svg+xml",
+        Some("ttf") => "font/ttf",
+        Some("otf") => "font/otf",
+        Some("woff2") => "font/woff2",
+        Some("obj") | Some("mtl") | Some("path") | Some("txt") => "text/plain",
+        _ => "application/octet-stream",
+    }
+}
+
+// Decodes %XX escapes. Returns None for broken escapes or bytes that are not UTF-8.
+fn percent_decode(input: &str) -> Option<String> {
+    let mut bytes = Vec::with_capacity(input.len());
+    let mut rest = input.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        if byte == b'%' {
+            let hex = tail.get(..2)?;
+            let hex = std::str::from_utf8(hex).ok()?;
+            bytes.push(u8::from_str_radix(hex, 16).ok()?);
+            rest = &tail[2..];
+        } else {
+            bytes.push(byte);
+            rest = tail;
+        }
+    }
+    String::from_utf8(bytes).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A root with a few files, next to a file that must not be served. Removed when dropped.
+    struct MyFixture {
+        dir: PathBuf,
+    }
+
+    impl MyFixture {
+        fn new(name: &str) -> Self {
+            let dir =
+                std::env::temp_dir().join(format!("wry-tutorials-{}-{}", std::process::id(), name));
+            _ = std::fs::remove_dir_all(&dir);
+            std::fs::create_dir_all(dir.join("root/sub")).unwrap();
+            std::fs::write(dir.join("root/index.html"), "<html></html>").unwrap();
+            std::fs::write(dir.join("root/sub/notes.txt"), "notes").unwrap();
+            std::fs::write(dir.join("secret.txt"), "secret").unwrap();
+            Self { dir }
+        }
+
+        fn root(&self) -> PathBuf {
+            self.dir.join("root")
+        }
+    }
+
+    impl Drop for MyFixture {
+        fn drop(&mut self) {
+            _ = std::fs::remove_dir_all(&self.dir);
+        }
+    }
+
+    #[test]
+    fn resolves_files_under_the_root() {
+        let fixture = MyFixture::new("files");
+        let root = fixture.root().canonicalize().unwrap();
+        assert_eq!(
+            resolve(&fixture.root(), "/index.html"),
+            Ok(root.join("index.html"))
+        );
+        assert_eq!(
+            resolve(&fixture.root(), "/sub/notes.txt"),
+            Ok(root.join("sub/notes.txt"))
+        );
+        assert_eq!(
+            resolve(&fixture.root(), "/sub%2Fnotes%2etxt"),
+            Ok(root.join("sub/notes.txt"))
+        );
+        for request_path in ["/", "/sub", "/missing.html", "/sub/"] {
+            assert_eq!(
+                resolve(&fixture.root(), request_path),
+                Err(StatusCode::NOT_FOUND),
+                "{request_path}"
+            );
+        }
+    }
+
+    #[test]
+    fn forbids_parent_directories() {
+        let fixture = MyFixture::new("parent");
+        for request_path in [
+            "/../secret.txt",
+            "/sub/../../secret.txt",
+            // even when it would end up under the root
+            "/sub/../index.html",
+            "/./index.html",
+        ] {
+            assert_eq!(
+                resolve(&fixture.root(), request_path),
+                Err(StatusCode::FORBIDDEN),
+                "{request_path}"
+            );
+        }
+    }
+
+    #[test]
+    fn forbids_percent_encoded_traversal() {
+        let fixture = MyFixture::new("encoded");
+        for request_path in [
+            "/%2e%2e/secret.txt",
+            "/%2E%2E%2Fsecret.txt",
+            "/sub/%2e%2e%2f%2e%2e%2fsecret.txt",
+            "/..%5csecret.txt",
+            "/sub\\..\\..\\secret.txt",
+        ] {
+            assert_eq!(
+                resolve(&fixture.root(), request_path),
+                Err(StatusCode::FORBIDDEN),
+                "{request_path}"
+            );
+        }
+        for request_path in ["/%", "/%2", "/%zz", "/%ff"] {
+            assert_eq!(
+                resolve(&fixture.root(), request_path),
+                Err(StatusCode::BAD_REQUEST),
+                "{request_path}"
+            );
+        }
+    }
+
+    #[test]
+    fn absolute_paths_stay_under_the_root() {
+        let fixture = MyFixture::new("absolute");
+        let secret = fixture.dir.join("secret.txt");
+        let secret = secret.to_str().unwrap();
+        let encoded = secret.replace('/', "%2F");
+        for request_path in [format!("/{secret}"), format!("//{secret}"), encoded] {
+            // the leading slashes are dropped, and the rest is looked up under the root
+            assert_eq!(
+                resolve(&fixture.root(), &request_path),
+                Err(StatusCode::NOT_FOUND),
+                "{request_path}"
+            );
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn forbids_symlinks_out_of_the_root() {
+        use std::os::unix::fs::symlink;
+
+        let fixture = MyFixture::new("symlinks");
+        let root = fixture.root();
+        symlink(fixture.dir.join("secret.txt"), root.join("secret.txt")).unwrap();
+        symlink(&fixture.dir, root.join("outside")).unwrap();
+        symlink(root.join("index.html"), root.join("home.html")).unwrap();
+
+        for request_path in [
+            "/secret.txt",
+            "/outside/secret.txt",
+            "/outside/root/../secret.txt",
+        ] {
+            assert_eq!(
+                resolve(&root, request_path),
+                Err(StatusCode::FORBIDDEN),
+                "{request_path}"
+            );
+        }
+        // a link that stays inside is served as the file it points to
+        assert_eq!(
+            resolve(&root, "/home.html"),
+            Ok(root.canonicalize().unwrap().join("index.html"))
+        );
+    }
+
+    #[test]
+    fn mime_types_ignore_case() {
+        assert_eq!(mime_type(Path::new("index.HTML")), "text/html");
+        assert_eq!(mime_type(Path::new("ferris.png")), "image/png");
+        assert_eq!(
+            mime_type(Path::new("noextension")),
+            "application/octet-stream"
+        );
+    }
+}
@@ -0,0 +1,215 @@
+use serde::{Deserialize, Serialize};
+
+// The bouncing box from pixels-tutorials, moving in a fixed playfield.
+// It knows nothing about the webview; the page only sees the GameState.
+pub struct MyGame {
+    box_x: f64,
+    box_y: f64,
+    velocity_x: f64,
+    velocity_y: f64,
+    time: f64,
+    bounces: u64,
+    paused: bool,
+    speed: f64,
+    frame: u64,
+}
+
+// What the HUD draws. frame goes up whenever anything in it changes.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GameState {
+    pub frame: u64,
+    pub width: f64,
+    pub height: f64,
+    pub box_x: f64,
+    pub box_y: f64,
+    pub box_size: f64,
+    pub time: f64,
+    pub bounces: u64,
+    pub paused: bool,
+    pub speed: f64,
+}
+
+impl MyGame {
+    pub fn new() -> Self {
+        Self {
+            box_x: 16.0,
+            box_y: 16.0,
+            velocity_x: MY_BOX_VELOCITY,
+            velocity_y: MY_BOX_VELOCITY,
+            time: 0.0,
+            bounces: 0,
+            paused: false,
+            speed: 1.0,
+            frame: 0,
+        }
+    }
+
+    // dt is in seconds of real time, and is scaled by the speed.
+    pub fn update(&mut self, dt: f64) {
+        if self.paused {
+            return;
+        }
+        let dt = dt * self.speed;
+        self.time += dt;
+        self.box_x += self.velocity_x * dt;
+        self.box_y += self.velocity_y * dt;
+
+        // A box past a wall is put back against it and turned around. This is not an exact
+        // reflection, as the distance past the wall is dropped, but a long step cannot push
+        // the box out.
+        let (max_x, max_y) = (MY_WIDTH - MY_BOX_SIZE, MY_HEIGHT - MY_BOX_SIZE);
+        if self.box_x < 0.0 || self.box_x > max_x {
+            self.box_x = self.box_x.clamp(0.0, max_x);
+            self.velocity_x = -self.velocity_x;
+            self.bounces += 1;
+        }
+        if self.box_y < 0.0 || self.box_y > max_y {
+            self.box_y = self.box_y.clamp(0.0, max_y);
+            self.velocity_y = -self.velocity_y;
+            self.bounces += 1;
+        }
+        self.frame += 1;
+    }
+
+    pub fn set_paused(&mut self, paused: bool) {
+        self.paused = paused;
+        self.frame += 1;
+    }
+
+    pub fn set_speed(&mut self, speed: f64) -> Result<(), String> {
+        if !(speed > 0.0 && speed <= MY_MAX_SPEED) {
+            return Err(format!("speed must be in (0, {MY_MAX_SPEED}], got {speed}"));
+        }
+        self.speed = speed;
+        self.frame += 1;
+        Ok(())
+    }
+
+    // Starts over, but keeps the speed and the pause, which are the player's settings.
+    pub fn reset(&mut self) {
+        *self = Self {
+            paused: self.paused,
+            speed: self.speed,
+            frame: self.frame + 1,
+            ..Self::new()
+        };
+    }
+
+    pub fn frame(&self) -> u64 {
+        self.frame
+    }
+
+    pub fn state(&self) -> GameState {
+        GameState {
+            frame: self.frame,
+            width: MY_WIDTH,
+            height: MY_HEIGHT,
+            box_x: self.box_x,
+            box_y: self.box_y,
+            box_size: MY_BOX_SIZE,
+            time: self.time,
+            bounces: self.bounces,
+            paused: self.paused,
+            speed: self.speed,
+        }
+    }
+}
+
+// in CSS pixels, and per second
+const MY_WIDTH: f64 = 320.0;
+const MY_HEIGHT: f64 = 180.0;
+const MY_BOX_SIZE: f64 = 32.0;
+const MY_BOX_VELOCITY: f64 = 90.0;
+const MY_MAX_SPEED: f64 = 8.0;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn moves_with_the_speed() {
+        let mut game = MyGame::new();
+        game.update(0.1);
+        let state = game.state();
+        assert_eq!((state.box_x, state.box_y), (25.0, 25.0));
+        assert_eq!(state.frame, 1);
+
+        let mut fast = MyGame::new();
+        fast.set_speed(2.0).unwrap();
+        fast.update(0.05);
+        assert_eq!(fast.state().box_x, state.box_x);
+        assert_eq!(fast.state().time, state.time);
+    }
+
+    #[test]
+    fn pausing_stops_the_box_but_not_the_frames() {
+        let mut game = MyGame::new();
+        game.set_paused(true);
+        let paused = game.state();
+        assert!(paused.paused);
+        assert_eq!(paused.frame, 1);
+
+        game.update(0.1);
+        assert_eq!(game.state(), paused);
+
+        game.set_paused(false);
+        game.update(0.1);
+        assert_eq!(game.state().frame, 3);
+        assert!(game.state().box_x > paused.box_x);
+    }
+
+    #[test]
+    fn bad_speeds_change_nothing() {
+        let mut game = MyGame::new();
+        for speed in [0.0, -1.0, MY_MAX_SPEED + 0.5, f64::NAN, f64::INFINITY] {
+            assert!(game.set_speed(speed).is_err(), "{speed}");
+        }
+        assert_eq!(game.state(), MyGame::new().state());
+        game.set_speed(MY_MAX_SPEED).unwrap();
+        assert_eq!(game.state().speed, MY_MAX_SPEED);
+    }
+
+    #[test]
+    fn reset_keeps_the_settings() {
+        let mut game = MyGame::new();
+        game.set_speed(3.0).unwrap();
+        game.update(1.0);
+        game.set_paused(true);
+        let before = game.state();
+        assert!(before.bounces > 0);
+
+        game.reset();
+        let state = game.state();
+        assert_eq!(
+            state,
+            GameState {
+                frame: before.frame + 1,
+                paused: true,
+                speed: 3.0,
+                ..MyGame::new().state()
+            }
+        );
+    }
+
+    #[test]
+    fn the_box_stays_inside_and_turns_at_the_walls() {
+        let mut game = MyGame::new();
+        // far past the right and the bottom walls in one step
+        game.update(10.0);
+        let state = game.state();
+        assert_eq!(state.box_x, MY_WIDTH - MY_BOX_SIZE);
+        assert_eq!(state.box_y, MY_HEIGHT - MY_BOX_SIZE);
+        assert_eq!(state.bounces, 2);
+        assert_eq!(
+            (game.velocity_x, game.velocity_y),
+            (-MY_BOX_VELOCITY, -MY_BOX_VELOCITY)
+        );
+
+        for _ in 0..1000 {
+            game.update(0.05);
+            let state = game.state();
+            assert!((0.0..=MY_WIDTH - MY_BOX_SIZE).contains(&state.box_x));
+            assert!((0.0..=MY_HEIGHT - MY_BOX_SIZE).contains(&state.box_y));
+        }
+    }
+}
@@ -0,0 +1,98 @@
+use std::time::{Duration, Instant};
+
+use crate::game::{GameState, MyGame};
+
+// Sends the newest game state to the page, but only once the page has drawn the last one.
+// The frames in between are dropped instead of piling up in the script queue, so a slow or
+// hidden page never falls behind. A frame that is never acknowledged, say after a reload,
+// is given up on after MY_HUD_TIMEOUT, and the newest state is sent again.
+#[derive(Default)]
+pub struct MyHud {
+    sent_frame: Option<u64>,
+    waiting_since: Option<Instant>,
+}
+
+impl MyHud {
+    // The state to send to the page at `now`, if it is time to send one.
+    pub fn next(&mut self, game: &MyGame, now: Instant) -> Option<GameState> {
+        match self.waiting_since {
+            Some(waiting_since) if now.duration_since(waiting_since) < MY_HUD_TIMEOUT => {
+                return None;
+            }
+            None if self.sent_frame == Some(game.frame()) => return None,
+            _ => {}
+        }
+        self.sent_frame = Some(game.frame());
+        self.waiting_since = Some(now);
+        Some(game.state())
+    }
+
+    // The page says that it has drawn a frame. Acknowledgements of older frames are ignored.
+    pub fn drawn(&mut self, frame: u64) {
+        if self.sent_frame == Some(frame) {
+            self.waiting_since = None;
+        }
+    }
+}
+
+const MY_HUD_TIMEOUT: Duration = Duration::from_secs(1);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MY_STEP: f64 = 1.0 / 60.0;
+
+    fn frame(state: Option<GameState>) -> Option<u64> {
+        state.map(|state| state.frame)
+    }
+
+    #[test]
+    fn frames_are_dropped_while_an_ack_is_outstanding() {
+        let start = Instant::now();
+        let mut game = MyGame::new();
+        let mut hud = MyHud::default();
+        assert_eq!(frame(hud.next(&game, start)), Some(0));
+
+        for i in 1..=3 {
+            game.update(MY_STEP);
+            let now = start + Duration::from_millis(16 * i);
+            assert_eq!(hud.next(&game, now), None, "frame {}", game.frame());
+        }
+
+        // an ack for a frame that was never sent does not count
+        hud.drawn(2);
+        assert_eq!(hud.next(&game, start + Duration::from_millis(60)), None);
+
+        // frames 1 and 2 are skipped
+        hud.drawn(0);
+        let now = start + Duration::from_millis(70);
+        assert_eq!(frame(hud.next(&game, now)), Some(3));
+
+        // nothing new after the ack, so nothing is sent
+        hud.drawn(3);
+        assert_eq!(hud.next(&game, now), None);
+        game.set_paused(true);
+        assert_eq!(frame(hud.next(&game, now)), Some(4));
+    }
+
+    #[test]
+    fn the_message_is_sent_again_after_the_timeout() {
+        let start = Instant::now();
+        let mut game = MyGame::new();
+        let mut hud = MyHud::default();
+        assert_eq!(frame(hud.next(&game, start)), Some(0));
+
+        // the page reloaded, so frame 0 is never acknowledged
+        let before = start + MY_HUD_TIMEOUT - Duration::from_millis(1);
+        assert_eq!(hud.next(&game, before), None);
+        // the same frame again, as nothing has changed
+        let after = start + MY_HUD_TIMEOUT;
+        assert_eq!(frame(hud.next(&game, after)), Some(0));
+
+        // and the timeout starts over from the new message
+        game.update(MY_STEP);
+        assert_eq!(hud.next(&game, after + MY_HUD_TIMEOUT / 2), None);
+        assert_eq!(frame(hud.next(&game, after + MY_HUD_TIMEOUT)), Some(1));
+    }
+}
@@ -0,0 +1,176 @@
+use serde::{Deserialize, Serialize};
+
+use crate::game::GameState;
+
+// A message from the page, posted with window.ipc.postMessage(JSON.stringify(message)).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum PageMessage {
+    Request(IpcRequest),
+    // The page has drawn the HUD for this frame, and is ready for the next one.
+    HudDrawn { frame: u64 },
+}
+
+// The id is chosen by the page, and comes back with the response.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IpcRequest {
+    pub id: u64,
+    pub command: Command,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Command {
+    SetTitle { title: String },
+    // in logical pixels
+    SetSize { width: f64, height: f64 },
+    ResetCounter,
+    GetWindowInfo,
+    SetPaused { paused: bool },
+    SetSpeed { speed: f64 },
+    ResetGame,
+}
+
+// A message to the page, delivered by calling MY_RECEIVER with it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum IpcMessage {
+    Response { id: u64, result: Reply },
+    Error { id: u64, message: String },
+    Counter { value: u64 },
+    Hud { state: GameState },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Reply {
+    Done,
+    WindowInfo {
+        title: String,
+        width: f64,
+        height: f64,
+        scale_factor: f64,
+    },
+}
+
+impl IpcMessage {
+    pub fn from_result(id: u64, result: Result<Reply, String>) -> Self {
+        match result {
+            Ok(result) => IpcMessage::Response { id, result },
+            Err(message) => IpcMessage::Error { id, message },
+        }
+    }
+}
+
+pub fn decode(body: &str) -> Result<PageMessage, String> {
+    serde_json::from_str(body).map_err(|err| format!("invalid IPC message {body:?}: {err}"))
+}
+
+// Returns the script for evaluate_script. JSON is a valid JavaScript expression, so the
+// payload can be passed as it is, without another round of quoting.
+pub fn to_script(message: &IpcMessage) -> String {
+    let payload = serde_json::to_string(message).unwrap();
+    format!("{MY_RECEIVER}({payload});")
+}
+
+const MY_RECEIVER: &str = "window.rustBridge.receive";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::MyGame;
+
+    #[test]
+    fn decodes_the_messages_of_the_page() {
+        let body = r#"{"type":"request","id":7,"command":{"type":"set_title","title":"Hi"}}"#;
+        let expected = PageMessage::Request(IpcRequest {
+            id: 7,
+            command: Command::SetTitle {
+                title: "Hi".to_owned(),
+            },
+        });
+        assert_eq!(decode(body), Ok(expected));
+
+        let body =
+            r#"{"type":"request","id":8,"command":{"type":"set_size","width":640,"height":480.5}}"#;
+        let Ok(PageMessage::Request(request)) = decode(body) else {
+            panic!("{body}");
+        };
+        assert_eq!(
+            request.command,
+            Command::SetSize {
+                width: 640.0,
+                height: 480.5
+            }
+        );
+
+        let body = r#"{"type":"request","id":9,"command":{"type":"reset_counter"}}"#;
+        let Ok(PageMessage::Request(request)) = decode(body) else {
+            panic!("{body}");
+        };
+        assert_eq!(request.command, Command::ResetCounter);
+
+        let body = r#"{"type":"hud_drawn","frame":42}"#;
+        assert_eq!(decode(body), Ok(PageMessage::HudDrawn { frame: 42 }));
+    }
+
+    #[test]
+    fn rejects_what_it_cannot_decode() {
+        for body in [
+            "",
+            "not json",
+            r#"{"type":"shutdown"}"#,
+            r#"{"type":"request","command":{"type":"reset_game"}}"#,
+            r#"{"type":"request","id":1,"command":{"type":"set_speed"}}"#,
+            r#"{"type":"request","id":-1,"command":{"type":"reset_game"}}"#,
+        ] {
+            let err = decode(body).unwrap_err();
+            // the message names the body, so that a broken page can be found from the log
+            assert!(err.contains(&format!("{body:?}")), "{err}");
+        }
+    }
+
+    #[test]
+    fn script_passes_the_payload_as_it_is() {
+        let message = IpcMessage::Counter { value: 3 };
+        assert_eq!(
+            to_script(&message),
+            r#"window.rustBridge.receive({"type":"counter","value":3});"#
+        );
+    }
+
+    #[test]
+    fn script_escapes_what_could_end_the_payload() {
+        // quotes, backslashes, line breaks, and a script tag that means nothing outside HTML;
+        // JSON is a subset of JavaScript, with U+2028 and U+2029 too since ES2019
+        let message = "say \"hi\" \\ then\nbreak\r\t</script>\u{2028}\u{2029}\u{0}";
+        let message = IpcMessage::Error {
+            id: 1,
+            message: message.to_owned(),
+        };
+        let script = to_script(&message);
+        assert!(!script.contains('\n') && !script.contains('\r') && !script.contains('\0'));
+        assert!(
+            script.contains(r#"say \"hi\" \\ then\nbreak\r\t"#),
+            "{script}"
+        );
+
+        let payload = script
+            .strip_prefix("window.rustBridge.receive(")
+            .and_then(|script| script.strip_suffix(");"))
+            .unwrap();
+        let decoded: IpcMessage = serde_json::from_str(payload).unwrap();
+        assert_eq!(decoded, message);
+    }
+
+    #[test]
+    fn script_carries_the_hud() {
+        let message = IpcMessage::Hud {
+            state: MyGame::new().state(),
+        };
+        let script = to_script(&message);
+        let payload = &script[MY_RECEIVER.len() + 1..script.len() - 2];
+        let decoded: IpcMessage = serde_json::from_str(payload).unwrap();
+        assert_eq!(decoded, message);
+    }
+}
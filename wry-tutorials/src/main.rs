@@ -1,12 +1,25 @@
+mod assets;
+mod game;
+mod hud;
+mod ipc;
+
 use std::rc::Rc;
+use std::time::{Duration, Instant};
 use tao::event::{ElementState, KeyEvent, MouseButton, RawKeyEvent};
 
+// Events sent to the event loop from outside of it.
+#[derive(Debug)]
+enum MyEvent {
+    Ipc(String),
+}
+
 fn main() {
     use tao::dpi::PhysicalSize;
-    use tao::event_loop::EventLoop;
+    use tao::event_loop::EventLoopBuilder;
     use tao::window::WindowBuilder;
 
-    let event_queue = EventLoop::new();
+    let event_queue = EventLoopBuilder::<MyEvent>::with_user_event().build();
+    let proxy = event_queue.create_proxy();
 
     let window = WindowBuilder::new()
         .with_title("wry tutorial")
@@ -14,10 +27,34 @@ fn main() {
         .build(&event_queue)
         .unwrap();
 
+    use std::borrow::Cow;
+    use std::path::PathBuf;
+    use wry::http::{header, Response};
     use wry::WebViewBuilder;
 
+    let asset_dir = if let Ok(manifest_dir) = std::env::var("CARGO_MANIFEST_DIR") {
+        let mut path = PathBuf::from(manifest_dir);
+        path.push("../assets");
+        path
+    } else {
+        PathBuf::from("../assets")
+    };
+
+    // the handler runs outside of the event loop, so the messages are handled there instead
     let webview = WebViewBuilder::new()
-        .with_html(MY_HTML_SOURCE)
+        .with_custom_protocol("app".to_owned(), move |_, request| {
+            match request.uri().path() {
+                "/" | "/index.html" => Response::builder()
+                    .header(header::CONTENT_TYPE, "text/html")
+                    .body(Cow::Borrowed(MY_HTML_SOURCE.as_bytes()))
+                    .unwrap(),
+                path => assets::respond(&asset_dir, path),
+            }
+        })
+        .with_url(MY_APP_URL)
+        .with_ipc_handler(move |request| {
+            _ = proxy.send_event(MyEvent::Ipc(request.into_body()));
+        })
         // .with_url("https://tauri.app/")
         .build(&window)
         .unwrap();
@@ -25,17 +62,63 @@ fn main() {
     let mut window = Some(window);
     let mut webview = Some(webview);
     let mut visible = true;
+    let mut counter = 0;
+    let mut game = game::MyGame::new();
+    let mut hud = hud::MyHud::default();
+    let mut last_update = Instant::now();
+    let mut next_tick = last_update + MY_COUNTER_INTERVAL;
+    let mut next_frame = last_update + MY_FRAME_INTERVAL;
     event_queue.run(move |event, _, control_flow| {
         use tao::event::{DeviceEvent, Event, StartCause, WindowEvent};
         use tao::event_loop::ControlFlow;
         use tao::keyboard::KeyCode;
 
-        *control_flow = ControlFlow::Wait;
+        *control_flow = ControlFlow::WaitUntil(next_tick.min(next_frame));
 
         match event {
             Event::NewEvents(StartCause::Init) => {
                 println!("Wry has started!");
             }
+            Event::NewEvents(StartCause::ResumeTimeReached { .. }) => {
+                let now = Instant::now();
+                if now >= next_tick {
+                    counter += 1;
+                    next_tick += MY_COUNTER_INTERVAL;
+                    if let Some(webview) = &webview {
+                        push(webview, &ipc::IpcMessage::Counter { value: counter });
+                    }
+                }
+                if now >= next_frame {
+                    // after a stall, the game slows down instead of jumping ahead
+                    let dt = now.duration_since(last_update).min(MY_MAX_FRAME_TIME);
+                    game.update(dt.as_secs_f64());
+                    last_update = now;
+                    next_frame = now + MY_FRAME_INTERVAL;
+                    if let Some(webview) = &webview {
+                        push_hud(webview, &mut hud, &game);
+                    }
+                }
+                *control_flow = ControlFlow::WaitUntil(next_tick.min(next_frame));
+            }
+            Event::UserEvent(MyEvent::Ipc(body)) => {
+                let (Some(window), Some(webview)) = (&window, &webview) else {
+                    return;
+                };
+                match ipc::decode(&body) {
+                    Ok(ipc::PageMessage::Request(request)) => {
+                        println!("{:?}", request);
+                        let result =
+                            handle_command(request.command, window, &mut counter, &mut game);
+                        push(webview, &ipc::IpcMessage::from_result(request.id, result));
+                        push(webview, &ipc::IpcMessage::Counter { value: counter });
+                        push_hud(webview, &mut hud, &game);
+                    }
+                    Ok(ipc::PageMessage::HudDrawn { frame }) => {
+                        hud.drawn(frame);
+                    }
+                    Err(err) => eprintln!("{err}"),
+                }
+            }
             Event::WindowEvent {
                 event: WindowEvent::CloseRequested,
                 ..
@@ -101,10 +184,74 @@ fn main() {
     });
 }
 
-static MY_HTML_SOURCE: &str = "
-<html>
-    <body>
-        <h1>It works!</h1>
-    </body>
-</html>
-";
+fn handle_command(
+    command: ipc::Command,
+    window: &tao::window::Window,
+    counter: &mut u64,
+    game: &mut game::MyGame,
+) -> Result<ipc::Reply, String> {
+    use ipc::{Command, Reply};
+    use tao::dpi::LogicalSize;
+
+    match command {
+        Command::SetTitle { title } => {
+            window.set_title(&title);
+            Ok(Reply::Done)
+        }
+        Command::SetSize { width, height } => {
+            if !(width >= 1.0 && height >= 1.0) {
+                return Err(format!("invalid size {width}x{height}"));
+            }
+            window.set_inner_size(LogicalSize::new(width, height));
+            Ok(Reply::Done)
+        }
+        Command::ResetCounter => {
+            *counter = 0;
+            Ok(Reply::Done)
+        }
+        Command::GetWindowInfo => {
+            let scale_factor = window.scale_factor();
+            let size = window.inner_size().to_logical::<f64>(scale_factor);
+            Ok(Reply::WindowInfo {
+                title: window.title(),
+                width: size.width,
+                height: size.height,
+                scale_factor,
+            })
+        }
+        Command::SetPaused { paused } => {
+            game.set_paused(paused);
+            Ok(Reply::Done)
+        }
+        Command::SetSpeed { speed } => {
+            game.set_speed(speed)?;
+            Ok(Reply::Done)
+        }
+        Command::ResetGame => {
+            game.reset();
+            Ok(Reply::Done)
+        }
+    }
+}
+
+fn push_hud(webview: &wry::WebView, hud: &mut hud::MyHud, game: &game::MyGame) {
+    if let Some(state) = hud.next(game, Instant::now()) {
+        push(webview, &ipc::IpcMessage::Hud { state });
+    }
+}
+
+fn push(webview: &wry::WebView, message: &ipc::IpcMessage) {
+    if let Err(err) = webview.evaluate_script(&ipc::to_script(message)) {
+        eprintln!("{err}");
+    }
+}
+
+static MY_HTML_SOURCE: &str = include_str!("index.html");
+// Windows and Android serve custom protocols as http://<scheme>.localhost instead
+#[cfg(any(target_os = "windows", target_os = "android"))]
+const MY_APP_URL: &str = "http://app.localhost/";
+#[cfg(not(any(target_os = "windows", target_os = "android")))]
+const MY_APP_URL: &str = "app://localhost/";
+const MY_COUNTER_INTERVAL: Duration = Duration::from_secs(1);
+const MY_FRAME_INTERVAL: Duration = Duration::from_micros(16_667);
+const MY_MAX_FRAME_TIME: Duration = Duration::from_millis(100);